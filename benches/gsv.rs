@@ -0,0 +1,140 @@
+//! GSV throughput benchmark
+//!
+//! Replays the GSV sentences of a canned 10-second, 10 Hz capture from a four-constellation
+//! receiver (`data/gsv_capture.nmea`). Throughput is reported per sentence, so the time per
+//! element is the cost of one GSV sentence.
+//!
+//! - `gsv_storage/hashmap` stores the satellites of each sentence the way the parser did before
+//!   `SatelliteMap`: a `HashMap<u16, SatelliteInfo>` per system plus the PRNs in update order.
+//!   This is the baseline.
+//! - `gsv_storage/satellite_map` stores them in a `SatelliteMap`. The satellites are parsed
+//!   before the measurement, so the two runs differ in the storage alone.
+//! - `gsv/feed_nmea_at` feeds whole sentences through the parser, including framing, checksum,
+//!   statistics and dispatch.
+//!
+//! Run with `cargo bench --bench gsv`.
+//!
+//! Measured on a noisy single-core VM, per sentence, in the same session:
+//! - `gsv_storage`: ~185 ns with the `HashMap`, ~110 ns with `SatelliteMap`, about 1.7x faster
+//! - `gsv/feed_nmea_at`: ~1.5 us before `SatelliteMap` (the parent of the commit that added it,
+//!   run with this bench's `gsv` group), ~0.9 us after, 1.4x to 2x faster between runs
+//!
+//! Neither reaches the 3x target. Parsing the fields costs as much as storing them, and
+//! framing, checksum, statistics and dispatch take about half of a whole sentence, whatever
+//! the GSV handler does.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use nema_parser::gnss_multignss_parser::{GnssData, SatelliteInfo, SatelliteMap};
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+
+/// Canned 10-second capture at 10 Hz with GPS, GLONASS, Galileo and BeiDou in view.
+const CAPTURE: &str = include_str!("data/gsv_capture.nmea");
+
+/// Returns the GSV sentences of the capture.
+fn gsv_sentences() -> Vec<&'static str> {
+    CAPTURE.lines().filter(|line| line.get(3..6) == Some("GSV")).collect()
+}
+
+/// Returns the capture's system index for a talker ID.
+fn system_index(fields: &[&str]) -> usize {
+    match fields[0].get(0..2) {
+        Some("GP") => 0,
+        Some("GL") => 1,
+        Some("GA") => 2,
+        _ => 3,
+    }
+}
+
+/// Parses the satellite blocks of a GSV sentence split into fields.
+fn satellites<'a>(fields: &'a [&str]) -> impl Iterator<Item = SatelliteInfo> + 'a {
+    fields.get(4..).unwrap_or_default().chunks_exact(4).filter_map(|block| {
+        Some(SatelliteInfo {
+            prn: block[0].parse().ok()?,
+            elevation: block[1].parse().ok(),
+            azimuth: block[2].parse().ok(),
+            snr: block[3].parse().ok(),
+            signals: Vec::new(),
+            used: false,
+        })
+    })
+}
+
+/// Satellite storage of a system before `SatelliteMap`.
+#[derive(Default)]
+struct HashMapStorage {
+    /// Satellites by PRN
+    satellites: HashMap<u16, SatelliteInfo>,
+    /// PRNs from the least to the most recently updated, for eviction
+    order: Vec<u16>,
+}
+
+impl HashMapStorage {
+    /// Stores a satellite, as `GnssSystemData::record_satellite` did.
+    fn record(&mut self, info: SatelliteInfo) {
+        let prn = info.prn;
+        self.order.retain(|&known| known != prn);
+        self.order.push(prn);
+        self.satellites.insert(prn, info);
+    }
+}
+
+fn bench_gsv_storage(c: &mut Criterion) {
+    // Framing and field parsing are measured by the `gsv` group; parse the satellites once here
+    let sentences: Vec<(usize, Vec<SatelliteInfo>)> = gsv_sentences().iter()
+        .map(|sentence| {
+            let fields: Vec<&str> = sentence[1..].split('*').next().unwrap_or_default().split(',').collect();
+            (system_index(&fields), satellites(&fields).collect())
+        })
+        .collect();
+    let mut group = c.benchmark_group("gsv_storage");
+    group.throughput(Throughput::Elements(sentences.len() as u64));
+
+    group.bench_function("hashmap", |b| {
+        b.iter(|| {
+            let mut systems: [HashMapStorage; 4] = Default::default();
+            for (system, satellites) in &sentences {
+                for info in black_box(satellites) {
+                    systems[*system].record(info.clone());
+                }
+            }
+            black_box(systems)
+        })
+    });
+
+    group.bench_function("satellite_map", |b| {
+        b.iter(|| {
+            let mut systems: [SatelliteMap; 4] = Default::default();
+            for (system, satellites) in &sentences {
+                for info in black_box(satellites) {
+                    systems[*system].insert(info.prn, info.clone());
+                }
+            }
+            black_box(systems)
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_feed_gsv(c: &mut Criterion) {
+    let sentences = gsv_sentences();
+    let mut group = c.benchmark_group("gsv");
+    group.throughput(Throughput::Elements(sentences.len() as u64));
+
+    // A fixed receive time keeps the host clock out of the measurement
+    group.bench_function("feed_nmea_at", |b| {
+        b.iter(|| {
+            let mut gnss = GnssData::new();
+            for sentence in &sentences {
+                let _ = black_box(gnss.feed_nmea_at(black_box(sentence), UNIX_EPOCH));
+            }
+            black_box(gnss)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_gsv_storage, bench_feed_gsv);
+criterion_main!(benches);
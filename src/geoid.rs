@@ -0,0 +1,159 @@
+//! Coarse Geoid Model
+//!
+//! This module provides a global geoid undulation grid with bilinear interpolation, used to convert
+//! between heights above mean sea level (MSL) and heights above the WGS84 ellipsoid when an NMEA
+//! sentence does not carry the geoid separation itself.
+//!
+//! The grid layout follows the EGM96 `ww15mgh.grd` convention: rows run from 90°N down to 90°S and
+//! columns from 0°E eastward, with a fixed spacing in degrees. The crate does not ship grid data
+//! yet, so there is no built-in EGM96 lookup; load a coarse EGM96-style grid (e.g. resampled to 5°
+//! or 15°) and pass its values to [`GeoidGrid::new`].
+//!
+//! The accuracy of the lookup is that of the grid loaded. Interpolation reproduces the grid nodes
+//! exactly, but a coarse grid smooths out geoid features narrower than its spacing, which can
+//! amount to tens of meters. Compare the grid against the full model at points of interest
+//! before relying on it.
+//!
+//! This module is only available with the `geoid` cargo feature.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nema_parser::geoid::GeoidGrid;
+//! // A 90° grid: 3 rows (90N, 0, 90S) by 4 columns (0E, 90E, 180E, 270E)
+//! let grid = GeoidGrid::new(90.0, vec![
+//!     14.0, 14.0, 14.0, 14.0,
+//!     17.0, -60.0, 21.0, -4.0,
+//!     -30.0, -30.0, -30.0, -30.0,
+//! ]).unwrap();
+//! let ellipsoidal_height = 545.4 + grid.geoid_separation_at(0.0, 90.0);
+//! assert!((ellipsoidal_height - 485.4).abs() < 1e-9);
+//! ```
+
+/// Global geoid undulation grid in meters.
+#[derive(Debug, Clone)]
+pub struct GeoidGrid {
+    /// Grid spacing in degrees
+    spacing_deg: f64,
+    /// Number of latitude rows (90°N to 90°S inclusive)
+    rows: usize,
+    /// Number of longitude columns (0°E eastward, wrapping at 360°)
+    cols: usize,
+    /// Geoid separations in meters, row-major from the north-west corner
+    values: Vec<f32>,
+}
+
+impl GeoidGrid {
+    /// Creates a grid from row-major geoid separations.
+    ///
+    /// # Arguments
+    /// * `spacing_deg` - Grid spacing in degrees; must evenly divide 180
+    /// * `values` - Separations in meters, `(180 / spacing + 1) * (360 / spacing)` entries
+    ///
+    /// # Returns
+    /// * `Option<GeoidGrid>` - The grid, or None if the spacing or value count is inconsistent
+    pub fn new(spacing_deg: f64, values: Vec<f32>) -> Option<Self> {
+        if !spacing_deg.is_finite() || spacing_deg <= 0.0 || (180.0 / spacing_deg).fract() != 0.0 {
+            return None;
+        }
+        let rows = (180.0 / spacing_deg) as usize + 1;
+        let cols = (360.0 / spacing_deg) as usize;
+        if values.len() != rows * cols {
+            return None;
+        }
+        Some(Self {
+            spacing_deg,
+            rows,
+            cols,
+            values,
+        })
+    }
+
+    /// Gets the grid spacing in degrees.
+    pub fn spacing_deg(&self) -> f64 {
+        self.spacing_deg
+    }
+
+    /// Returns the geoid separation (ellipsoid height minus MSL height) at a position.
+    ///
+    /// Values are bilinearly interpolated between the four surrounding grid nodes. Longitude wraps
+    /// around the antimeridian and latitude is clamped to ±90°.
+    ///
+    /// # Arguments
+    /// * `lat` - Latitude in decimal degrees
+    /// * `lon` - Longitude in decimal degrees (either -180..180 or 0..360)
+    ///
+    /// # Returns
+    /// * `f64` - Geoid separation in meters
+    pub fn geoid_separation_at(&self, lat: f64, lon: f64) -> f64 {
+        let row_pos = ((90.0 - lat.clamp(-90.0, 90.0)) / self.spacing_deg).min((self.rows - 1) as f64);
+        let col_pos = lon.rem_euclid(360.0) / self.spacing_deg;
+
+        let row0 = row_pos.floor() as usize;
+        let row1 = (row0 + 1).min(self.rows - 1);
+        let col0 = (col_pos.floor() as usize) % self.cols;
+        let col1 = (col0 + 1) % self.cols;
+        let row_frac = row_pos - row_pos.floor();
+        let col_frac = col_pos - col_pos.floor();
+
+        let north = self.value(row0, col0) * (1.0 - col_frac) + self.value(row0, col1) * col_frac;
+        let south = self.value(row1, col0) * (1.0 - col_frac) + self.value(row1, col1) * col_frac;
+        north * (1.0 - row_frac) + south * row_frac
+    }
+
+    /// Gets the separation stored at a grid node.
+    fn value(&self, row: usize, col: usize) -> f64 {
+        f64::from(self.values[row * self.cols + col])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a grid whose values are a known function of the node coordinates.
+    fn synthetic_grid(spacing: f64, f: impl Fn(f64, f64) -> f64) -> GeoidGrid {
+        let rows = (180.0 / spacing) as usize + 1;
+        let cols = (360.0 / spacing) as usize;
+        let mut values = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let lat = 90.0 - row as f64 * spacing;
+                let lon = col as f64 * spacing;
+                values.push(f(lat, lon) as f32);
+            }
+        }
+        GeoidGrid::new(spacing, values).unwrap()
+    }
+
+    #[test]
+    fn test_grid_nodes_are_exact() {
+        let grid = synthetic_grid(15.0, |lat, lon| lat * 0.5 - lon * 0.1);
+        assert!((grid.geoid_separation_at(45.0, 30.0) - 19.5).abs() < 1e-4);
+        assert!((grid.geoid_separation_at(-60.0, 105.0) - -40.5).abs() < 1e-4);
+        assert!((grid.geoid_separation_at(90.0, 0.0) - 45.0).abs() < 1e-4);
+        assert!((grid.geoid_separation_at(-90.0, 0.0) - -45.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_bilinear_interpolation_between_nodes() {
+        let grid = synthetic_grid(15.0, |lat, lon| lat + 2.0 * lon);
+        // A plane is reproduced exactly by bilinear interpolation
+        assert!((grid.geoid_separation_at(48.1173, 11.5166667) - (48.1173 + 2.0 * 11.5166667)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_longitude_wraps_around_antimeridian() {
+        let grid = synthetic_grid(90.0, |_, lon| if lon == 0.0 { 10.0 } else if lon == 270.0 { 30.0 } else { 0.0 });
+        // Halfway between 270°E and 360°E (= 0°E) on the equator
+        assert!((grid.geoid_separation_at(0.0, -45.0) - 20.0).abs() < 1e-9);
+        assert!((grid.geoid_separation_at(0.0, 315.0) - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_grid_rejected() {
+        assert!(GeoidGrid::new(15.0, vec![0.0; 10]).is_none());
+        assert!(GeoidGrid::new(7.0, vec![0.0; 10]).is_none());
+        assert!(GeoidGrid::new(0.0, Vec::new()).is_none());
+    }
+}
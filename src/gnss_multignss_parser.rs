@@ -1,1162 +1,11401 @@
 //! GNSS Multi-System NMEA Parser
 //!
 //! This module provides data structures and logic for parsing NMEA sentences from multiple GNSS systems
-//! (GPS, GLONASS, GALILEO, BEIDOU). It supports extracting satellite information, position, DOP values,
+//! (GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC). It supports extracting satellite information, position, DOP values,
 //! and fusing positions from different systems for improved accuracy.
 //!
 //! # Altitude Reference
 //!
-//! All altitude values (e.g., `altitude` fields in structs and fused position) are **above mean sea level** (MSL),
-//! as reported by NMEA GGA sentences.
+//! Altitudes reported by NMEA GGA and GNS sentences are **above mean sea level** (MSL). A system's
+//! `altitude_datum` records whether its altitude is MSL or ellipsoidal, and ellipsoidal altitudes are
+//! converted to MSL with the geoid separation before fusion. The fused position carries both
+//! `altitude_msl` and, when the geoid separation is known from GGA, GNS or a geoid model (with the
+//! `geoid` feature), `altitude_ellipsoidal`.
+//!
+//! A system whose GSA reports a 2D fix only carries an altitude assumed by the receiver. It is left
+//! out of the fused altitude when another system measured one, and `altitude_source` tells the two
+//! cases apart.
 //!
 //! # Features
-//! - Parses GGA, RMC, VTG, GSA, GSV, and GLL sentences for supported systems
+//! - Parses GGA, RMC, VTG, GSA, GSV, GLL, GNS, GST, GBS, DTM, ZDA, TXT, HDT, and THS sentences for supported systems
 //! - Tracks satellite info and usage per system
 //! - Calculates fused position using weighted averaging and advanced filtering
 //! - Provides utility functions for latitude/longitude parsing
 //!
+//! # Talker Attribution
+//!
+//! GGA, RMC, VTG, GSA, GLL and GST are accepted from the "GN" talker and from any constellation
+//! talker (GP, GL, GA, GB/BD, GQ/QZ, GI). Data from a constellation talker is attributed to that
+//! system alone: a GPGGA sets the GPS position and a GPGSA the GPS satellites and DOPs. "GN"
+//! positions apply to every system with satellites in view. A GNGSA is attributed to the system
+//! named by its NMEA 4.10 system ID, satellites and DOPs alike; without one, its PRNs are
+//! attributed by PRN range. GNS is accepted from "GN" only, GSV from constellation talkers only.
+//!
+//! # Sentence Precedence
+//!
+//! A receiver often reports the same quantity in several sentences of one epoch, with small
+//! differences in the last digits. Within an epoch, a sentence only overwrites a quantity if it
+//! has the same or higher precedence than the sentence that last set it:
+//! - Position (combined and per-system latitude, longitude and altitude): GGA > GNS > RMC > GLL
+//! - Speed and track over ground: RMC > VTG
+//!
+//! An epoch starts whenever a GGA, GNS, RMC or GLL carries a UTC time different from the current
+//! one, which clears the claims. Sentence types missing from a precedence list rank below all
+//! listed ones. The lists are configurable with `set_position_precedence` and `set_speed_precedence`.
+//!
+//! # DOP Sources
+//!
+//! Fusion weighs each system by its DOPs, taken from the first available source:
+//! 1. A GSA whose satellites all belong to that system
+//! 2. A GSA spanning several systems (`combined_pdop`, `combined_hdop`, `combined_vdop`)
+//! 3. The HDOP in GGA or GNS field 8 (`gga_hdop`), multiplied by `GGA_HDOP_PENALTY`
+//!
+//! GGA carries no PDOP or VDOP, so with only GGA available, the inflated HDOP also stands in for
+//! the PDOP, and the VDOP falls back to its usual estimate.
+//!
+//! A DGPS fix (GGA quality 2) whose corrections are older than `DGPS_MAX_AGE_S` is little better
+//! than an autonomous one, so fusion multiplies the system accuracies by `STALE_DGPS_PENALTY`.
+//!
+//! A GNGST sentence of the epoch being fused overrides the result: its latitude and longitude
+//! sigmas become the fused `instantaneous_accuracy`, and its altitude sigma the `altitude_accuracy`.
+//! Constellation GST sentences (GPGST, GLGST, ...) set per-system sigmas instead, which the
+//! advanced fusion combines when every contributing system has reported them.
+//!
+//! # Horizontal Accuracy
+//!
+//! A fused position carries two horizontal accuracies:
+//! - `instantaneous_accuracy` describes the epoch alone, from its DOPs or GST sigmas. It reacts
+//!   at once when the sky view degrades, so use it for geofencing and other decisions that
+//!   must not trust a fix better than it is right now.
+//! - `estimated_accuracy` is the uncertainty of the filtered position. With a filter set by
+//!   `set_position_filter`, successive fixes are blended: the uncertainty shrinks while the
+//!   receiver stays put and grows back during outages. Use it for maps and recorded tracks.
+//!
+//! Without a filter (the default), both are equal.
+//!
+//! # Number Format
+//!
+//! Numbers are parsed and formatted with Rust's own routines, which never consult the system
+//! locale: numeric fields must use '.' as the decimal separator, and every number the crate
+//! emits (KML, DMS strings, serialized data) uses '.' as well. A latitude split in two by a
+//! comma decimal separator is detected, and the sentence is dropped with a
+//! `DataWarning::CommaDecimalSeparator` rather than misread. Streams rewritten by locale-aware
+//! middleware can be read with `set_decimal_separator`.
+//!
+//! # Time
+//!
+//! Time-dependent behavior reads a `Clock`: `SystemClock` unless another is set with
+//! `GnssData::set_clock`. Under a `ManualClock` it is fully deterministic.
+//! - `feed_nmea`, `feed_nmea_with_fields` and `feed_bytes` stamp each sentence with the
+//!   clock's time; `feed_nmea_at` takes the receive time instead.
+//! - Times kept while feeding and fusing are receive times: GSV cycles and satellite locks,
+//!   talker and system updates, held altitudes, the best-fix staleness, the position filter
+//!   and the timing offset. The time to first fix runs from the acquisition start, set from
+//!   the clock by `new`, `reset` and `set_clock`.
+//! - `expire_stale` measures ages against the clock, so a silent receiver expires its systems
+//!   without further sentences.
+//! - `gsv_cycle_age` and `lock_duration` take the reference time, which `GnssData::now` reads
+//!   from the clock.
+//!
+//! # Reading Data
+//!
+//! `GnssData` and `GnssSystemData` are read through accessor methods: `latitude()`, `hdop()`,
+//! `system("GPS")`, `satellites()`, `fused_position()` and so on. Their fields are still public
+//! so that existing code keeps compiling, but they are hidden from the documentation, deprecated
+//! and will become private in a future release, letting the internal representation change
+//! without breaking downstream crates. Each deprecation warning names the accessor to move to.
+//!
 //! # Usage
 //!
 //! ```rust
 //! use nema_parser::gnss_multignss_parser::GnssData;
 //! let mut gnss = GnssData::new();
-//! gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+//! gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
 //! gnss.calculate_fused_position();
-//! if let Some(fused) = &gnss.fused_position {
+//! if let Some(fused) = gnss.fused_position() {
 //!     println!("Fused position: {}, {}", fused.latitude, fused.longitude);
-//!     println!("Altitude above mean sea level: {}", fused.altitude);
+//!     println!("Altitude above mean sea level: {}", fused.altitude_msl);
 //! }
 //! ```
 
-use std::collections::HashMap;
+// The public fields are deprecated for downstream crates only; this module owns them
+#![allow(deprecated)]
 
-/// Information about a single satellite, including PRN, elevation, azimuth, and SNR.
-#[derive(Debug, Default, Clone)]
-pub struct SatelliteInfo {
-    /// Pseudo-Random Noise number (satellite identifier)
-    pub prn: u16,
-    /// Elevation angle in degrees
-    pub elevation: Option<u8>,
-    /// Azimuth angle in degrees
-    pub azimuth: Option<u16>,
-    /// Signal-to-noise ratio in dBHz
-    pub snr: Option<u8>,
-}
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// Data for a single GNSS system (GPS, GLONASS, GALILEO, BEIDOU).
-#[derive(Debug, Default, Clone)]
-pub struct GnssSystemData {
-    /// List of satellites used for position fix
-    pub satellites_used: Vec<u16>,
-    /// Information about all tracked satellites
-    pub satellites_info: HashMap<u16, SatelliteInfo>,
-    /// Position Dilution of Precision
-    pub pdop: Option<f64>,
-    /// Horizontal Dilution of Precision
-    pub hdop: Option<f64>,
-    /// Vertical Dilution of Precision
-    pub vdop: Option<f64>,
-    /// Latitude in decimal degrees
-    pub latitude: Option<f64>,
-    /// Longitude in decimal degrees
-    pub longitude: Option<f64>,
-    /// Altitude above mean sea level in meters
-    pub altitude: Option<f64>,
-    /// Fixed accuracy in meters (best-case for this system)
-    pub fixed_accuracy: f64,
-    /// Module accuracy in meters (dynamically updated)
-    pub accuracy: f64,
+/// Point in time read from a `Clock`.
+pub type Timestamp = SystemTime;
+
+/// Source of the current time for a `GnssData`.
+///
+/// Sentences fed without an explicit receive time are stamped with the clock, and every age
+/// the parser measures is taken between such receive times, falling back to the clock before
+/// any sentence was fed. See "Time" in the module docs.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> Timestamp;
 }
 
+/// Clock reading the host system time (the default).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
 
-/// Main GNSS data structure holding parsed information and fused position.
-#[derive(Debug, Default, Clone)]
-pub struct GnssData {
-    /// UTC time from NMEA sentence
-    pub time: Option<String>,
-    /// Latitude in decimal degrees
-    pub latitude: Option<f64>,
-    /// Longitude in decimal degrees
-    pub longitude: Option<f64>,
-    /// Fix quality indicator
-    pub fix_quality: Option<u8>,
-    /// Number of satellites used for fix
-    pub num_satellites: Option<u8>,
-    /// Altitude above mean sea level in meters
-    pub altitude: Option<f64>,
-    /// Speed over ground in knots
-    pub speed_knots: Option<f64>,
-    /// Track angle in degrees
-    pub track_angle: Option<f64>,
-    /// Date in DDMMYY format
-    pub date: Option<String>,
-    /// Data for each GNSS system
-    pub systems: HashMap<&'static str, GnssSystemData>,
-    /// Fused position calculated from available systems
-    pub fused_position: Option<FusedPosition>,
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        SystemTime::now()
+    }
 }
 
-/// Fused position result from multiple GNSS systems.
+/// Clock that only moves when told to, for tests and hosts with their own time source.
+///
+/// Clones share the same time, so a handle kept after `GnssData::set_clock` still drives it.
+///
+/// # Example
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use nema_parser::gnss_multignss_parser::{Clock, ManualClock};
+/// let clock = ManualClock::new(UNIX_EPOCH);
+/// let handle = clock.clone();
+/// handle.advance(Duration::from_secs(5));
+/// assert_eq!(clock.now(), UNIX_EPOCH + Duration::from_secs(5));
+/// ```
 #[derive(Debug, Clone)]
-pub struct FusedPosition {
-    /// Fused latitude in decimal degrees
-    pub latitude: f64,
-    /// Fused longitude in decimal degrees
-    pub longitude: f64,
-    /// Fused altitude above mean sea level in meters
-    pub altitude: f64,
-    /// Estimated horizontal accuracy in meters
-    pub estimated_accuracy: f64,
-    /// Estimated altitude accuracy in meters
-    pub altitude_accuracy: f64,
-    /// List of contributing GNSS systems
-    pub contributing_systems: Vec<String>,
+pub struct ManualClock {
+    now: Arc<Mutex<Timestamp>>,
 }
 
-impl GnssData {
-    /// Creates a new `GnssData` instance with all supported GNSS systems initialized.
-    ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    ///
-    /// let gnss = GnssData::new();
-    /// ```
-    pub fn new() -> Self {
-        let mut systems = HashMap::new();
-
-        // Initialize GPS with 2.0m fixed accuracy
-        let gps_system = GnssSystemData { fixed_accuracy: 2.0, accuracy: 2.0, ..Default::default() };
-        systems.insert("GPS", gps_system);
-
-        // Initialize GLONASS with 4.0m fixed accuracy
-        let glonass_system = GnssSystemData { fixed_accuracy: 4.0, accuracy: 4.0, ..Default::default() };
-        systems.insert("GLONASS", glonass_system);
-
-        // Initialize GALILEO with 3.0m fixed accuracy
-        let galileo_system = GnssSystemData { fixed_accuracy: 3.0, accuracy: 3.0, ..Default::default() };
-        systems.insert("GALILEO", galileo_system);
+impl ManualClock {
+    /// Creates a clock standing at `start`.
+    pub fn new(start: Timestamp) -> Self {
+        ManualClock { now: Arc::new(Mutex::new(start)) }
+    }
 
-        // Initialize BEIDOU with 3.0m fixed accuracy
-        let beidou_system = GnssSystemData { fixed_accuracy: 3.0, accuracy: 3.0, ..Default::default() };
-        systems.insert("BEIDOU", beidou_system);
+    /// Sets the time.
+    pub fn set(&self, now: Timestamp) {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = now;
+    }
 
-        Self {
-            systems,
-            ..Default::default()
-        }
+    /// Moves the time forward.
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) += by;
     }
+}
 
-    /// Parses and updates GNSS data from a GGA sentence.
-    fn update_gga(&mut self, parts: &[&str]) {
-        let lat = parse_lat(parts.get(2), parts.get(3));
-        let lon = parse_lon(parts.get(4), parts.get(5));
-        let altitude = parts.get(9).and_then(|s| s.parse().ok());
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
 
-        self.time = parts.get(1).map(|s| s.to_string());
-        self.latitude = lat;
-        self.longitude = lon;
-        self.fix_quality = parts.get(6).and_then(|s| s.parse().ok());
-        self.num_satellites = parts.get(7).and_then(|s| s.parse().ok());
-        self.altitude = altitude;
+/// Clock handle shared by a `GnssData` and its clones.
+#[derive(Debug, Clone)]
+struct SharedClock(Arc<dyn Clock>);
 
-        // Update coordinates and altitude for all systems that have satellites
-        for (_, system_data) in self.systems.iter_mut() {
-            if !system_data.satellites_info.is_empty() {
-                system_data.latitude = lat;
-                system_data.longitude = lon;
-                system_data.altitude = altitude;
-            } else {
-                system_data.latitude = None;
-                system_data.longitude = None;
-                system_data.altitude = None;
-            }
-        }
+impl Default for SharedClock {
+    fn default() -> Self {
+        SharedClock(Arc::new(SystemClock))
     }
+}
 
-    /// Parses and updates GNSS data from an RMC sentence.
-    fn update_rmc(&mut self, parts: &[&str]) {
-        let lat = parse_lat(parts.get(3), parts.get(4));
-        let lon = parse_lon(parts.get(5), parts.get(6));
-        self.time = parts.get(1).map(|s| s.to_string());
-        self.latitude = lat;
-        self.longitude = lon;
-        self.speed_knots = parts.get(7).and_then(|s| s.parse().ok());
-        self.track_angle = parts.get(8).and_then(|s| s.parse().ok());
-        self.date = parts.get(9).map(|s| s.to_string());
+/// GNSS constellations tracked by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GnssSystem {
+    /// United States Global Positioning System
+    Gps,
+    /// Russian GLONASS
+    Glonass,
+    /// European Galileo
+    Galileo,
+    /// Chinese BeiDou
+    Beidou,
+    /// Japanese Quasi-Zenith Satellite System
+    Qzss,
+    /// Indian NavIC (IRNSS)
+    Navic,
+}
 
-        // Update coordinates for all systems that have satellites
-        for (_, system_data) in self.systems.iter_mut() {
-            if !system_data.satellites_info.is_empty() {
-                system_data.latitude = lat;
-                system_data.longitude = lon;
-            } else {
-                system_data.latitude = None;
-                system_data.longitude = None;
-            }
+impl GnssSystem {
+    /// All supported systems in canonical order.
+    pub const ALL: [GnssSystem; 6] = [
+        GnssSystem::Gps,
+        GnssSystem::Glonass,
+        GnssSystem::Galileo,
+        GnssSystem::Beidou,
+        GnssSystem::Qzss,
+        GnssSystem::Navic,
+    ];
+
+    /// Returns the system name used as key in `GnssData::systems`.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssSystem;
+    /// assert_eq!(GnssSystem::Glonass.name(), "GLONASS");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            GnssSystem::Gps => "GPS",
+            GnssSystem::Glonass => "GLONASS",
+            GnssSystem::Galileo => "GALILEO",
+            GnssSystem::Beidou => "BEIDOU",
+            GnssSystem::Qzss => "QZSS",
+            GnssSystem::Navic => "NAVIC",
         }
     }
 
-    /// Parses and updates GNSS data from a VTG sentence.
-    fn update_vtg(&mut self, parts: &[&str]) {
-        self.speed_knots = parts.get(5).and_then(|s| s.parse().ok());
+    /// Looks up a system by its name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC").
+    ///
+    /// # Returns
+    /// * `Option<GnssSystem>` - The matching system, or None if the name is unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|system| system.name() == name)
     }
 
-    /// Parses and updates GNSS system data from a GSA sentence.
-    fn update_gsa(&mut self, parts: &[&str]) {
-        let mut gps_ids = Vec::new();
-        for i in 3..=14 {
-            if let Some(Ok(prn)) = parts.get(i).map(|s| s.parse()) {
-                gps_ids.push(prn);
-            }
+    /// Looks up the system reporting under a two-letter NMEA talker ID ("GP", "GL", "GA", "GB" or
+    /// "BD", "GQ" or "QZ", "GI").
+    ///
+    /// # Returns
+    /// * `Option<GnssSystem>` - The matching system, or None for combined or unknown talkers
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssSystem;
+    /// assert_eq!(GnssSystem::from_talker("GA"), Some(GnssSystem::Galileo));
+    /// assert_eq!(GnssSystem::from_talker("GB"), Some(GnssSystem::Beidou));
+    /// assert_eq!(GnssSystem::from_talker("GN"), None);
+    /// ```
+    pub fn from_talker(talker: &str) -> Option<Self> {
+        match talker {
+            "GP" => Some(GnssSystem::Gps),
+            "GL" => Some(GnssSystem::Glonass),
+            "GA" => Some(GnssSystem::Galileo),
+            // NMEA 4.11 assigns "GB"; earlier receivers use "BD"
+            "BD" | "GB" => Some(GnssSystem::Beidou),
+            // NMEA 4.11 assigns "GQ"; earlier receivers use "QZ"
+            "QZ" | "GQ" => Some(GnssSystem::Qzss),
+            "GI" => Some(GnssSystem::Navic),
+            _ => None,
         }
+    }
 
-        // Find DOP values by searching for the first three non-empty numeric fields after satellites
-        let mut dop_values = Vec::new();
-        for i in 15..parts.len() {
-            if let Some(part) = parts.get(i) {
-                let clean_part = part.split('*').next().unwrap_or(part);
-                if !clean_part.is_empty() {
-                    if let Ok(value) = clean_part.parse::<f64>() {
-                        dop_values.push(value);
-                        if dop_values.len() == 3 {
-                            break;
-                        }
-                    }
-                }
-            }
+    /// Looks up a system by the NMEA 4.10 system ID carried in GSA and GSV sentences
+    /// (1 GPS, 2 GLONASS, 3 Galileo, 4 BeiDou, 5 QZSS, 6 NavIC).
+    ///
+    /// # Returns
+    /// * `Option<GnssSystem>` - The matching system, or None for unknown IDs
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssSystem;
+    /// assert_eq!(GnssSystem::from_system_id(3), Some(GnssSystem::Galileo));
+    /// assert_eq!(GnssSystem::from_system_id(0), None);
+    /// ```
+    pub fn from_system_id(id: u8) -> Option<Self> {
+        match id {
+            1..=6 => Some(Self::ALL[usize::from(id) - 1]),
+            _ => None,
         }
+    }
+}
 
-        let pdop = dop_values.first().copied();
-        let hdop = dop_values.get(1).copied();
-        let vdop = dop_values.get(2).copied();
+/// Position fix quality, as reported by the GGA fix quality field or a GNS mode indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FixQuality {
+    /// No fix or invalid fix (GGA 0, mode 'N')
+    Invalid,
+    /// Autonomous GNSS fix (GGA 1, mode 'A')
+    Gps,
+    /// Differential GNSS fix (GGA 2, mode 'D')
+    Dgps,
+    /// Precise positioning service fix (GGA 3, mode 'P')
+    Pps,
+    /// Real-time kinematic fix with fixed integers (GGA 4, mode 'R')
+    RtkFixed,
+    /// Real-time kinematic fix with float integers (GGA 5, mode 'F')
+    RtkFloat,
+    /// Estimated (dead reckoning) position (GGA 6, mode 'E')
+    DeadReckoning,
+    /// Manually entered position (GGA 7, mode 'M')
+    Manual,
+    /// Simulated position (GGA 8, mode 'S')
+    Simulation,
+    /// SBAS-corrected fix such as WAAS or EGNOS (GGA 9)
+    Sbas,
+}
 
-        let mut updated_systems = Vec::new();
-        for prn in &gps_ids {
-            match prn {
-                1..=32 => {
-                    self.systems.get_mut("GPS").unwrap().satellites_used.push(*prn as u16);
-                    if !updated_systems.contains(&"GPS") {
-                        updated_systems.push("GPS");
-                    }
-                },
-                65..=96 => {
-                    self.systems.get_mut("GLONASS").unwrap().satellites_used.push(*prn as u16);
-                    if !updated_systems.contains(&"GLONASS") {
-                        updated_systems.push("GLONASS");
-                    }
-                },
-                201..=236 => {
-                    self.systems.get_mut("BEIDOU").unwrap().satellites_used.push(*prn as u16);
-                    if !updated_systems.contains(&"BEIDOU") {
-                        updated_systems.push("BEIDOU");
-                    }
-                },
-                301..=336 => {
-                    self.systems.get_mut("GALILEO").unwrap().satellites_used.push(*prn as u16);
-                    if !updated_systems.contains(&"GALILEO") {
-                        updated_systems.push("GALILEO");
-                    }
-                },
-                _ => {}
-            }
-        }
-        // Only update error values for systems that received satellites in this GSA sentence
-        for sys_name in updated_systems {
-            if let Some(sys) = self.systems.get_mut(sys_name) {
-                sys.pdop = pdop;
-                sys.hdop = hdop;
-                sys.vdop = vdop;
-                // Dynamically update accuracy using HDOP and fixed_accuracy
-                if let Some(hdop_val) = hdop {
-                    sys.accuracy = hdop_val * sys.fixed_accuracy;
-                } else {
-                    sys.accuracy = sys.fixed_accuracy;
-                }
-            }
+impl From<u8> for FixQuality {
+    /// Converts a GGA fix quality value; unknown values map to `Invalid`.
+    fn from(value: u8) -> Self {
+        match value {
+            1 => FixQuality::Gps,
+            2 => FixQuality::Dgps,
+            3 => FixQuality::Pps,
+            4 => FixQuality::RtkFixed,
+            5 => FixQuality::RtkFloat,
+            6 => FixQuality::DeadReckoning,
+            7 => FixQuality::Manual,
+            8 => FixQuality::Simulation,
+            9 => FixQuality::Sbas,
+            _ => FixQuality::Invalid,
         }
     }
+}
 
-    /// Parses and updates satellite information from a GSV sentence for the specified system.
-    fn update_gsv(&mut self, parts: &[&str], system: &str) {
-        if let Some(sys_data) = self.systems.get_mut(system) {
-            let mut i = 4;
-            while i + 3 < parts.len() {
-                if let Some(Ok(prn)) = parts.get(i).map(|s| s.parse()) {
-                    let elevation = parts.get(i + 1).and_then(|s| s.parse().ok());
-                    let azimuth = parts.get(i + 2).and_then(|s| s.parse().ok());
-                    let snr = parts.get(i + 3).and_then(|s| s.trim_end_matches('*').parse().ok());
-                    sys_data.satellites_info.insert(
-                        prn,
-                        SatelliteInfo {
-                            prn,
-                            elevation,
-                            azimuth,
-                            snr,
-                        },
-                    );
-                }
-                i += 4;
-            }
+impl FixQuality {
+    /// Converts an NMEA mode indicator character (GNS, RMC, GLL, VTG).
+    ///
+    /// # Returns
+    /// * `Option<FixQuality>` - The matching quality, or None for an unknown character
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::FixQuality;
+    /// assert_eq!(FixQuality::from_mode_indicator('R'), Some(FixQuality::RtkFixed));
+    /// assert_eq!(FixQuality::from_mode_indicator('N'), Some(FixQuality::Invalid));
+    /// ```
+    pub fn from_mode_indicator(mode: char) -> Option<Self> {
+        match mode {
+            'N' => Some(FixQuality::Invalid),
+            'A' => Some(FixQuality::Gps),
+            'D' => Some(FixQuality::Dgps),
+            'P' => Some(FixQuality::Pps),
+            'R' => Some(FixQuality::RtkFixed),
+            'F' => Some(FixQuality::RtkFloat),
+            'E' => Some(FixQuality::DeadReckoning),
+            'M' => Some(FixQuality::Manual),
+            'S' => Some(FixQuality::Simulation),
+            _ => None,
         }
     }
 
-    /// Parses and updates latitude/longitude from a GLL sentence for the specified system.
-    fn update_gll(&mut self, parts: &[&str], system: &str) {
-        let lat = parse_lat(parts.get(1), parts.get(2));
-        let lon = parse_lon(parts.get(3), parts.get(4));
-        self.latitude = lat;
-        self.longitude = lon;
-        if let Some(sys) = self.systems.get_mut(system) {
-            if !sys.satellites_info.is_empty() {
-                sys.latitude = lat;
-                sys.longitude = lon;
-            } else {
-                sys.latitude = None;
-                sys.longitude = None;
-            }
+    /// Ranks qualities from least to most trustworthy for picking the best one.
+    fn rank(&self) -> u8 {
+        match self {
+            FixQuality::Invalid => 0,
+            FixQuality::Simulation => 1,
+            FixQuality::Manual => 2,
+            FixQuality::DeadReckoning => 3,
+            FixQuality::Gps => 4,
+            FixQuality::Pps => 5,
+            FixQuality::Sbas => 6,
+            FixQuality::Dgps => 7,
+            FixQuality::RtkFloat => 8,
+            FixQuality::RtkFixed => 9,
         }
     }
+}
 
-    /// Feeds a single NMEA sentence to the parser and updates internal state.
+/// Fix type reported by a GSA sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FixType {
+    /// No fix (GSA 1)
+    None,
+    /// Two-dimensional fix with an assumed altitude (GSA 2)
+    Fix2D,
+    /// Three-dimensional fix (GSA 3)
+    Fix3D,
+}
+
+impl FixType {
+    /// Converts a GSA fix type value.
     ///
-    /// # Arguments
-    /// * `sentence` - A string slice containing the NMEA sentence.
+    /// # Returns
+    /// * `Option<FixType>` - The matching fix type, or None for an unknown value
     ///
     /// # Example
     /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let mut gnss = GnssData::new();
-    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+    /// use nema_parser::gnss_multignss_parser::FixType;
+    /// assert_eq!(FixType::from_gsa(3), Some(FixType::Fix3D));
+    /// assert_eq!(FixType::from_gsa(0), None);
     /// ```
-    pub fn feed_nmea(&mut self, sentence: &str) {
-        let sentence = sentence.trim_start_matches('$');
-        let parts: Vec<&str> = sentence.split(',').collect();
-        match parts.first().filter(|s| s.len() >= 5).map(|s| &s[0..5]) {
-            Some("GNGGA") => self.update_gga(&parts),
-            Some("GNRMC") => self.update_rmc(&parts),
-            Some("GNVTG") => self.update_vtg(&parts),
-            Some("GNGSA") => self.update_gsa(&parts),
-            Some("GPGSV") => self.update_gsv(&parts, "GPS"),
-            Some("GLGSV") => self.update_gsv(&parts, "GLONASS"),
-            Some("GAGSV") => self.update_gsv(&parts, "GALILEO"),
-            Some("BDGSV") => self.update_gsv(&parts, "BEIDOU"),
-            Some("GPGLL") => self.update_gll(&parts, "GPS"),
-            Some("GLGLL") => self.update_gll(&parts, "GLONASS"),
-            Some("GAGLL") => self.update_gll(&parts, "GALILEO"),
-            Some("BDGLL") => self.update_gll(&parts, "BEIDOU"),
-            _ => {}
+    pub fn from_gsa(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(FixType::None),
+            2 => Some(FixType::Fix2D),
+            3 => Some(FixType::Fix3D),
+            _ => None,
         }
     }
+}
 
-    /// Calculates a fused position from all available GNSS systems using weighted averaging.
+/// Compact receiver status for driving a single status indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GnssStatus {
+    /// No NMEA sentence has been handled yet
+    NoData,
+    /// Sentences are arriving but there is no usable GNSS fix
+    Searching,
+    /// Autonomous fix with fewer than 4 satellites and no fused position
+    Fix2d,
+    /// Autonomous fix with at least 4 satellites or a fused position
+    Fix3d,
+    /// Differentially corrected fix (DGPS or SBAS)
+    Dgps,
+    /// Real-time kinematic fix, fixed or float
+    Rtk,
+}
+
+/// Vertical datum an altitude is referenced to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AltitudeDatum {
+    /// Height above mean sea level (the geoid), as reported by GGA and GNS
+    #[default]
+    Msl,
+    /// Height above the WGS84 ellipsoid
+    Ellipsoidal,
+}
+
+/// Where the altitude of a fused position comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AltitudeSource {
+    /// At least one contributing system measured the altitude in a 3D fix
+    Measured3D,
+    /// Every contributing system has a 2D fix; the altitude is assumed by the receiver or held
+    Constrained2D,
+    /// No altitude is known
+    Unavailable,
+}
+
+/// Information about a single satellite, including PRN, elevation, azimuth, and SNR.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SatelliteInfo {
+    /// Pseudo-Random Noise number (satellite identifier)
+    pub prn: u16,
+    /// Elevation angle in degrees
+    pub elevation: Option<u8>,
+    /// Azimuth angle in degrees
+    pub azimuth: Option<u16>,
+    /// Signal-to-noise ratio in dBHz; the best over `signals` when the receiver reports several
+    pub snr: Option<u8>,
+    /// Signal-to-noise ratio per signal, from NMEA 4.10+ GSV sentences carrying a signal ID,
+    /// sorted by signal ID (empty for receivers that report no signal IDs)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signals: Vec<SignalInfo>,
+    /// Whether the satellite is in the `satellites_used` list of its system's last GSA
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub used: bool,
+}
+
+impl SatelliteInfo {
+    /// Returns the best signal-to-noise ratio over all signals of the satellite.
     ///
-    /// The fused position is stored in `self.fused_position`.
+    /// # Returns
+    /// * `Option<u8>` - The highest SNR in dBHz, or `snr` if no per-signal SNR was reported
     ///
     /// # Example
     /// ```
     /// use nema_parser::gnss_multignss_parser::GnssData;
     /// let mut gnss = GnssData::new();
-    /// gnss.calculate_fused_position();
-    /// if let Some(fused) = &gnss.fused_position {
-    ///     println!("Fused position: {}, {}", fused.latitude, fused.longitude);
-    /// }
+    /// gnss.feed_nmea("$GPGSV,1,1,01,03,13,172,42,1*56").unwrap();
+    /// gnss.feed_nmea("$GPGSV,1,1,01,03,13,172,47,8*5A").unwrap();
+    /// let sat = gnss.system("GPS").unwrap().satellites().get(&3).unwrap();
+    /// assert_eq!(sat.signals.len(), 2);
+    /// assert_eq!(sat.best_snr(), Some(47));
     /// ```
-    pub fn calculate_fused_position(&mut self) {
-        let mut valid_positions = Vec::new();
-
-        for (system_name, system_data) in &self.systems {
-            if system_data.satellites_info.len() >= 4 {
-                if let (Some(lat), Some(lon), Some(hdop)) = (system_data.latitude, system_data.longitude, system_data.hdop) {
-                    let altitude = system_data.altitude.unwrap_or(0.0);
-                    let vdop = system_data.vdop.unwrap_or(hdop * 1.5); // Default VDOP if not available
-                    let system_accuracy = system_data.accuracy;
-                    valid_positions.push((system_name.to_string(), lat, lon, altitude, hdop, vdop, system_accuracy));
-                }
-            }
+    pub fn best_snr(&self) -> Option<u8> {
+        if self.signals.is_empty() {
+            return self.snr;
         }
+        self.signals.iter().filter_map(|signal| signal.snr).max()
+    }
+}
 
-        if valid_positions.is_empty() {
-            self.fused_position = None;
-            return;
-        }
+/// Signal-to-noise ratio of one signal of a satellite, such as GPS L1 C/A or L5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalInfo {
+    /// GSV signal ID ('1' = GPS L1 C/A, '6' = L2 CL, '8' = L5 Q, ...; the meaning depends on the system)
+    pub signal_id: char,
+    /// Signal-to-noise ratio in dBHz
+    pub snr: Option<u8>,
+}
 
-        if valid_positions.len() == 1 {
-            let (system, lat, lon, altitude, hdop, vdop, system_accuracy) = &valid_positions[0];
-            // Use system accuracy as multiplier instead of hardcoded 3.0
-            let horizontal_accuracy = (hdop * system_accuracy).max(*system_accuracy);
-            let vertical_accuracy = (vdop * system_accuracy * 1.5).max(*system_accuracy * 1.5);
+/// Satellites of one system keyed by PRN, with the lookup methods of a `HashMap<u16, SatelliteInfo>`.
+///
+/// Entries are kept in a vector sorted by PRN and updated in place, which avoids hashing and
+/// rehashing on the GSV path where every tracked satellite is refreshed each cycle. Iteration
+/// yields satellites in ascending PRN order. With the `serde` feature it serializes as a list
+/// of satellites.
+///
+/// Up to version 0.1.4 `GnssSystemData::satellites_info` was a `HashMap<u16, SatelliteInfo>`.
+/// Code using its lookup methods keeps compiling; code naming the `HashMap` type must switch
+/// to `SatelliteMap`, and iteration order changes from arbitrary to ascending PRN.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<SatelliteInfo>", into = "Vec<SatelliteInfo>"))]
+pub struct SatelliteMap {
+    /// Satellites sorted by PRN
+    entries: Vec<SatelliteInfo>,
+    /// Update sequence number of each entry, parallel to `entries`
+    updated: Vec<u64>,
+    /// Sequence number given to the next insert
+    next_update: u64,
+}
 
-            self.fused_position = Some(FusedPosition {
-                latitude: *lat,
-                longitude: *lon,
-                altitude: *altitude,
-                estimated_accuracy: horizontal_accuracy,
-                altitude_accuracy: vertical_accuracy,
-                contributing_systems: vec![system.clone()],
-            });
-            return;
-        }
+impl SatelliteMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        // Weighted average using inverse of combined accuracy (DOP + system accuracy) as weights
-        let mut weighted_lat = 0.0;
-        let mut weighted_lon = 0.0;
-        let mut weighted_alt = 0.0;
-        let mut total_weight = 0.0;
-        let mut total_alt_weight = 0.0;
-        let mut contributing_systems = Vec::new();
+    /// Creates an empty map with room for `capacity` satellites.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: Vec::with_capacity(capacity), updated: Vec::with_capacity(capacity), next_update: 0 }
+    }
 
-        for (system, lat, lon, altitude, hdop, vdop, system_accuracy) in &valid_positions {
-            // Use system accuracy as the multiplier for DOP values instead of hardcoded constants
-            let combined_horizontal_accuracy = (hdop * system_accuracy).max(*system_accuracy);
-            let combined_vertical_accuracy = (vdop * system_accuracy * 1.5).max(*system_accuracy * 1.5);
+    /// Reserves room for at least `additional` more satellites.
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+        self.updated.reserve(additional);
+    }
 
-            let weight = 1.0 / (combined_horizontal_accuracy + 0.1); // Add small value to avoid division by zero
-            let alt_weight = 1.0 / (combined_vertical_accuracy + 0.1); // Weight for altitude
+    /// Returns the number of satellites.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
 
-            weighted_lat += lat * weight;
-            weighted_lon += lon * weight;
-            weighted_alt += altitude * alt_weight;
-            total_weight += weight;
-            total_alt_weight += alt_weight;
-            contributing_systems.push(system.clone());
+    /// Checks whether the map holds no satellites.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the satellite with the given PRN.
+    pub fn get(&self, prn: &u16) -> Option<&SatelliteInfo> {
+        let index = self.position(*prn).ok()?;
+        Some(&self.entries[index])
+    }
+
+    /// Returns the satellite with the given PRN for modification.
+    pub fn get_mut(&mut self, prn: &u16) -> Option<&mut SatelliteInfo> {
+        let index = self.position(*prn).ok()?;
+        Some(&mut self.entries[index])
+    }
+
+    /// Checks whether a satellite with the given PRN is present.
+    pub fn contains_key(&self, prn: &u16) -> bool {
+        self.position(*prn).is_ok()
+    }
+
+    /// Inserts or replaces a satellite in place and marks it as the most recently updated.
+    ///
+    /// The stored entry's `prn` is set to `prn`.
+    ///
+    /// # Returns
+    /// * `Option<SatelliteInfo>` - The replaced satellite, or None if the PRN was new
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{SatelliteInfo, SatelliteMap};
+    /// let mut satellites = SatelliteMap::new();
+    /// satellites.insert(12, SatelliteInfo { snr: Some(40), ..Default::default() });
+    /// satellites.insert(3, SatelliteInfo::default());
+    /// assert_eq!(satellites.keys().collect::<Vec<_>>(), vec![3, 12]);
+    /// assert_eq!(satellites.get(&12).unwrap().snr, Some(40));
+    /// ```
+    pub fn insert(&mut self, prn: u16, mut info: SatelliteInfo) -> Option<SatelliteInfo> {
+        info.prn = prn;
+        let sequence = self.next_update;
+        self.next_update += 1;
+        match self.position(prn) {
+            Ok(index) => {
+                self.updated[index] = sequence;
+                Some(std::mem::replace(&mut self.entries[index], info))
+            }
+            Err(index) => {
+                self.entries.insert(index, info);
+                self.updated.insert(index, sequence);
+                None
+            }
         }
+    }
 
-        if total_weight > 0.0 {
-            let fused_lat = weighted_lat / total_weight;
-            let fused_lon = weighted_lon / total_weight;
-            let fused_alt = if total_alt_weight > 0.0 { weighted_alt / total_alt_weight } else { 0.0 };
+    /// Removes the satellite with the given PRN.
+    ///
+    /// # Returns
+    /// * `Option<SatelliteInfo>` - The removed satellite, or None if it was not present
+    pub fn remove(&mut self, prn: &u16) -> Option<SatelliteInfo> {
+        let index = self.position(*prn).ok()?;
+        self.updated.remove(index);
+        Some(self.entries.remove(index))
+    }
 
-            // Calculate fused accuracy based on weighted system accuracies and DOP values
-            let mut weighted_horizontal_accuracy = 0.0;
-            let mut weighted_vertical_accuracy = 0.0;
-            let mut total_weight = 0.0;
-            let mut total_alt_weight = 0.0;
+    /// Removes all satellites.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.updated.clear();
+    }
 
-            for (_, _, _, _, hdop, vdop, system_accuracy) in &valid_positions {
-                // Use system accuracy as multiplier instead of hardcoded 2.0
-                let combined_horizontal_accuracy = (hdop * system_accuracy).max(*system_accuracy);
-                let combined_vertical_accuracy = (vdop * system_accuracy * 1.5).max(*system_accuracy * 1.5);
+    /// Iterates over the PRNs in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = u16> + '_ {
+        self.entries.iter().map(|sat| sat.prn)
+    }
 
-                let weight = 1.0 / (combined_horizontal_accuracy + 0.1);
-                let alt_weight = 1.0 / (combined_vertical_accuracy + 0.1);
+    /// Iterates over the satellites in ascending PRN order.
+    pub fn values(&self) -> std::slice::Iter<'_, SatelliteInfo> {
+        self.entries.iter()
+    }
 
-                weighted_horizontal_accuracy += combined_horizontal_accuracy * weight;
-                weighted_vertical_accuracy += combined_vertical_accuracy * alt_weight;
-                total_weight += weight;
-                total_alt_weight += alt_weight;
-            }
+    /// Iterates mutably over the satellites in ascending PRN order.
+    ///
+    /// The PRN of an entry must not be changed through this iterator.
+    pub fn values_mut(&mut self) -> std::slice::IterMut<'_, SatelliteInfo> {
+        self.entries.iter_mut()
+    }
 
-            let final_horizontal_accuracy = if total_weight > 0.0 {
-                (weighted_horizontal_accuracy / total_weight).max(self.get_fused_accuracy())
-            } else {
-                self.get_fused_accuracy()
-            };
-            let final_vertical_accuracy = if total_alt_weight > 0.0 {
-                (weighted_vertical_accuracy / total_alt_weight)
-                    .max(self.get_fused_accuracy() * 1.5)
-            } else {
-                final_horizontal_accuracy * 1.5
-            };
+    /// Iterates over `(prn, satellite)` pairs in ascending PRN order.
+    pub fn iter(&self) -> impl Iterator<Item = (&u16, &SatelliteInfo)> {
+        self.entries.iter().map(|sat| (&sat.prn, sat))
+    }
 
-            self.fused_position = Some(FusedPosition {
-                latitude: fused_lat,
-                longitude: fused_lon,
-                altitude: fused_alt,
-                estimated_accuracy: final_horizontal_accuracy,
-                altitude_accuracy: final_vertical_accuracy,
-                contributing_systems,
-            });
-        } else {
-            self.fused_position = None;
-        }
+    /// Removes the satellites last updated before the given update sequence number.
+    fn retain_updated_since(&mut self, sequence: u64) {
+        let mut updated = self.updated.iter();
+        self.entries.retain(|_| updated.next().is_some_and(|updated| *updated >= sequence));
+        self.updated.retain(|updated| *updated >= sequence);
     }
 
-    /// Calculates an advanced fused position using a Kalman-like filtering approach.
+    /// Locates a PRN in the sorted entries.
     ///
-    /// The fused position is stored in `self.fused_position`.
-    pub fn calculate_advanced_fused_position(&mut self) {
-        let mut valid_positions = Vec::new();
+    /// # Returns
+    /// * `Result<usize, usize>` - The index of the PRN, or the index it would be inserted at
+    fn position(&self, prn: u16) -> Result<usize, usize> {
+        self.entries.binary_search_by_key(&prn, |sat| sat.prn)
+    }
+}
 
-        for (system_name, system_data) in &self.systems {
-            if let (Some(lat), Some(lon), Some(hdop), Some(pdop)) = (system_data.latitude, system_data.longitude, system_data.hdop, system_data.pdop) {
-                let altitude = system_data.altitude.unwrap_or(0.0);
-                let vdop = system_data.vdop.unwrap_or(pdop * 0.8); // Default VDOP if not available
-                let system_accuracy = system_data.accuracy;
-                valid_positions.push((system_name.to_string(), lat, lon, altitude, hdop, pdop, vdop, system_accuracy));
-            }
-        }
+impl<'a> IntoIterator for &'a SatelliteMap {
+    type Item = (&'a u16, &'a SatelliteInfo);
+    type IntoIter = std::iter::Map<std::slice::Iter<'a, SatelliteInfo>, fn(&'a SatelliteInfo) -> (&'a u16, &'a SatelliteInfo)>;
 
-        if valid_positions.is_empty() {
-            self.fused_position = None;
-            return;
+    /// Iterates over `(prn, satellite)` pairs in ascending PRN order, as `iter` does.
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|sat| (&sat.prn, sat))
+    }
+}
+
+impl From<Vec<SatelliteInfo>> for SatelliteMap {
+    fn from(satellites: Vec<SatelliteInfo>) -> Self {
+        let mut map = SatelliteMap::new();
+        for info in satellites {
+            map.insert(info.prn, info);
         }
+        map
+    }
+}
 
-        // Kalman-like filtering approach
-        let mut weighted_lat = 0.0;
-        let mut weighted_lon = 0.0;
-        let mut weighted_alt = 0.0;
-        let mut total_weight = 0.0;
-        let mut total_alt_weight = 0.0;
-        let mut contributing_systems = Vec::new();
+impl From<SatelliteMap> for Vec<SatelliteInfo> {
+    fn from(map: SatelliteMap) -> Self {
+        map.entries
+    }
+}
 
-        for (system, lat, lon, altitude, hdop, pdop, vdop, system_accuracy) in &valid_positions {
-            // Combined weight using both HDOP, PDOP and system accuracy for horizontal accuracy
-            let combined_dop = (hdop * hdop + pdop * pdop).sqrt();
-            let combined_horizontal_accuracy = combined_dop.max(*system_accuracy);
-            let weight = 1.0 / (combined_horizontal_accuracy + 0.1);
+/// Progress of one signal's multi-sentence GSV sequence.
+#[derive(Debug, Clone, Default)]
+struct GsvSequence {
+    /// Signal ID of NMEA 4.10+ sentences, None for earlier sentences without one
+    signal: Option<char>,
+    /// Number of sentences in the sequence
+    total: u8,
+    /// Last sentence number received in order, 0 once the sequence is broken
+    received: u8,
+    /// Satellites of the sequence in progress, committed when its last sentence arrives
+    staged: Vec<SatelliteInfo>,
+    /// Satellite map update sequence number the last complete sequence was committed at
+    committed: Option<u64>,
+}
 
-            // Weight for altitude based on VDOP, PDOP and system accuracy
-            let alt_combined_dop = (vdop * vdop + pdop * pdop).sqrt();
-            let combined_vertical_accuracy = alt_combined_dop.max(*system_accuracy * 1.5);
-            let alt_weight = 1.0 / (combined_vertical_accuracy + 0.1);
+/// Data for a single GNSS system (GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC).
+///
+/// Read the data through the accessor methods, as for `GnssData`.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GnssSystemData {
+    /// List of satellites used for position fix, without duplicates. The GSA sentences of a new
+    /// epoch (started by a position sentence with a new time) replace the list.
+    #[doc(hidden)]
+    #[deprecated(note = "use the `satellites_used()` accessor")]
+    pub satellites_used: Vec<u16>,
+    /// Information about all tracked satellites; a `HashMap<u16, SatelliteInfo>` up to 0.1.4
+    #[doc(hidden)]
+    #[deprecated(note = "use the `satellites()` accessor")]
+    pub satellites_info: SatelliteMap,
+    /// Position Dilution of Precision
+    #[doc(hidden)]
+    #[deprecated(note = "use the `pdop()` accessor")]
+    pub pdop: Option<f64>,
+    /// Horizontal Dilution of Precision
+    #[doc(hidden)]
+    #[deprecated(note = "use the `hdop()` accessor")]
+    pub hdop: Option<f64>,
+    /// Vertical Dilution of Precision
+    #[doc(hidden)]
+    #[deprecated(note = "use the `vdop()` accessor")]
+    pub vdop: Option<f64>,
+    /// Latitude in decimal degrees
+    #[doc(hidden)]
+    #[deprecated(note = "use the `latitude()` accessor")]
+    pub latitude: Option<f64>,
+    /// Longitude in decimal degrees
+    #[doc(hidden)]
+    #[deprecated(note = "use the `longitude()` accessor")]
+    pub longitude: Option<f64>,
+    /// Altitude in meters, referenced to `altitude_datum`
+    #[doc(hidden)]
+    #[deprecated(note = "use the `altitude()` accessor")]
+    pub altitude: Option<f64>,
+    /// Vertical datum of `altitude`
+    #[doc(hidden)]
+    #[deprecated(note = "use the `altitude_datum()` accessor")]
+    pub altitude_datum: AltitudeDatum,
+    /// Fixed accuracy in meters (best-case for this system)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `fixed_accuracy()` accessor")]
+    pub fixed_accuracy: f64,
+    /// Module accuracy in meters (dynamically updated)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `accuracy()` accessor")]
+    pub accuracy: f64,
+    /// Standard deviation of the latitude error in meters, from the system's last GST sentence
+    #[doc(hidden)]
+    #[deprecated(note = "use the `error_std()` accessor")]
+    pub lat_error_std: Option<f64>,
+    /// Standard deviation of the longitude error in meters, from the system's last GST sentence
+    #[doc(hidden)]
+    #[deprecated(note = "use the `error_std()` accessor")]
+    pub lon_error_std: Option<f64>,
+    /// Standard deviation of the altitude error in meters, from the system's last GST sentence
+    #[doc(hidden)]
+    #[deprecated(note = "use the `error_std()` accessor")]
+    pub alt_error_std: Option<f64>,
+    /// GSA 2D/3D selection mode ('A' = automatic, 'M' = manual)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `selection_mode()` accessor")]
+    pub selection_mode: Option<char>,
+    /// GSA fix type
+    #[doc(hidden)]
+    #[deprecated(note = "use the `fix_type()` accessor")]
+    pub fix_type: Option<FixType>,
+    /// GNS per-constellation mode indicator ('N' = no fix, 'A' = autonomous, 'D' = differential, 'R' = RTK, ...)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `fix_mode()` accessor")]
+    pub fix_mode: Option<char>,
+    /// Time the final sentence of the last complete GSV cycle was received
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[doc(hidden)]
+    #[deprecated(note = "use the `last_gsv_cycle()` accessor")]
+    pub last_gsv_cycle: Option<Timestamp>,
+    /// Host receive time of the last sentence that carried data for this system
+    #[doc(hidden)]
+    #[deprecated(note = "use the `last_update()` accessor")]
+    pub last_update: Option<SystemTime>,
+    /// Whether the stored position is dead-reckoned rather than a GNSS fix
+    dead_reckoned: bool,
+    /// Whether the last position sentence reported no fix (GGA quality 0, RMC or GLL status 'V'),
+    /// leaving the stored position stale
+    fix_lost: bool,
+    /// Whether the last GBS flagged one of the system's satellites as the most likely failed
+    integrity_fault: bool,
+    /// Datum of the system's positions from the last DTM sentence that applied to it
+    datum: Option<DatumInfo>,
+    /// Progress of the GSV sequence of each signal
+    #[cfg_attr(feature = "serde", serde(skip))]
+    gsv_sequences: Vec<GsvSequence>,
+    /// Epoch generation `satellites_used` was filled in
+    #[cfg_attr(feature = "serde", serde(skip))]
+    used_generation: u64,
+    /// Start of the current continuous-visibility run per PRN, kept with satellite history enabled
+    #[cfg_attr(feature = "serde", serde(skip))]
+    lock_starts: HashMap<u16, Timestamp>,
+}
 
-            weighted_lat += lat * weight;
-            weighted_lon += lon * weight;
-            weighted_alt += altitude * alt_weight;
-            total_weight += weight;
-            total_alt_weight += alt_weight;
-            contributing_systems.push(system.clone());
-        }
+impl GnssSystemData {
+    /// Returns the satellites in view.
+    pub fn satellites(&self) -> &SatelliteMap {
+        &self.satellites_info
+    }
 
-        if total_weight > 0.0 {
-            let fused_lat = weighted_lat / total_weight;
-            let fused_lon = weighted_lon / total_weight;
-            let fused_alt = if total_alt_weight > 0.0 { weighted_alt / total_alt_weight } else { 0.0 };
+    /// Returns the PRNs of the satellites used for the fix, without duplicates.
+    pub fn satellites_used(&self) -> &[u16] {
+        &self.satellites_used
+    }
 
-            // Calculate confidence interval for horizontal accuracy using system accuracies
-            let variance: f64 = valid_positions.iter()
-                .map(|(_, lat, lon, _, hdop, _, _, system_accuracy)| {
-                    let combined_accuracy = hdop.max(*system_accuracy);
-                    let weight = 1.0 / (combined_accuracy + 0.1);
-                    let lat_diff = lat - fused_lat;
-                    let lon_diff = lon - fused_lon;
-                    weight * (lat_diff * lat_diff + lon_diff * lon_diff)
-                })
-                .sum::<f64>() / total_weight;
+    /// Returns the Position Dilution of Precision from the system's GSA.
+    pub fn pdop(&self) -> Option<f64> {
+        self.pdop
+    }
 
-            let estimated_accuracy = (variance.sqrt() * 111000.0).max(self.get_fused_accuracy()); // Convert to meters and apply minimum
+    /// Returns the Horizontal Dilution of Precision from the system's GSA.
+    pub fn hdop(&self) -> Option<f64> {
+        self.hdop
+    }
 
-            // Calculate altitude variance and accuracy using system accuracies
-            let alt_variance: f64 = if total_alt_weight > 0.0 {
-                valid_positions.iter()
-                    .map(|(_, _, _, altitude, _, _, vdop, system_accuracy)| {
-                        let combined_accuracy = vdop.max(*system_accuracy * 1.5);
-                        let weight = 1.0 / (combined_accuracy + 0.1);
-                        let alt_diff = altitude - fused_alt;
-                        weight * (alt_diff * alt_diff)
-                    })
-                    .sum::<f64>() / total_alt_weight
-            } else {
-                0.0
-            };
+    /// Returns the Vertical Dilution of Precision from the system's GSA.
+    pub fn vdop(&self) -> Option<f64> {
+        self.vdop
+    }
 
-            let altitude_accuracy = if alt_variance > 0.0 {
-                alt_variance.sqrt().max(self.get_fused_accuracy() * 1.5) // Minimum based on fused accuracy
-            } else {
-                (estimated_accuracy * 1.5).max(self.get_fused_accuracy() * 1.5) // Default to 1.5x horizontal accuracy
-            };
+    /// Returns the latitude of the system's fix in decimal degrees.
+    pub fn latitude(&self) -> Option<f64> {
+        self.latitude
+    }
 
-            self.fused_position = Some(FusedPosition {
-                latitude: fused_lat,
-                longitude: fused_lon,
-                altitude: fused_alt,
-                estimated_accuracy: estimated_accuracy.max(self.get_fused_accuracy()), // Apply minimum fused accuracy
-                altitude_accuracy,
-                contributing_systems,
-            });
-        } else {
-            self.fused_position = None;
+    /// Returns the longitude of the system's fix in decimal degrees.
+    pub fn longitude(&self) -> Option<f64> {
+        self.longitude
+    }
+
+    /// Returns the altitude of the system's fix in meters, on the datum of `altitude_datum`.
+    pub fn altitude(&self) -> Option<f64> {
+        self.altitude
+    }
+
+    /// Returns the datum the altitude is measured from.
+    pub fn altitude_datum(&self) -> AltitudeDatum {
+        self.altitude_datum
+    }
+
+    /// Returns the configured accuracy of the system in meters at a DOP of 1.
+    pub fn fixed_accuracy(&self) -> f64 {
+        self.fixed_accuracy
+    }
+
+    /// Returns the current accuracy of the system in meters, scaled by its HDOP.
+    pub fn accuracy(&self) -> f64 {
+        self.accuracy
+    }
+
+    /// Returns the standard deviations of the latitude, longitude and altitude errors in meters,
+    /// from the system's last GST sentence.
+    pub fn error_std(&self) -> (Option<f64>, Option<f64>, Option<f64>) {
+        (self.lat_error_std, self.lon_error_std, self.alt_error_std)
+    }
+
+    /// Returns the GSA 2D/3D selection mode ('A' = automatic, 'M' = manual).
+    pub fn selection_mode(&self) -> Option<char> {
+        self.selection_mode
+    }
+
+    /// Returns the fix type of the system's last GSA sentence.
+    pub fn fix_type(&self) -> Option<FixType> {
+        self.fix_type
+    }
+
+    /// Returns the GNS mode indicator of the system in the current epoch ('N' = no fix, 'A' = autonomous, ...).
+    pub fn fix_mode(&self) -> Option<char> {
+        self.fix_mode
+    }
+
+    /// Returns the time the final sentence of the last complete GSV cycle was received.
+    pub fn last_gsv_cycle(&self) -> Option<Timestamp> {
+        self.last_gsv_cycle
+    }
+
+    /// Returns the receive time of the last sentence that carried data for the system.
+    pub fn last_update(&self) -> Option<Timestamp> {
+        self.last_update
+    }
+
+    /// Returns the datum of the system's positions from the last DTM sentence that applied to it.
+    pub fn datum(&self) -> Option<&DatumInfo> {
+        self.datum.as_ref()
+    }
+
+    /// Returns the system's position in WGS84, converted with the DTM datum offsets.
+    ///
+    /// Without a DTM the position is taken as WGS84; None if it cannot be converted.
+    fn wgs84_coordinates(&self) -> Option<(f64, f64)> {
+        let (latitude, longitude) = (self.latitude?, self.longitude?);
+        match &self.datum {
+            Some(datum) => datum.to_wgs84(latitude, longitude, None).map(|(latitude, longitude, _)| (latitude, longitude)),
+            None => Some((latitude, longitude)),
         }
     }
 
-    /// Gets the fused data accuracy in meters.
+    /// Checks whether the system may contribute to the fused position.
     ///
-    /// # Returns
-    /// * `f64` - The fused accuracy value in meters
+    /// A system needs at least a 2D fix: one whose GSA reports no fix, whose GNS mode indicator
+    /// is 'N', or whose last GGA, RMC or GLL reported no fix, is excluded regardless of satellite
+    /// counts, as is a dead-reckoned position when `exclude_dead_reckoning` is set.
+    fn is_fusion_eligible(&self, exclude_dead_reckoning: bool) -> bool {
+        self.fix_type != Some(FixType::None)
+            && self.fix_mode != Some('N')
+            && !self.fix_lost
+            && !(exclude_dead_reckoning && self.dead_reckoned)
+    }
+
+    /// Returns the horizontal error sigma implied by the GST latitude and longitude sigmas.
+    fn horizontal_error_std(&self) -> Option<f64> {
+        Some(self.lat_error_std?.hypot(self.lon_error_std?))
+    }
+
+    /// Returns the fix quality implied by the system's GNS mode indicator.
+    fn mode_fix_quality(&self) -> Option<FixQuality> {
+        self.fix_mode.and_then(FixQuality::from_mode_indicator)
+    }
+
+    /// Inserts or refreshes a satellite, marking it as the most recently updated.
     ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let gnss = GnssData::new();
-    /// // The fused accuracy is calculated using RSS formula from active system accuracies
-    /// assert!((gnss.get_fused_accuracy() - 1.37).abs() < 0.01);
-    /// ```
-    pub fn get_fused_accuracy(&self) -> f64 {
-        let mut active_systems = Vec::new();
-        for system_data in self.systems.values() {
-            if !system_data.satellites_info.is_empty() &&
-               system_data.latitude.is_some() &&
-               system_data.longitude.is_some() {
-                active_systems.push(system_data.accuracy);
+    /// A satellite reported with a signal ID keeps the SNR of its other signals, and its `snr`
+    /// becomes the best over them.
+    fn record_satellite(&mut self, mut info: SatelliteInfo, signal: Option<char>) {
+        if let Some(signal_id) = signal {
+            let mut signals = self.satellites_info.get(&info.prn).map(|sat| sat.signals.clone()).unwrap_or_default();
+            match signals.iter_mut().find(|known| known.signal_id == signal_id) {
+                Some(known) => known.snr = info.snr,
+                None => {
+                    signals.push(SignalInfo { signal_id, snr: info.snr });
+                    signals.sort_by_key(|known| known.signal_id);
+                }
             }
+            info.signals = signals;
+            info.snr = info.best_snr();
         }
-        if active_systems.is_empty() {
-            // If no active systems, use all system dynamic accuracies
-            active_systems = self.systems.values().map(|sys| sys.accuracy).collect();
+        info.used = self.satellites_used.contains(&info.prn);
+        self.satellites_info.insert(info.prn, info);
+    }
+
+    /// Sets the `used` flag of every tracked satellite from `satellites_used`.
+    fn mark_used_satellites(&mut self) {
+        for sat in self.satellites_info.values_mut() {
+            sat.used = self.satellites_used.contains(&sat.prn);
         }
-        let sum_of_inverse_squares: f64 = active_systems.iter()
-            .map(|accuracy| 1.0 / accuracy.powi(2))
-            .sum();
-        if sum_of_inverse_squares == 0.0 {
-            return 0.0;
-        }
-        1.0 / sum_of_inverse_squares.sqrt()
     }
 
-    /// Sets the fused data accuracy in meters.
+    /// Forgets a signal on the satellites missing from its latest complete sequence.
     ///
-    /// # Arguments
-    /// * `accuracy` - The fused accuracy value in meters
-    ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let mut gnss = GnssData::new();
-    /// gnss.set_fused_accuracy(3.0);
-    /// // Fused accuracy is always dynamically calculated, not set by this function
-    /// let fused_accuracy = gnss.get_fused_accuracy();
-    /// assert!((fused_accuracy - 1.3675).abs() < 0.01);
-    /// ```
-    pub fn set_fused_accuracy(&mut self, _accuracy: f64) {
-        // No-op: fused accuracy is now always dynamic
+    /// A satellite that loses its last signal is removed.
+    fn drop_signal_except(&mut self, signal_id: char, seen: &[u16]) {
+        let mut lost = Vec::new();
+        for sat in self.satellites_info.values_mut().filter(|sat| !seen.contains(&sat.prn)) {
+            let before = sat.signals.len();
+            sat.signals.retain(|known| known.signal_id != signal_id);
+            if sat.signals.len() != before {
+                if sat.signals.is_empty() {
+                    lost.push(sat.prn);
+                } else {
+                    sat.snr = sat.best_snr();
+                }
+            }
+        }
+        for prn in lost {
+            self.satellites_info.remove(&prn);
+        }
     }
 
-    /// Gets the dynamic accuracy for a specific GNSS system in meters.
-    ///
-    /// # Arguments
-    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU")
-    ///
-    /// # Returns
-    /// * `Option<f64>` - The system accuracy value in meters, or None if system doesn't exist
-    ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let gnss = GnssData::new();
-    /// assert_eq!(gnss.get_system_accuracy("GPS"), Some(2.0));
-    /// assert_eq!(gnss.get_system_accuracy("GLONASS"), Some(4.0));
-    /// ```
-    pub fn get_system_accuracy(&self, system: &str) -> Option<f64> {
-        self.systems.get(system).map(|sys| sys.accuracy)
+    /// Removes all tracked satellites.
+    fn clear_satellites(&mut self) {
+        self.satellites_info.clear();
     }
 
-    /// Gets the fixed accuracy for a specific GNSS system in meters.
-    ///
-    /// # Arguments
-    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU")
-    ///
-    /// # Returns
-    /// * `Option<f64>` - The fixed accuracy value in meters, or None if the system doesn't exist
-    ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let gnss = GnssData::new();
-    /// assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(2.0));
-    /// assert_eq!(gnss.get_system_fixed_accuracy("GLONASS"), Some(4.0));
-    /// ```
-    pub fn get_system_fixed_accuracy(&self, system: &str) -> Option<f64> {
-        self.systems.get(system).map(|sys| sys.fixed_accuracy)
+    /// Drops everything learned from the system's sentences, keeping its configured accuracy.
+    fn expire(&mut self) {
+        *self = GnssSystemData {
+            fixed_accuracy: self.fixed_accuracy,
+            accuracy: self.fixed_accuracy,
+            ..GnssSystemData::default()
+        };
     }
 
-    /// Sets the accuracy for a specific GNSS system in meters.
+    /// Closes a GSV cycle: PRNs seen for the first time start a lock run at `cycle_time`,
+    /// and PRNs missing from the cycle end theirs.
+    fn close_lock_runs(&mut self, seen: Vec<u16>, cycle_time: Timestamp) {
+        self.lock_starts.retain(|prn, _| seen.contains(prn));
+        for prn in seen {
+            self.lock_starts.entry(prn).or_insert(cycle_time);
+        }
+    }
+
+    /// Adds a PRN to `satellites_used`, replacing the list left by an earlier epoch's GSA.
+    fn record_used_satellite(&mut self, prn: u16, generation: u64) {
+        self.begin_used_satellites(generation);
+        if !self.satellites_used.contains(&prn) {
+            self.satellites_used.push(prn);
+        }
+    }
+
+    /// Clears `satellites_used` if it was filled in an earlier epoch generation.
+    fn begin_used_satellites(&mut self, generation: u64) {
+        if self.used_generation != generation {
+            self.satellites_used.clear();
+            self.used_generation = generation;
+        }
+    }
+
+    /// Adds the satellites of a GSV sentence to its signal's sequence.
     ///
-    /// # Arguments
-    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU")
-    /// * `accuracy` - The system accuracy value in meters
+    /// Sentence 1 starts a new sequence, with room for `in_view` satellites; later sentences must
+    /// follow in order with the same total, otherwise the partial sequence is discarded and the
+    /// signal's sentences are ignored until the next sentence 1.
     ///
     /// # Returns
-    /// * `bool` - True if the system exists and accuracy was set, false otherwise
-    ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let mut gnss = GnssData::new();
-    /// assert!(gnss.set_system_fixed_accuracy("GPS", 1.5));
-    /// assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(1.5));
-    /// assert!(!gnss.set_system_fixed_accuracy("INVALID", 1.0));
-    /// ```
-    pub fn set_system_fixed_accuracy(&mut self, system: &str, accuracy: f64) -> bool {
-        if let Some(sys) = self.systems.get_mut(system) {
-            sys.fixed_accuracy = accuracy;
-            true
+    /// * `Option<Vec<SatelliteInfo>>` - The satellites of the whole sequence if the sentence completes it
+    fn stage_gsv_sentence(
+        &mut self,
+        signal: Option<char>,
+        (total, number, in_view): (Option<u8>, Option<u8>, Option<u16>),
+        satellites: impl Iterator<Item = SatelliteInfo>,
+    ) -> Option<Vec<SatelliteInfo>> {
+        let (Some(total), Some(number)) = (total, number) else {
+            return None;
+        };
+        let index = match self.gsv_sequences.iter().position(|sequence| sequence.signal == signal) {
+            Some(index) => index,
+            None if number == 1 => {
+                self.gsv_sequences.push(GsvSequence { signal, ..GsvSequence::default() });
+                self.gsv_sequences.len() - 1
+            }
+            None => return None,
+        };
+        let sequence = &mut self.gsv_sequences[index];
+        if number == 1 {
+            sequence.total = total;
+            sequence.received = 1;
+            sequence.staged.clear();
+            sequence.staged.reserve(in_view.unwrap_or(0).into());
+            sequence.staged.extend(satellites);
+        } else if sequence.received > 0 && sequence.total == total && sequence.received + 1 == number {
+            sequence.received = number;
+            sequence.staged.extend(satellites);
         } else {
-            false
+            sequence.received = 0;
+            sequence.staged.clear();
+            return None;
         }
+        if sequence.received != total {
+            return None;
+        }
+        sequence.committed = Some(self.satellites_info.next_update);
+        Some(std::mem::take(&mut sequence.staged))
     }
 
-    /// Gets all system accuracies as a HashMap.
+    /// Drops satellites missing from the last complete sequence of every signal.
+    fn prune_uncommitted_satellites(&mut self) {
+        if let Some(committed) = self.gsv_sequences.iter().filter_map(|sequence| sequence.committed).min() {
+            self.satellites_info.retain_updated_since(committed);
+        }
+    }
+
+    /// Evicts the weakest satellite: the lowest SNR (missing SNR counts as lowest),
+    /// breaking ties by evicting the least recently updated entry.
     ///
     /// # Returns
-    /// * `HashMap<String, f64>` - Map of system names to their accuracy values in meters
-    ///
-    /// # Example
-    /// ```
-    /// use nema_parser::gnss_multignss_parser::GnssData;
-    /// let gnss = GnssData::new();
-    /// let accuracies = gnss.get_all_system_accuracies();
-    /// assert_eq!(accuracies.get("GPS"), Some(&2.0));
-    /// assert_eq!(accuracies.get("GLONASS"), Some(&4.0));
-    /// ```
-    pub fn get_all_system_accuracies(&self) -> HashMap<String, f64> {
-        self.systems.iter()
-            .map(|(name, sys)| (name.to_string(), sys.fixed_accuracy))
-            .collect()
+    /// * `Option<SatelliteInfo>` - The evicted satellite, or None if the map is empty
+    fn evict_weakest_satellite(&mut self) -> Option<SatelliteInfo> {
+        let satellites = &self.satellites_info;
+        let victim = satellites.entries.iter().zip(&satellites.updated)
+            .min_by_key(|(sat, updated)| (sat.snr, **updated, sat.prn))
+            .map(|(sat, _)| sat.prn)?;
+        self.satellites_info.remove(&victim)
     }
 }
 
-/// Parses latitude from NMEA format to decimal degrees.
+
+/// Serializes a float that may be NaN or infinite as null when it is not finite.
 ///
-/// # Arguments
-/// * `value` - Latitude value as string (DDMM.MMMM)
-/// * `hemi` - Hemisphere ("N" or "S")
+/// JSON has no representation for non-finite numbers; serde_json writes them as null and then
+/// refuses to read null back into an `f64`.
+#[cfg(feature = "serde")]
+fn serialize_finite<S: serde::Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::Serialize;
+    value.is_finite().then_some(value).serialize(serializer)
+}
+
+/// Deserializes a float written by `serialize_finite`, reading null as NaN.
+#[cfg(feature = "serde")]
+fn deserialize_or_nan<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    use serde::Deserialize;
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+}
+
+/// Deserializes a float written by `serialize_finite`, reading null as infinity.
+#[cfg(feature = "serde")]
+fn deserialize_or_infinity<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    use serde::Deserialize;
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+}
+
+/// Serializes the per-system data keyed by system name, in `GnssSystem::ALL` order.
+#[cfg(feature = "serde")]
+fn serialize_systems<S: serde::Serializer>(systems: &HashMap<&'static str, GnssSystemData>, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeMap;
+    let mut map = serializer.serialize_map(Some(systems.len()))?;
+    for system in GnssSystem::ALL {
+        if let Some(data) = systems.get(system.name()) {
+            map.serialize_entry(system.name(), data)?;
+        }
+    }
+    map.end()
+}
+
+/// Deserializes the per-system data, mapping each name back to its `GnssSystem` name.
+#[cfg(feature = "serde")]
+fn deserialize_systems<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<HashMap<&'static str, GnssSystemData>, D::Error> {
+    use serde::Deserialize;
+    HashMap::<String, GnssSystemData>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(name, data)| match GnssSystem::from_name(&name) {
+            Some(system) => Ok((system.name(), data)),
+            None => Err(serde::de::Error::custom(format!("unknown GNSS system \"{name}\""))),
+        })
+        .collect()
+}
+
+/// Main GNSS data structure holding parsed information and fused position.
 ///
-/// # Returns
-/// * `Option<f64>` - Latitude in decimal degrees
-fn parse_lat(value: Option<&&str>, hemi: Option<&&str>) -> Option<f64> {
-    let val = value?.parse::<f64>().ok()?;
-    let deg = (val / 100.0).floor();
-    let min = val % 100.0;
-    let mut result = deg + min / 60.0;
-    if hemi? == &"S" { result *= -1.0; }
-    Some(result)
+/// Data is read through the accessor methods and changes only by feeding sentences or through
+/// the configuration setters; see "Reading Data" in the module documentation.
+///
+/// With the `serde` feature the parsed data serializes, with `systems` keyed by system name.
+/// Configuration, statistics, warnings and epoch bookkeeping are not serialized; a deserialized
+/// value starts from the defaults of `GnssData::new` for them.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default = "GnssData::new"))]
+pub struct GnssData {
+    /// UTC time from NMEA sentence
+    #[doc(hidden)]
+    #[deprecated(note = "use the `time()` accessor")]
+    pub time: Option<String>,
+    /// Latitude in decimal degrees
+    #[doc(hidden)]
+    #[deprecated(note = "use the `latitude()` accessor")]
+    pub latitude: Option<f64>,
+    /// Longitude in decimal degrees
+    #[doc(hidden)]
+    #[deprecated(note = "use the `longitude()` accessor")]
+    pub longitude: Option<f64>,
+    /// Fix quality indicator
+    #[doc(hidden)]
+    #[deprecated(note = "use the `fix_quality()` accessor")]
+    pub fix_quality: Option<u8>,
+    /// Number of satellites used for fix
+    #[doc(hidden)]
+    #[deprecated(note = "use the `num_satellites()` accessor")]
+    pub num_satellites: Option<u8>,
+    /// Altitude above mean sea level in meters
+    #[doc(hidden)]
+    #[deprecated(note = "use the `altitude()` accessor")]
+    pub altitude: Option<f64>,
+    /// Geoid separation (ellipsoid height minus MSL height) in meters, from GGA or GNS
+    #[doc(hidden)]
+    #[deprecated(note = "use the `geoid_separation()` accessor")]
+    pub geoid_separation: Option<f64>,
+    /// Age of the differential corrections in seconds, from GGA (None without DGPS)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `dgps_age()` accessor")]
+    pub dgps_age: Option<f64>,
+    /// Reference station ID of the differential corrections, from GGA (None without DGPS)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `dgps_station()` accessor")]
+    pub dgps_station: Option<u16>,
+    /// Speed over ground in knots
+    #[doc(hidden)]
+    #[deprecated(note = "use the `speed_knots()` accessor")]
+    pub speed_knots: Option<f64>,
+    /// Track angle in degrees
+    #[doc(hidden)]
+    #[deprecated(note = "use the `track_angle()` accessor")]
+    pub track_angle: Option<f64>,
+    /// Magnetic track angle in degrees, from VTG
+    track_angle_magnetic: Option<f64>,
+    /// True heading in degrees, from HDT or THS
+    heading_true: Option<f64>,
+    /// Mode indicator of the last THS sentence, None after an HDT
+    heading_mode: Option<char>,
+    /// Date in DDMMYY format, from RMC or ZDA
+    #[doc(hidden)]
+    #[deprecated(note = "use the `date()` accessor")]
+    pub date: Option<String>,
+    /// Status of the last RMC sentence ('A' = valid, 'V' = void)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `rmc_status()` accessor")]
+    pub rmc_status: Option<char>,
+    /// Magnetic variation from the last RMC sentence in degrees, positive east
+    magnetic_variation: Option<f64>,
+    /// FAA mode indicator of the last RMC sentence (NMEA 2.3+; 'A', 'D', 'E', 'N', ...)
+    rmc_mode: Option<char>,
+    /// Navigational status of the last RMC sentence (NMEA 4.1; 'S', 'C', 'U', 'V')
+    nav_status: Option<char>,
+    /// Local time zone from the last ZDA sentence, in minutes east of UTC
+    #[doc(hidden)]
+    #[deprecated(note = "use the `local_zone_offset()` accessor")]
+    pub local_zone_offset: Option<i16>,
+    /// Position Dilution of Precision of the combined solution, from a GSA spanning several systems
+    #[doc(hidden)]
+    #[deprecated(note = "use the `pdop()` accessor")]
+    pub combined_pdop: Option<f64>,
+    /// Horizontal Dilution of Precision of the combined solution, from a GSA spanning several systems
+    #[doc(hidden)]
+    #[deprecated(note = "use the `hdop()` accessor")]
+    pub combined_hdop: Option<f64>,
+    /// Vertical Dilution of Precision of the combined solution, from a GSA spanning several systems
+    #[doc(hidden)]
+    #[deprecated(note = "use the `vdop()` accessor")]
+    pub combined_vdop: Option<f64>,
+    /// Fix type of the last GSA sentence
+    fix_type: Option<FixType>,
+    /// Horizontal Dilution of Precision reported in GGA or GNS field 8 (None without a fix)
+    #[doc(hidden)]
+    #[deprecated(note = "use the `gga_hdop()` accessor")]
+    pub gga_hdop: Option<f64>,
+    /// Error statistics of the combined solution from the last GST sentence
+    #[doc(hidden)]
+    #[deprecated(note = "use the `error_statistics()` accessor")]
+    pub error_statistics: Option<ErrorStatistics>,
+    /// RAIM integrity report from the last GBS sentence
+    integrity: Option<IntegrityInfo>,
+    /// Datum announced by the last DTM sentence
+    datum: Option<DatumInfo>,
+    /// Data for each GNSS system
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_systems", deserialize_with = "deserialize_systems"))]
+    #[doc(hidden)]
+    #[deprecated(note = "use the `system()` or `systems()` accessors")]
+    pub systems: HashMap<&'static str, GnssSystemData>,
+    /// Fused position calculated from available systems
+    #[doc(hidden)]
+    #[deprecated(note = "use the `fused_position()` accessor")]
+    pub fused_position: Option<FusedPosition>,
+    /// Number of consecutive good epochs suppressed before a fused position is reported
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warmup_epochs: u32,
+    /// Count of consecutive epochs that produced a fused position
+    #[cfg_attr(feature = "serde", serde(skip))]
+    consecutive_good_epochs: u32,
+    /// Parser statistics
+    #[cfg_attr(feature = "serde", serde(skip))]
+    stats: ParserStats,
+    /// Maximum satellites kept per system in `satellites_info` (0 means unlimited)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    max_satellites_per_system: usize,
+    /// Maximum satellites kept across all systems (0 means unlimited)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    max_satellites_total: usize,
+    /// Movement gate for derived course, as a multiple of the fix accuracy
+    #[cfg_attr(feature = "serde", serde(skip))]
+    course_gate_factor: f64,
+    /// Number of derived bearings averaged for the derived course
+    #[cfg_attr(feature = "serde", serde(skip))]
+    course_window: usize,
+    /// Last fix (latitude, longitude, accuracy) a course was derived from
+    #[cfg_attr(feature = "serde", serde(skip))]
+    course_anchor: Option<(f64, f64, f64)>,
+    /// Recent derived bearings in degrees
+    #[cfg_attr(feature = "serde", serde(skip))]
+    course_history: Vec<f64>,
+    /// Smoothed course derived from successive fused fixes in degrees
+    #[cfg_attr(feature = "serde", serde(skip))]
+    derived_course: Option<f64>,
+    /// Pending data-quality warnings, oldest first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warnings: VecDeque<DataWarning>,
+    /// Latest TXT messages from the receiver, oldest first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    text_messages: Vec<TxtMessage>,
+    /// Leading parts of a multi-sentence TXT message awaiting the rest
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending_txt: Option<PendingTxt>,
+    /// Latest antenna status reported in a TXT message
+    #[cfg_attr(feature = "serde", serde(skip))]
+    antenna_status: Option<AntennaStatus>,
+    /// How fusion treats dead-reckoned positions
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dead_reckoning_policy: DeadReckoningPolicy,
+    /// How fusion treats a system with a satellite flagged as failed by GBS
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fault_policy: FaultPolicy,
+    /// How fusion treats a system reported in a datum other than WGS84
+    #[cfg_attr(feature = "serde", serde(skip))]
+    datum_policy: DatumPolicy,
+    /// Smoothed offset between sentence receipt and the UTC time it carries
+    #[cfg_attr(feature = "serde", serde(skip))]
+    timing: Option<TimingEstimate>,
+    /// Host time acquisition started (creation or last reset)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    acquisition_start: Option<SystemTime>,
+    /// Host receive time of the last sentence fed
+    #[cfg_attr(feature = "serde", serde(skip))]
+    last_receive_time: Option<SystemTime>,
+    /// Time from acquisition start until `RAMP_SATELLITES` satellites reported an SNR
+    #[cfg_attr(feature = "serde", serde(skip))]
+    signal_ramp: Option<Duration>,
+    /// Longest signal ramp-up classified as a hot start
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hot_start_max: Duration,
+    /// Longest signal ramp-up classified as a warm start
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warm_start_max: Duration,
+    /// Decimal separator of numeric fields ('.' or ',')
+    #[cfg_attr(feature = "serde", serde(skip))]
+    decimal_separator: char,
+    /// Altitude spread between contributing systems above which altitude accuracy is inflated
+    #[cfg_attr(feature = "serde", serde(skip))]
+    altitude_spread_limit: f64,
+    /// Distance from the median system position beyond which the advanced fusion drops a system
+    #[cfg_attr(feature = "serde", serde(skip))]
+    outlier_threshold: f64,
+    /// DOP below which a reported DOP is treated as missing
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dop_floor: f64,
+    /// DOP above which a reported DOP is treated as missing
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dop_ceiling: f64,
+    /// Diagnostics of the last fusion epoch
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fusion_diagnostics: Option<FusionDiagnostics>,
+    /// Whether only fixes with better accuracy replace the stored fused position
+    #[cfg_attr(feature = "serde", serde(skip))]
+    keep_best_only: bool,
+    /// Age after which a kept best fix may be replaced by a worse one (None keeps it indefinitely)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    best_fix_staleness: Option<Duration>,
+    /// Host time the stored best fix was accepted
+    #[cfg_attr(feature = "serde", serde(skip))]
+    best_fix_time: Option<SystemTime>,
+    /// How long the altitude of the last 3D epoch is held for 2D epochs (None disables holding)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    altitude_hold: Option<Duration>,
+    /// Altitude, altitude accuracy and host time of the last 3D epoch
+    #[cfg_attr(feature = "serde", serde(skip))]
+    held_altitude: Option<(f64, f64, SystemTime)>,
+    /// How fast in m/s the true position may drift between epochs, if the position filter is enabled
+    #[cfg_attr(feature = "serde", serde(skip))]
+    position_filter: Option<f64>,
+    /// Filtered latitude, longitude, variance in m² and host time of the last filtered epoch
+    #[cfg_attr(feature = "serde", serde(skip))]
+    filter_state: Option<(f64, f64, f64, SystemTime)>,
+    /// Sentence types allowed to set the position, highest precedence first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    position_precedence: Vec<String>,
+    /// Sentence types allowed to set speed and track, highest precedence first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    speed_precedence: Vec<String>,
+    /// UTC time of the current precedence epoch in hhmmss.ss as a number
+    #[cfg_attr(feature = "serde", serde(skip))]
+    epoch_time: Option<f64>,
+    /// Number of epoch changes seen, grouping the GSA sentences of an epoch
+    #[cfg_attr(feature = "serde", serde(skip))]
+    epoch_generation: u64,
+    /// Receiver features seen so far, summarized by `receiver_profile`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    features: ObservedFeatures,
+    /// PRNs a "GN" GSA attributes to NavIC, if they differ from `DEFAULT_NAVIC_PRNS`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    navic_prns: Option<RangeInclusive<u16>>,
+    /// Newest UTC time carried by an accepted position sentence
+    #[cfg_attr(feature = "serde", serde(skip))]
+    newest_position_time: Option<UtcTime>,
+    /// How far a position sentence's time may trail the newest time before it counts as late
+    #[cfg_attr(feature = "serde", serde(skip))]
+    late_tolerance: Duration,
+    /// Precedence rank of the sentence that set the position this epoch
+    #[cfg_attr(feature = "serde", serde(skip))]
+    position_claim: Option<usize>,
+    /// Precedence rank of the sentence that set speed and track this epoch
+    #[cfg_attr(feature = "serde", serde(skip))]
+    speed_claim: Option<usize>,
+    /// Latitude and longitude reported this epoch, per sentence type
+    #[cfg_attr(feature = "serde", serde(skip))]
+    epoch_positions: Vec<(&'static str, f64, f64)>,
+    /// Handlers registered at runtime, keyed by sentence type
+    #[cfg_attr(feature = "serde", serde(skip))]
+    custom_handlers: HashMap<String, CustomSentenceHandler>,
+    /// Whether per-satellite history such as lock runs is kept
+    #[cfg_attr(feature = "serde", serde(skip))]
+    satellite_history: bool,
+    /// Whether sentences without a valid checksum are dropped
+    #[cfg_attr(feature = "serde", serde(skip))]
+    checksum_validation: bool,
+    /// Whether sentences with bytes after the checksum are dropped rather than stripped
+    #[cfg_attr(feature = "serde", serde(skip))]
+    strict_framing: bool,
+    /// Order in which fused positions list their contributing systems
+    #[cfg_attr(feature = "serde", serde(skip))]
+    contributing_order: ContributingOrder,
+    /// Accuracy in meters exports degrade the fused position to, if privacy mode is enabled
+    #[cfg_attr(feature = "serde", serde(skip))]
+    privacy_accuracy: Option<f64>,
+    /// Four-digit year of `date`, if the sentence that set it carried one (ZDA)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    date_year: Option<u16>,
+    /// UTC time of the sentence that set `date`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    date_reported_at: Option<UtcTime>,
+    /// Number of sealed epoch records kept (0 disables recording)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    epoch_capacity: usize,
+    /// Whether epoch records leave out the tracked satellites
+    #[cfg_attr(feature = "serde", serde(skip))]
+    elide_epoch_satellites: bool,
+    /// Sealed epoch records, oldest first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    epochs: VecDeque<EpochData>,
+    /// Warnings raised during the current epoch, kept while recording
+    #[cfg_attr(feature = "serde", serde(skip))]
+    epoch_warnings: Vec<DataWarning>,
+    /// Bytes of the line `feed_bytes` has not seen the terminator of yet
+    #[cfg_attr(feature = "serde", serde(skip))]
+    partial_line: Vec<u8>,
+    /// Source of the receive time for sentences fed without one
+    #[cfg_attr(feature = "serde", serde(skip))]
+    clock: SharedClock,
+    /// Geoid model used when no sentence reports the geoid separation
+    #[cfg(feature = "geoid")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geoid_model: Option<crate::geoid::GeoidGrid>,
 }
 
-/// Parses longitude from NMEA format to decimal degrees.
+/// Maximum number of pending data-quality warnings; older warnings are dropped first.
+pub const MAX_PENDING_WARNINGS: usize = 64;
+
+/// Maximum number of TXT messages kept by `GnssData`; older messages are dropped first.
+pub const MAX_TEXT_MESSAGES: usize = 32;
+
+/// Maximum length in bytes of a line `feed_bytes` buffers while waiting for its terminator.
+pub const MAX_BUFFERED_LINE: usize = 1024;
+
+/// A data-quality anomaly that the parser detected and handled.
 ///
-/// # Arguments
-/// * `value` - Longitude value as string (DDDMM.MMMM)
-/// * `hemi` - Hemisphere ("E" or "W")
+/// Warnings are queued on `GnssData` and drained with `take_warnings`. Aggregate counts are
+/// still kept in `ParserStats`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataWarning {
+    /// A sentence's `*hh` checksum did not match its payload
+    ChecksumMismatch {
+        /// Sentence address field (e.g. "GPGSV")
+        sentence_type: String,
+        /// Checksum text carried by the sentence
+        expected: String,
+        /// Checksum computed over the payload
+        computed: u8,
+    },
+    /// A satellite was dropped because a capacity limit was reached
+    SatelliteEvicted {
+        /// System the satellite belonged to
+        system: String,
+        /// PRN of the evicted satellite
+        prn: u16,
+        /// SNR of the evicted satellite in dBHz
+        snr: Option<u8>,
+    },
+    /// A PRN could not be attributed to any supported system
+    UnattributedPrn {
+        /// Sentence type the PRN appeared in (e.g. "GSA")
+        sentence_type: String,
+        /// The unattributed PRN
+        prn: u16,
+    },
+    /// Contributing systems disagreed on altitude by more than the configured limit
+    AltitudeDisagreement {
+        /// Spread between the highest and lowest system altitude in meters
+        spread: f64,
+        /// Configured spread limit in meters
+        limit: f64,
+    },
+    /// A callback panicked: a handler registered with `GnssData::register_sentence_handler`,
+    /// which was removed, or the callback of `GnssData::feed_nmea_with_fields`
+    ObserverPanicked {
+        /// Sentence type the callback ran for (e.g. "ROT")
+        sentence_type: String,
+    },
+    /// A DOP outside the plausible range was treated as missing
+    ImplausibleDop {
+        /// Sentence type the DOP appeared in (e.g. "GSA")
+        sentence_type: String,
+        /// The reported DOP
+        value: f64,
+    },
+    /// A VTG sentence's speeds in knots and km/h disagreed; the knots value was kept
+    SpeedMismatch {
+        /// Speed over ground in knots (field 5)
+        knots: f64,
+        /// Speed over ground in km/h (field 7)
+        kmh: f64,
+    },
+    /// A sentence was dropped because its numbers use a comma decimal separator
+    CommaDecimalSeparator {
+        /// Sentence address field (e.g. "GPGGA")
+        sentence_type: String,
+    },
+    /// A DTM sentence announced positions in a datum other than WGS84
+    NonWgs84Datum {
+        /// Local datum code (e.g. "W72")
+        datum: String,
+    },
+    /// The advanced fusion dropped a system too far from the median system position
+    OutlierRejected {
+        /// Name of the dropped system (e.g. "BEIDOU")
+        system: String,
+        /// Distance from the median position in meters
+        distance: f64,
+    },
+}
+
+/// Sentence recognized by `GnssData::feed_nmea`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SentenceType {
+    /// Fix data (GGA)
+    Gga,
+    /// Recommended minimum data (RMC)
+    Rmc,
+    /// Course and speed over ground (VTG)
+    Vtg,
+    /// DOPs and active satellites (GSA)
+    Gsa,
+    /// Combined fix data (GNGNS)
+    Gns,
+    /// Error statistics of the combined solution (GNGST) or a single system
+    Gst,
+    /// UTC date and time (from any talker)
+    Zda,
+    /// Receiver status text (from any talker)
+    Txt,
+    /// RAIM fault detection of the combined solution (GNGBS) or a single system
+    Gbs,
+    /// Datum reference of the combined solution (GNDTM) or a single system
+    Dtm,
+    /// True heading (from any talker)
+    Hdt,
+    /// True heading and its mode indicator (from any talker)
+    Ths,
+    /// Satellites in view of one system
+    Gsv {
+        /// System named by the talker ID
+        system: GnssSystem,
+    },
+    /// Geographic position
+    Gll {
+        /// System named by the talker ID, or None for the combined solution ("GN")
+        system: Option<GnssSystem>,
+    },
+    /// Sentence type accepted by a handler registered with `register_sentence_handler`
+    Custom(String),
+}
+
+impl SentenceType {
+    /// Maps a built-in sentence type and the system its talker names to a `SentenceType`.
+    fn builtin(sentence_type: &str, system: Option<GnssSystem>) -> Option<Self> {
+        match (sentence_type, system) {
+            ("GGA", _) => Some(SentenceType::Gga),
+            ("RMC", _) => Some(SentenceType::Rmc),
+            ("VTG", _) => Some(SentenceType::Vtg),
+            ("GSA", _) => Some(SentenceType::Gsa),
+            ("GNS", _) => Some(SentenceType::Gns),
+            ("GST", _) => Some(SentenceType::Gst),
+            ("ZDA", _) => Some(SentenceType::Zda),
+            ("TXT", _) => Some(SentenceType::Txt),
+            ("GBS", _) => Some(SentenceType::Gbs),
+            ("DTM", _) => Some(SentenceType::Dtm),
+            ("HDT", _) => Some(SentenceType::Hdt),
+            ("THS", _) => Some(SentenceType::Ths),
+            ("GSV", Some(system)) => Some(SentenceType::Gsv { system }),
+            ("GLL", system) => Some(SentenceType::Gll { system }),
+            _ => None,
+        }
+    }
+}
+
+/// Problem found in a sentence by `GnssData::feed_nmea`.
 ///
-/// # Returns
-/// * `Option<f64>` - Longitude in decimal degrees
-fn parse_lon(value: Option<&&str>, hemi: Option<&&str>) -> Option<f64> {
-    let val = value?.parse::<f64>().ok()?;
-    let deg = (val / 100.0).floor();
-    let min = val % 100.0;
-    let mut result = deg + min / 60.0;
-    if hemi? == &"W" { result *= -1.0; }
-    Some(result)
+/// Field indexes count the address field (e.g. "GNGGA") as field 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NmeaError {
+    /// The sentence carries nothing after the leading `$`
+    EmptySentence,
+    /// The sentence type is neither built in nor registered with `register_sentence_handler`
+    UnknownSentence {
+        /// Sentence address field (e.g. "GPXYZ")
+        address: String,
+    },
+    /// The sentence type is built in but not accepted from this talker, such as a per-system
+    /// sentence (GSV) from "GN" or a GNSS sentence from a talker that names no constellation
+    UnsupportedTalker {
+        /// Sentence address field (e.g. "GNGSV")
+        address: String,
+    },
+    /// The `*hh` checksum does not match the payload, or is missing while checksum validation
+    /// is enabled
+    ChecksumMismatch {
+        /// Checksum text carried by the sentence (empty if it carries none)
+        expected: String,
+        /// Checksum computed over the payload
+        computed: u8,
+    },
+    /// Bytes other than line terminators follow the checksum while strict framing is enabled
+    TrailingGarbage {
+        /// The bytes following the checksum
+        garbage: String,
+    },
+    /// The sentence has fewer fields than its type defines
+    MissingFields {
+        /// Number of fields the sentence type defines, address included
+        expected: usize,
+        /// Number of fields the sentence carries, address included
+        found: usize,
+    },
+    /// A numeric field holds something other than a number
+    InvalidNumber {
+        /// Index of the offending field
+        index: usize,
+    },
+    /// The sentence uses a comma decimal separator while '.' is configured
+    CommaDecimalSeparator {
+        /// Sentence address field (e.g. "GPGGA")
+        address: String,
+    },
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl std::fmt::Display for NmeaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NmeaError::EmptySentence => write!(f, "empty sentence"),
+            NmeaError::UnknownSentence { address } => write!(f, "unknown sentence {}", address),
+            NmeaError::UnsupportedTalker { address } => write!(f, "unsupported talker for {}", address),
+            NmeaError::ChecksumMismatch { expected, computed } if expected.is_empty() => {
+                write!(f, "missing checksum, computed {:02X}", computed)
+            }
+            NmeaError::ChecksumMismatch { expected, computed } => {
+                write!(f, "checksum mismatch: sentence carries {}, computed {:02X}", expected, computed)
+            }
+            NmeaError::TrailingGarbage { garbage } => write!(f, "trailing garbage after checksum: {:?}", garbage),
+            NmeaError::MissingFields { expected, found } => {
+                write!(f, "expected {} fields, found {}", expected, found)
+            }
+            NmeaError::InvalidNumber { index } => write!(f, "field {} is not a number", index),
+            NmeaError::CommaDecimalSeparator { address } => write!(f, "comma decimal separator in {}", address),
+        }
+    }
+}
 
-    #[test]
-    fn test_gnssdata_new() {
-        let gnss = GnssData::new();
-        assert!(gnss.systems.contains_key("GPS"));
-        assert!(gnss.systems.contains_key("GLONASS"));
-        assert!(gnss.systems.contains_key("GALILEO"));
-        assert!(gnss.systems.contains_key("BEIDOU"));
+impl std::error::Error for NmeaError {}
+
+/// Default position precedence within an epoch, highest first.
+pub const DEFAULT_POSITION_PRECEDENCE: [&str; 4] = ["GGA", "GNS", "RMC", "GLL"];
+
+/// Default speed and track precedence within an epoch, highest first.
+pub const DEFAULT_SPEED_PRECEDENCE: [&str; 2] = ["RMC", "VTG"];
+
+/// Default altitude spread between contributing systems above which altitude accuracy is inflated.
+pub const DEFAULT_ALTITUDE_SPREAD_LIMIT_M: f64 = 20.0;
+
+/// Default distance from the median system position beyond which the advanced fusion drops a system.
+pub const DEFAULT_OUTLIER_THRESHOLD_M: f64 = 100.0;
+
+/// Default DOP below which a reported DOP is treated as missing.
+///
+/// Some receivers report 0.0 instead of leaving an unavailable DOP empty, which would give the
+/// system an enormous fusion weight.
+pub const DEFAULT_DOP_FLOOR: f64 = 0.3;
+
+/// Default DOP above which a reported DOP is treated as missing.
+pub const DEFAULT_DOP_CEILING: f64 = 50.0;
+
+/// Default movement gate for derived course, as a multiple of the fix accuracy.
+pub const DEFAULT_COURSE_GATE_FACTOR: f64 = 3.0;
+
+/// Default number of derived bearings averaged for the derived course.
+pub const DEFAULT_COURSE_WINDOW: usize = 3;
+
+/// Origin of a course-over-ground value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CourseSource {
+    /// Track angle reported by an RMC sentence
+    Rmc,
+    /// Computed from successive fused fixes (lower trust)
+    Derived,
+}
+
+/// Course over ground with the source it was taken from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Course {
+    /// True course in degrees (0-360)
+    pub degrees: f64,
+    /// Where the course came from
+    pub source: CourseSource,
+}
+
+/// Default maximum number of satellites tracked per system.
+pub const DEFAULT_MAX_SATELLITES_PER_SYSTEM: usize = 64;
+
+/// Factor applied to the GGA HDOP when fusion falls back to it for lack of GSA DOPs.
+///
+/// The GGA HDOP describes the whole solution rather than a single system, and comes without
+/// PDOP or VDOP, so it is trusted less than GSA-derived values.
+pub const GGA_HDOP_PENALTY: f64 = 1.5;
+
+/// Default maximum number of satellites tracked across all systems.
+pub const DEFAULT_MAX_SATELLITES_TOTAL: usize = 256;
+
+/// Age in seconds beyond which differential corrections are considered stale.
+pub const DGPS_MAX_AGE_S: f64 = 60.0;
+
+/// Factor applied to the system accuracies during fusion while a DGPS fix uses stale corrections.
+pub const STALE_DGPS_PENALTY: f64 = 2.0;
+
+/// Nominal horizontal accuracy in meters of an RTK solution with fixed integer ambiguities.
+pub const RTK_FIXED_ACCURACY_M: f64 = 0.02;
+
+/// Nominal horizontal accuracy in meters of an RTK solution with float ambiguities.
+pub const RTK_FLOAT_ACCURACY_M: f64 = 0.2;
+
+/// Receiver start classification inferred from how quickly satellite signals appeared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StartType {
+    /// Signals appeared immediately: the receiver had valid ephemeris, time and position
+    Hot,
+    /// Signals ramped up moderately: almanac and approximate time were available
+    Warm,
+    /// Signals ramped up slowly: the receiver searched the sky without prior knowledge
+    Cold,
+}
+
+/// Default PRNs a "GN" GSA attributes to NavIC, as assigned by NMEA 4.11.
+pub const DEFAULT_NAVIC_PRNS: RangeInclusive<u16> = 401..=414;
+
+/// Default tolerance for position sentences timestamped before the newest time seen.
+pub const DEFAULT_LATE_TOLERANCE: Duration = Duration::from_millis(50);
+
+/// Default longest signal ramp-up classified as a hot start.
+pub const DEFAULT_HOT_START_MAX: Duration = Duration::from_secs(3);
+
+/// Default longest signal ramp-up classified as a warm start.
+pub const DEFAULT_WARM_START_MAX: Duration = Duration::from_secs(20);
+
+/// Satellites with a reported SNR needed to consider the signal ramp-up complete.
+const RAMP_SATELLITES: usize = 4;
+
+/// Statistics for a single sentence source on a (possibly multiplexed) NMEA feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TalkerStats {
+    /// Source key: the TAG block `s:` source ID when present, otherwise the talker ID (e.g. "GP")
+    pub source: String,
+    /// Number of sentences received from this source
+    pub sentences: u64,
+    /// Number of sentences from this source whose checksum did not match
+    pub checksum_failures: u64,
+    /// Receive time of the last sentence from this source
+    pub last_seen: Option<Timestamp>,
+}
+
+/// FNV-1a hasher for the statistics counters, which are looked up for every sentence.
+///
+/// Their keys are two-letter talker IDs and three-letter sentence types, which SipHash takes
+/// longer to hash than the rest of the bookkeeping costs.
+#[derive(Debug, Clone, Copy)]
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
     }
+}
 
-    #[test]
-    fn test_feed_nmea_gga() {
-        let mut gnss = GnssData::new();
-        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
-        gnss.feed_nmea(gga);
-        assert_eq!(gnss.time, Some("123519".to_string()));
-        assert!(gnss.latitude.is_some());
-        assert!(gnss.longitude.is_some());
-        assert_eq!(gnss.fix_quality, Some(1));
-        assert_eq!(gnss.num_satellites, Some(8));
-        assert_eq!(gnss.altitude, Some(545.4));
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
     }
 
-    #[test]
-    fn test_feed_nmea_gps_gsv() {
-        let mut gnss = GnssData::new();
-        let gsv = "$GPGSV,2,1,08,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C";
-        gnss.feed_nmea(gsv);
-        let gps_info = &gnss.systems["GPS"].satellites_info;
-        assert_eq!(gps_info.len(), 4);
-        assert!(gps_info.contains_key(&1));
-        assert!(gps_info.contains_key(&2));
-        assert!(gps_info.contains_key(&3));
-        assert!(gps_info.contains_key(&4));
-        let sat1 = gps_info.get(&1).unwrap();
-        assert_eq!(sat1.prn, 1);
-        assert_eq!(sat1.elevation, Some(40));
-        assert_eq!(sat1.azimuth, Some(83));
-        assert_eq!(sat1.snr, Some(41));
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
     }
+}
 
-    #[test]
-    fn test_feed_nmea_glonass_gsv() {
-        let mut gnss = GnssData::new();
-        let gsv = "$GLGSV,2,1,08,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*61";
-        gnss.feed_nmea(gsv);
-        let glonass_info = &gnss.systems["GLONASS"].satellites_info;
-        assert_eq!(glonass_info.len(), 4);
-        assert!(glonass_info.contains_key(&67));
-        assert!(glonass_info.contains_key(&68));
-        assert!(glonass_info.contains_key(&69));
-        assert!(glonass_info.contains_key(&77));
-        let sat67 = glonass_info.get(&67).unwrap();
-        assert_eq!(sat67.prn, 67);
-        assert_eq!(sat67.elevation, Some(14));
-        assert_eq!(sat67.azimuth, Some(186));
-        assert_eq!(sat67.snr, Some(9));
+/// Map keyed by talker ID or sentence type, hashed with `FnvHasher`.
+type CounterMap<V> = HashMap<String, V, BuildHasherDefault<FnvHasher>>;
+
+/// Statistics collected while feeding NMEA sentences.
+#[derive(Debug, Default, Clone)]
+pub struct ParserStats {
+    /// Per-source statistics keyed by source
+    talkers: CounterMap<TalkerStats>,
+    /// Satellites evicted from `satellites_info` because a capacity limit was reached
+    satellite_evictions: u64,
+    /// Sentences dispatched to a handler, keyed by sentence type
+    sentence_counts: CounterMap<u64>,
+    /// Sentences no handler accepted
+    unhandled_sentences: u64,
+    /// Position sentences ignored because they were older than the newest time seen
+    late_sentences: u64,
+    /// Sentences dropped by checksum validation or strict framing
+    rejected_sentences: u64,
+    /// Sentences with bytes other than line terminators after the checksum
+    trailing_garbage_sentences: u64,
+    /// Time from acquisition start to the first fused fix
+    time_to_first_fix: Option<Duration>,
+    /// Start classification made at the first fused fix
+    start_type: Option<StartType>,
+}
+
+impl ParserStats {
+    /// Returns the per-source statistics sorted by source key.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// let talkers = gnss.get_stats().talkers();
+    /// assert_eq!(talkers[0].source, "GP");
+    /// assert_eq!(talkers[0].sentences, 1);
+    /// ```
+    pub fn talkers(&self) -> Vec<TalkerStats> {
+        let mut talkers: Vec<TalkerStats> = self.talkers.values().cloned().collect();
+        talkers.sort_by(|a, b| a.source.cmp(&b.source));
+        talkers
     }
 
-    #[test]
-    fn test_feed_nmea_galileo_gsv() {
+    /// Returns the number of satellites evicted because a capacity limit was reached.
+    pub fn satellite_evictions(&self) -> u64 {
+        self.satellite_evictions
+    }
+
+    /// Returns the number of sentences dispatched per sentence type.
+    ///
+    /// Every built-in sentence type is listed, in dispatch table order, even when none
+    /// was received; types handled by runtime-registered handlers follow, sorted by type.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// let counts = gnss.get_stats().sentence_counts();
+    /// assert!(counts.contains(&("GGA".to_string(), 1)));
+    /// assert!(counts.contains(&("RMC".to_string(), 0)));
+    /// ```
+    pub fn sentence_counts(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = SENTENCE_TABLE
+            .iter()
+            .map(|descriptor| (descriptor.sentence_type.to_string(), self.sentence_count(descriptor.sentence_type)))
+            .collect();
+        let mut registered: Vec<(String, u64)> = self
+            .sentence_counts
+            .iter()
+            .filter(|(sentence_type, _)| builtin_descriptor(sentence_type).is_none())
+            .map(|(sentence_type, count)| (sentence_type.clone(), *count))
+            .collect();
+        registered.sort();
+        counts.extend(registered);
+        counts
+    }
+
+    /// Returns the number of sentences of `sentence_type` (e.g. "GSV") dispatched to a handler.
+    pub fn sentence_count(&self, sentence_type: &str) -> u64 {
+        self.sentence_counts.get(sentence_type).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of sentences that no handler accepted.
+    pub fn unhandled_sentences(&self) -> u64 {
+        self.unhandled_sentences
+    }
+
+    /// Returns the number of position sentences ignored because they arrived after a newer epoch.
+    pub fn late_sentences(&self) -> u64 {
+        self.late_sentences
+    }
+
+    /// Returns the number of sentences dropped by checksum validation or strict framing.
+    pub fn rejected_sentences(&self) -> u64 {
+        self.rejected_sentences
+    }
+
+    /// Returns the number of sentences that carried bytes after their checksum, whether the
+    /// bytes were stripped or the sentence was rejected.
+    pub fn trailing_garbage_sentences(&self) -> u64 {
+        self.trailing_garbage_sentences
+    }
+
+    /// Returns the time from acquisition start to the first fused fix.
+    pub fn time_to_first_fix(&self) -> Option<Duration> {
+        self.time_to_first_fix
+    }
+
+    /// Returns the start classification made at the first fused fix.
+    pub fn start_type(&self) -> Option<StartType> {
+        self.start_type
+    }
+
+    /// Records one sentence received from `source`.
+    fn record_sentence(&mut self, source: &str, checksum_failed: bool, received_at: Timestamp) {
+        // Look up before inserting so known sources don't allocate a key per sentence
+        let entry = match self.talkers.get_mut(source) {
+            Some(entry) => entry,
+            None => self.talkers.entry(source.to_string()).or_insert(TalkerStats {
+                source: source.to_string(),
+                sentences: 0,
+                checksum_failures: 0,
+                last_seen: None,
+            }),
+        };
+        entry.sentences += 1;
+        if checksum_failed {
+            entry.checksum_failures += 1;
+        }
+        entry.last_seen = Some(received_at);
+    }
+
+    /// Records the outcome of dispatching one sentence; None means no handler accepted it.
+    fn record_dispatch(&mut self, sentence_type: Option<&str>) {
+        match sentence_type {
+            Some(sentence_type) => match self.sentence_counts.get_mut(sentence_type) {
+                Some(count) => *count += 1,
+                None => {
+                    self.sentence_counts.insert(sentence_type.to_string(), 1);
+                }
+            },
+            None => self.unhandled_sentences += 1,
+        }
+    }
+}
+
+/// Receiver capabilities inferred from the sentences fed so far, built by
+/// `GnssData::receiver_profile`.
+///
+/// `Display` gives a one-paragraph summary suitable for support tickets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiverProfile {
+    /// Systems that reported satellites in GSV or GSA sentences, in canonical order
+    pub constellations: Vec<GnssSystem>,
+    /// Sentence types dispatched at least once, in dispatch table order
+    pub sentence_types: Vec<String>,
+    /// Whether GSV sentences carried a signal ID (NMEA 4.10 and later)
+    pub signal_ids: bool,
+    /// Whether GSA sentences carried a system ID (NMEA 4.10 and later)
+    pub system_ids: bool,
+    /// Whether RMC, GLL or VTG sentences carried a mode indicator (NMEA 2.3 and later)
+    pub mode_indicators: bool,
+    /// Oldest NMEA version with every feature seen ("4.10" or "2.3"), or None if undetermined
+    pub min_nmea_version: Option<&'static str>,
+    /// Epochs per second, from the shortest interval seen between epoch times
+    pub update_rate_hz: Option<f64>,
+    /// Whether RTK float or fixed solutions were reported (GGA quality 4 or 5, GNS mode R or F)
+    pub rtk: bool,
+}
+
+impl std::fmt::Display for ReceiverProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let constellations: Vec<&str> = self.constellations.iter().map(GnssSystem::name).collect();
+        if constellations.is_empty() {
+            write!(f, "No constellations detected.")?;
+        } else {
+            write!(f, "Tracks {}.", join_list(&constellations))?;
+        }
+        let sentence_types: Vec<&str> = self.sentence_types.iter().map(String::as_str).collect();
+        if sentence_types.is_empty() {
+            write!(f, " No sentences recognized")?;
+        } else {
+            write!(f, " Emits {}", join_list(&sentence_types))?;
+        }
+        match self.update_rate_hz {
+            Some(rate) => write!(f, " at {} Hz.", (rate * 10.0).round() / 10.0)?,
+            None => write!(f, " at an unknown rate.")?,
+        }
+        let mut features = Vec::new();
+        if self.signal_ids {
+            features.push("GSV signal IDs");
+        }
+        if self.system_ids {
+            features.push("GSA system IDs");
+        }
+        if self.mode_indicators {
+            features.push("mode indicators");
+        }
+        match self.min_nmea_version {
+            Some(version) => write!(f, " NMEA {} or later ({} seen).", version, join_list(&features))?,
+            None => write!(f, " NMEA version undetermined.")?,
+        }
+        if self.rtk {
+            write!(f, " RTK solutions reported.")?;
+        }
+        Ok(())
+    }
+}
+
+/// Joins words as "a", "a and b" or "a, b and c".
+fn join_list(words: &[&str]) -> String {
+    match words.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/// Receiver features seen while parsing, summarized by `GnssData::receiver_profile`.
+#[derive(Debug, Default, Clone)]
+struct ObservedFeatures {
+    /// Systems that reported satellites
+    systems: Vec<GnssSystem>,
+    /// Whether a GSV sentence carried a signal ID
+    signal_ids: bool,
+    /// Whether a GSA sentence carried a system ID
+    system_ids: bool,
+    /// Whether an RMC, GLL or VTG sentence carried a mode indicator
+    mode_indicators: bool,
+    /// Whether an RTK float or fixed solution was reported
+    rtk: bool,
+    /// Shortest interval between consecutive epoch times in milliseconds
+    shortest_epoch_interval: Option<u32>,
+}
+
+impl ObservedFeatures {
+    /// Records a system that reported satellites.
+    fn observe_system(&mut self, system: Option<GnssSystem>) {
+        if let Some(system) = system.filter(|system| !self.systems.contains(system)) {
+            self.systems.push(system);
+        }
+    }
+
+    /// Records whether a mode indicator field is present.
+    fn observe_mode_indicator(&mut self, field: Option<&&str>) {
+        self.mode_indicators |= field.is_some_and(|mode| !mode.is_empty());
+    }
+
+    /// Records the interval between two consecutive epoch times in hhmmss.ss.
+    fn observe_epoch_interval(&mut self, previous: f64, current: f64) {
+        const DAY_MS: i64 = 86_400_000;
+        let millis = |time: f64| {
+            let hours = (time / 10_000.0).floor();
+            let minutes = (time / 100.0).floor() % 100.0;
+            let seconds = time % 100.0;
+            (((hours * 60.0 + minutes) * 60.0 + seconds) * 1_000.0).round() as i64
+        };
+        let interval = (millis(current) - millis(previous)).rem_euclid(DAY_MS);
+        // Gaps of a minute or more are outages, not the update rate
+        if interval > 0 && interval < 60_000 {
+            let interval = interval as u32;
+            self.shortest_epoch_interval = Some(self.shortest_epoch_interval.map_or(interval, |shortest| shortest.min(interval)));
+        }
+    }
+}
+
+/// Borrowed view of the fields of a sentence, passed to the callback of
+/// `GnssData::feed_nmea_with_fields`.
+///
+/// Field 0 is the address field (e.g. "GNGGA"). The `*hh` checksum and line ending are
+/// stripped from the last field.
+#[derive(Debug, Clone, Copy)]
+pub struct SentenceFields<'a> {
+    parts: &'a [&'a str],
+}
+
+impl<'a> SentenceFields<'a> {
+    /// Returns the address field: the talker ID followed by the sentence type.
+    pub fn address(&self) -> &'a str {
+        self.get(0).unwrap_or_default()
+    }
+
+    /// Returns a field by index.
+    ///
+    /// # Arguments
+    /// * `index` - Field index, with the address as field 0
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The field text (empty for an empty field), or None past the last field
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        self.parts.get(index).copied()
+    }
+
+    /// Returns the number of fields, address included.
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Checks whether the sentence has no fields at all.
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Iterates the fields in order, starting with the address.
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+/// Handler for a sentence type registered at runtime with `GnssData::register_sentence_handler`.
+///
+/// The handler receives the comma-separated fields of the sentence, starting with the address field.
+pub type CustomSentenceHandler = fn(&mut GnssData, &[&str]);
+
+/// How a built-in sentence handler is routed.
+#[derive(Clone, Copy)]
+enum SentenceHandler {
+    /// Per-constellation sentence, routed to the system named by the talker ID
+    PerSystem(fn(&mut GnssData, &[&str], &str)),
+    /// Sentence accepted from the "GN" talker (no system) or a constellation talker
+    CombinedOrPerSystem(fn(&mut GnssData, &[&str], Option<GnssSystem>)),
+    /// Receiver-wide sentence, accepted from any talker
+    Any(fn(&mut GnssData, &[&str])),
+}
+
+/// Describes one built-in sentence type in the dispatch table.
+#[derive(Clone, Copy)]
+struct SentenceDescriptor {
+    /// Three-letter sentence type following the talker ID (e.g. "GGA")
+    sentence_type: &'static str,
+    /// Handler and its talker routing
+    handler: SentenceHandler,
+    /// Number of fields the sentence type defines, address included
+    min_fields: usize,
+    /// Indexes of fields that must be numeric when not empty
+    numeric_fields: &'static [usize],
+}
+
+/// Builds `SENTENCE_TABLE` from `"TYPE" => Routing(handler) fields(n) numeric[i, ...]` entries.
+///
+/// `fields` is the field count the type defines and `numeric` lists the fields that must parse
+/// as numbers; both are only checked by `feed_nmea`. Entries may carry attributes such
+/// as `#[cfg(feature = "...")]` to gate a handler behind a feature; a gated-out entry disappears
+/// from dispatch and statistics alike.
+macro_rules! sentence_table {
+    ($($(#[$attr:meta])* $sentence_type:literal => $routing:ident($handler:path)
+        fields($min_fields:literal) numeric[$($numeric:literal),*]),* $(,)?) => {
+        /// Built-in sentence handlers, consulted before runtime-registered ones.
+        const SENTENCE_TABLE: &[SentenceDescriptor] = &[
+            $($(#[$attr])* SentenceDescriptor {
+                sentence_type: $sentence_type,
+                handler: SentenceHandler::$routing($handler),
+                min_fields: $min_fields,
+                numeric_fields: &[$($numeric),*],
+            },)*
+        ];
+    };
+}
+
+sentence_table! {
+    "GGA" => CombinedOrPerSystem(GnssData::update_gga) fields(15) numeric[1, 2, 4, 6, 7, 8, 9, 11, 13, 14],
+    "RMC" => CombinedOrPerSystem(GnssData::update_rmc) fields(12) numeric[1, 3, 5, 7, 8, 9, 10],
+    // Receivers predating NMEA 2.0 send the four VTG values without unit letters
+    "VTG" => CombinedOrPerSystem(GnssData::update_vtg) fields(5) numeric[1, 3, 5, 7],
+    "GSA" => CombinedOrPerSystem(GnssData::update_gsa)
+        fields(18) numeric[2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18],
+    "GNS" => CombinedOrPerSystem(GnssData::update_gns) fields(13) numeric[1, 2, 4, 7, 8, 9, 10, 11, 12],
+    // Up to four satellites of four fields each follow the three header fields
+    "GSV" => PerSystem(GnssData::update_gsv)
+        fields(4) numeric[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19],
+    "GLL" => CombinedOrPerSystem(GnssData::update_gll) fields(7) numeric[1, 3, 5],
+    "GST" => CombinedOrPerSystem(GnssData::update_gst) fields(9) numeric[1, 2, 3, 4, 5, 6, 7, 8],
+    "GBS" => CombinedOrPerSystem(GnssData::update_gbs) fields(9) numeric[1, 2, 3, 4, 5, 6, 7, 8],
+    "DTM" => CombinedOrPerSystem(GnssData::update_dtm) fields(9) numeric[3, 5, 7],
+    "ZDA" => Any(GnssData::update_zda) fields(7) numeric[1, 2, 3, 4, 5, 6],
+    "TXT" => Any(GnssData::update_txt) fields(5) numeric[1, 2, 3],
+    "HDT" => Any(GnssData::update_hdt) fields(3) numeric[1],
+    "THS" => Any(GnssData::update_ths) fields(3) numeric[1],
+}
+
+/// Number of fields of the longest GSV sentence: address, three header fields, four
+/// satellites of four fields each and an NMEA 4.10 signal ID.
+const MAX_GSV_FIELDS: usize = 21;
+
+/// Splits a sentence payload into its comma-separated fields.
+///
+/// Equivalent to `payload.split(',').collect()`, but sized up front and scanning bytes: with
+/// twenty short fields to a GSV sentence, the generic splitter and vector growth dominated
+/// the cost of parsing it.
+fn split_fields(payload: &str) -> Vec<&str> {
+    let mut fields = Vec::with_capacity(MAX_GSV_FIELDS);
+    let mut start = 0;
+    for (index, byte) in payload.bytes().enumerate() {
+        if byte == b',' {
+            fields.push(&payload[start..index]);
+            start = index + 1;
+        }
+    }
+    fields.push(&payload[start..]);
+    fields
+}
+
+/// Checks whether a field is empty or a short run of digits with at most one decimal point,
+/// the form nearly every NMEA number takes, without the cost of a float parse.
+fn is_plain_number(field: &str) -> bool {
+    let (mut digits, mut points) = (0, 0);
+    for byte in field.bytes() {
+        match byte {
+            b'0'..=b'9' => digits += 1,
+            b'.' => points += 1,
+            _ => return false,
+        }
+    }
+    field.is_empty() || (digits > 0 && digits <= 16 && points <= 1)
+}
+
+/// Looks up the built-in descriptor for a sentence type.
+fn builtin_descriptor(sentence_type: &str) -> Option<&'static SentenceDescriptor> {
+    SENTENCE_TABLE.iter().find(|descriptor| descriptor.sentence_type == sentence_type)
+}
+
+impl SentenceDescriptor {
+    /// Checks a sentence against the field count and numeric fields of its type.
+    ///
+    /// # Arguments
+    /// * `parts` - Sentence fields starting with the address
+    fn check_fields(&self, parts: &[&str]) -> Result<(), NmeaError> {
+        if parts.len() < self.min_fields {
+            return Err(NmeaError::MissingFields { expected: self.min_fields, found: parts.len() });
+        }
+        for &index in self.numeric_fields {
+            let Some(field) = parts.get(index) else {
+                break;
+            };
+            if !is_plain_number(field) && !field.parse::<f64>().is_ok_and(f64::is_finite) {
+                return Err(NmeaError::InvalidNumber { index });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How fusion treats positions dead-reckoned from motion sensors rather than fixed by GNSS.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DeadReckoningPolicy {
+    /// Fuse them like GNSS fixes; the fix quality still flags them
+    #[default]
+    Include,
+    /// Fuse them with the system accuracy multiplied by the factor
+    Inflate(f64),
+    /// Leave them out of the fused position
+    Exclude,
+}
+
+/// Order in which `FusedPosition::contributing_systems` lists the systems.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContributingOrder {
+    /// Canonical system order: GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC
+    #[default]
+    Canonical,
+    /// Alphabetical by system name
+    Name,
+    /// Best horizontal accuracy as weighted by fusion (HDOP times system accuracy) first,
+    /// ties in canonical order
+    Accuracy,
+}
+
+/// Status text reported by the receiver in a TXT sentence (antenna status, firmware, errors).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxtMessage {
+    /// Message type: 0 error, 1 warning, 2 notice, 7 user
+    pub severity: u8,
+    /// Message text
+    pub text: String,
+}
+
+impl TxtMessage {
+    /// Gets the severity level named by the message type field.
+    ///
+    /// # Returns
+    /// * `TxtSeverity` - The severity level, `Other` for codes outside the NMEA set
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{TxtMessage, TxtSeverity};
+    /// let message = TxtMessage { severity: 1, text: "ANTENNA OPEN".to_string() };
+    /// assert_eq!(message.level(), TxtSeverity::Warning);
+    /// ```
+    pub fn level(&self) -> TxtSeverity {
+        match self.severity {
+            0 => TxtSeverity::Error,
+            1 => TxtSeverity::Warning,
+            2 => TxtSeverity::Notice,
+            7 => TxtSeverity::User,
+            code => TxtSeverity::Other(code),
+        }
+    }
+}
+
+/// Severity level of a TXT message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TxtSeverity {
+    /// Message type 00
+    Error,
+    /// Message type 01
+    Warning,
+    /// Message type 02
+    Notice,
+    /// Message type 07
+    User,
+    /// Any other message type
+    Other(u8),
+}
+
+/// Antenna state reported by the receiver in a TXT message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AntennaStatus {
+    /// Antenna connected and powered
+    Ok,
+    /// Open circuit: antenna disconnected or cable cut
+    Open,
+    /// Short circuit on the antenna feed
+    Short,
+}
+
+impl AntennaStatus {
+    /// Recognizes the antenna status texts of common receivers, such as u-blox
+    /// "ANTENNA OPEN" / "ANTSTATUS=OPEN" and MediaTek "ANTENNA SHORTED".
+    ///
+    /// # Arguments
+    /// * `text` - Text of a TXT message
+    ///
+    /// # Returns
+    /// * `Option<AntennaStatus>` - The reported status, or None if the text is not an antenna
+    ///   status or reports an unknown state
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::AntennaStatus;
+    /// assert_eq!(AntennaStatus::from_text("ANTSTATUS=SHORT"), Some(AntennaStatus::Short));
+    /// assert_eq!(AntennaStatus::from_text("ANTSTATUS=INIT"), None);
+    /// ```
+    pub fn from_text(text: &str) -> Option<Self> {
+        let text = text.to_ascii_uppercase();
+        // Whole words only, so that "ANTENNA BROKEN" does not read as "OK"
+        let words: Vec<&str> = text.split(|c: char| !c.is_ascii_alphanumeric()).filter(|word| !word.is_empty()).collect();
+        let has = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+        if !has(&["ANTENNA", "ANTSTATUS"]) {
+            return None;
+        }
+        if has(&["SHORT", "SHORTED"]) {
+            Some(AntennaStatus::Short)
+        } else if has(&["OPEN", "OPENED"]) {
+            Some(AntennaStatus::Open)
+        } else if has(&["OK", "GOOD"]) {
+            Some(AntennaStatus::Ok)
+        } else {
+            None
+        }
+    }
+}
+
+/// Leading parts of a TXT message split over several sentences.
+#[derive(Debug, Clone)]
+struct PendingTxt {
+    /// Number of sentences in the message
+    total: u8,
+    /// Number of the last sentence received
+    received: u8,
+    /// Message type of the first sentence
+    severity: u8,
+    /// Text assembled so far
+    text: String,
+}
+
+/// Pseudorange error statistics reported by a GST sentence, as 1-sigma values in meters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorStatistics {
+    /// UTC time of the epoch the statistics describe (hhmmss.ss)
+    pub time: Option<String>,
+    /// RMS value of the standard deviation of the range inputs, including pseudoranges and
+    /// DGNSS corrections
+    pub rms_residual: Option<f64>,
+    /// Standard deviation of the latitude error
+    pub sigma_latitude: Option<f64>,
+    /// Standard deviation of the longitude error
+    pub sigma_longitude: Option<f64>,
+    /// Standard deviation of the altitude error
+    pub sigma_altitude: Option<f64>,
+}
+
+impl ErrorStatistics {
+    /// Returns the horizontal accuracy implied by the latitude and longitude sigmas.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - `sqrt(sigma_latitude² + sigma_longitude²)` in meters, or None if either is missing
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::ErrorStatistics;
+    /// let stats = ErrorStatistics {
+    ///     time: Some("123519".to_string()),
+    ///     rms_residual: Some(1.2),
+    ///     sigma_latitude: Some(0.3),
+    ///     sigma_longitude: Some(0.4),
+    ///     sigma_altitude: Some(0.9),
+    /// };
+    /// assert!((stats.horizontal_sigma().unwrap() - 0.5).abs() < 1e-12);
+    /// ```
+    pub fn horizontal_sigma(&self) -> Option<f64> {
+        Some(self.sigma_latitude?.hypot(self.sigma_longitude?))
+    }
+}
+
+/// Receiver autonomous integrity monitoring (RAIM) report from a GBS sentence.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegrityInfo {
+    /// UTC time of the epoch the report describes (hhmmss.ss)
+    pub time: Option<String>,
+    /// Expected error in latitude in meters
+    pub latitude_error: Option<f64>,
+    /// Expected error in longitude in meters
+    pub longitude_error: Option<f64>,
+    /// Expected error in altitude in meters
+    pub altitude_error: Option<f64>,
+    /// PRN of the most likely failed satellite, or None if no satellite is flagged
+    pub failed_prn: Option<u16>,
+    /// System of the most likely failed satellite
+    pub failed_system: Option<GnssSystem>,
+    /// Probability of missed detection for the most likely failed satellite
+    pub missed_detection_probability: Option<f64>,
+    /// Estimated bias of the most likely failed satellite in meters
+    pub bias: Option<f64>,
+    /// Standard deviation of the bias estimate in meters
+    pub bias_std: Option<f64>,
+}
+
+/// Geodetic datum announced by a DTM sentence.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DatumInfo {
+    /// Datum the positions are reported in ("W84", "W72", "S85", "P90", "999" for user defined,
+    /// or an IHO datum code)
+    pub local_datum: String,
+    /// Subdivision code of the local datum
+    pub subdivision: Option<String>,
+    /// Latitude offset of the local datum from the reference datum in decimal degrees, positive north
+    pub latitude_offset: Option<f64>,
+    /// Longitude offset of the local datum from the reference datum in decimal degrees, positive east
+    pub longitude_offset: Option<f64>,
+    /// Altitude offset of the local datum from the reference datum in meters
+    pub altitude_offset: Option<f64>,
+    /// Datum the offsets are relative to (normally "W84")
+    pub reference_datum: String,
+}
+
+impl DatumInfo {
+    /// Checks whether positions are reported in WGS84.
+    pub fn is_wgs84(&self) -> bool {
+        self.local_datum == "W84"
+    }
+
+    /// Converts a position reported in the local datum to WGS84 by removing the offsets.
+    ///
+    /// # Arguments
+    /// * `latitude` - Reported latitude in decimal degrees
+    /// * `longitude` - Reported longitude in decimal degrees
+    /// * `altitude` - Reported altitude in meters, if any
+    ///
+    /// # Returns
+    /// * `Option<(f64, f64, Option<f64>)>` - The WGS84 latitude, longitude and altitude, or None
+    ///   unless the datum is WGS84 or a reported offset from it; the altitude is None without an
+    ///   altitude offset
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::DatumInfo;
+    /// let datum = DatumInfo {
+    ///     local_datum: "W72".to_string(),
+    ///     subdivision: None,
+    ///     latitude_offset: Some(0.001),
+    ///     longitude_offset: Some(-0.002),
+    ///     altitude_offset: Some(4.5),
+    ///     reference_datum: "W84".to_string(),
+    /// };
+    /// let (latitude, longitude, altitude) = datum.to_wgs84(48.0, 11.0, Some(545.4)).unwrap();
+    /// assert!((latitude - 47.999).abs() < 1e-9 && (longitude - 11.002).abs() < 1e-9);
+    /// assert!((altitude.unwrap() - 540.9).abs() < 1e-9);
+    /// ```
+    pub fn to_wgs84(&self, latitude: f64, longitude: f64, altitude: Option<f64>) -> Option<(f64, f64, Option<f64>)> {
+        if self.is_wgs84() {
+            return Some((latitude, longitude, altitude));
+        }
+        if self.reference_datum != "W84" {
+            return None;
+        }
+        Some((
+            latitude - self.latitude_offset?,
+            longitude - self.longitude_offset?,
+            altitude.zip(self.altitude_offset).map(|(altitude, offset)| altitude - offset),
+        ))
+    }
+}
+
+/// How fusion treats a system whose positions a DTM sentence reports in a datum other than WGS84.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DatumPolicy {
+    /// Convert the positions to WGS84 with the DTM offsets; systems whose datum has no offsets
+    /// from WGS84 are left out
+    #[default]
+    Convert,
+    /// Leave the systems out of the fused position
+    Exclude,
+}
+
+/// How fusion treats a system whose satellite a GBS sentence flags as failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FaultPolicy {
+    /// Fuse the system as usual; `GnssData::integrity` still reports the fault
+    #[default]
+    Include,
+    /// Fuse the system with its accuracy multiplied by the factor
+    Inflate(f64),
+    /// Leave the system out of the fused position
+    Exclude,
+}
+
+/// Fused position result from multiple GNSS systems.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FusedPosition {
+    /// Fused latitude in decimal degrees
+    pub latitude: f64,
+    /// Fused longitude in decimal degrees
+    pub longitude: f64,
+    /// Fused altitude above mean sea level in meters; alias of `altitude_msl`
+    #[deprecated(note = "use `altitude_msl`, or `altitude_ellipsoidal` for the height above the ellipsoid")]
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_finite", deserialize_with = "deserialize_or_nan"))]
+    pub altitude: f64,
+    /// Fused altitude above mean sea level in meters (NaN if no contributing system reported
+    /// an altitude and none is held; serialized as null)
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_finite", deserialize_with = "deserialize_or_nan"))]
+    pub altitude_msl: f64,
+    /// Fused height above the WGS84 ellipsoid in meters, if the geoid separation is known
+    pub altitude_ellipsoidal: Option<f64>,
+    /// Horizontal accuracy in meters of the filtered position; equal to `instantaneous_accuracy`
+    /// unless a position filter is set
+    pub estimated_accuracy: f64,
+    /// Horizontal accuracy in meters of this epoch alone, from its DOPs or GST sigmas
+    pub instantaneous_accuracy: f64,
+    /// Estimated altitude accuracy in meters (infinite if the altitude is unknown or only
+    /// assumed by a 2D fix; serialized as null)
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_finite", deserialize_with = "deserialize_or_infinity"))]
+    pub altitude_accuracy: f64,
+    /// Whether the altitude was held from the last 3D epoch instead of being recomputed
+    pub altitude_held: bool,
+    /// Where the altitude comes from
+    pub altitude_source: AltitudeSource,
+    /// Contributing GNSS systems, ordered as configured with `set_contributing_order`
+    /// (canonical order GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC by default)
+    pub contributing_systems: Vec<String>,
+    /// UTC time of the epoch this fix was computed for (hhmmss.ss)
+    pub time: Option<String>,
+    /// Best fix quality among the contributing systems
+    pub fix_quality: Option<FixQuality>,
+    /// True heading in degrees reported by HDT or THS, if any
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub heading: Option<f64>,
+}
+
+impl FusedPosition {
+    /// Returns latitude and longitude as integers in units of 1e-7 degrees.
+    ///
+    /// This is the fixed-point representation used by autopilot protocols such as MAVLink.
+    /// Values are rounded to the nearest unit.
+    ///
+    /// # Returns
+    /// * `Option<(i32, i32)>` - Scaled latitude and longitude, or None if a value is not finite or out of `i32` range
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{AltitudeSource, FusedPosition};
+    /// let fused = FusedPosition {
+    ///     latitude: 48.1173,
+    ///     longitude: -11.5166667,
+    ///     altitude: 545.4,
+    ///     altitude_msl: 545.4,
+    ///     altitude_ellipsoidal: None,
+    ///     estimated_accuracy: 2.0,
+    ///     instantaneous_accuracy: 2.0,
+    ///     altitude_accuracy: 3.0,
+    ///     altitude_held: false,
+    ///     altitude_source: AltitudeSource::Measured3D,
+    ///     contributing_systems: vec!["GPS".to_string()],
+    ///     time: Some("123519".to_string()),
+    ///     fix_quality: None,
+    ///     heading: None,
+    /// };
+    /// assert_eq!(fused.position_microdegrees(), Some((481_173_000, -115_166_667)));
+    /// assert_eq!(fused.altitude_mm(), Some(545_400));
+    /// ```
+    pub fn position_microdegrees(&self) -> Option<(i32, i32)> {
+        Some((scale_to_i32(self.latitude, 1e7)?, scale_to_i32(self.longitude, 1e7)?))
+    }
+
+    /// Returns the altitude above mean sea level in millimeters.
+    ///
+    /// # Returns
+    /// * `Option<i32>` - Rounded altitude in millimeters, or None if not finite or out of `i32` range
+    pub fn altitude_mm(&self) -> Option<i32> {
+        scale_to_i32(self.altitude_msl, 1e3)
+    }
+
+    /// Returns a copy with coordinates snapped to a grid coarse enough for the given accuracy.
+    ///
+    /// This is meant for sharing positions publicly. The grid cells are squares in meters, so the
+    /// degradation is the same in every direction: the longitude spacing is widened by
+    /// 1 / cos(latitude) of the snapped latitude. Cells measure `to_accuracy_m * √2` on a side, so
+    /// snapping moves a position by at most `to_accuracy_m`. Both horizontal accuracies are
+    /// raised to `to_accuracy_m` if they were better. The altitude is left untouched.
+    ///
+    /// # Arguments
+    /// * `to_accuracy_m` - Horizontal accuracy in meters to degrade to; values that are not
+    ///   finite and positive return the position unchanged
+    ///
+    /// # Returns
+    /// * `FusedPosition` - The degraded position
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{AltitudeSource, FusedPosition};
+    /// let fused = FusedPosition {
+    ///     latitude: 48.1173,
+    ///     longitude: 11.5166667,
+    ///     altitude: 545.4,
+    ///     altitude_msl: 545.4,
+    ///     altitude_ellipsoidal: None,
+    ///     estimated_accuracy: 2.0,
+    ///     instantaneous_accuracy: 2.0,
+    ///     altitude_accuracy: 3.0,
+    ///     altitude_held: false,
+    ///     altitude_source: AltitudeSource::Measured3D,
+    ///     contributing_systems: vec!["GPS".to_string()],
+    ///     time: Some("123519".to_string()),
+    ///     fix_quality: None,
+    ///     heading: None,
+    /// };
+    /// let shared = fused.degraded(1000.0);
+    /// assert_eq!(shared.estimated_accuracy, 1000.0);
+    /// assert!((shared.latitude - fused.latitude).abs() < 0.01);
+    /// ```
+    pub fn degraded(&self, to_accuracy_m: f64) -> FusedPosition {
+        let mut degraded = self.clone();
+        if !(to_accuracy_m.is_finite() && to_accuracy_m > 0.0) {
+            return degraded;
+        }
+        let spacing_deg = (to_accuracy_m * std::f64::consts::SQRT_2 / EARTH_RADIUS_M).to_degrees();
+        degraded.latitude = ((self.latitude / spacing_deg).round() * spacing_deg).clamp(-90.0, 90.0);
+        let lon_spacing_deg = spacing_deg / degraded.latitude.to_radians().cos();
+        degraded.longitude = if lon_spacing_deg.is_finite() && lon_spacing_deg < 360.0 {
+            let snapped = (self.longitude / lon_spacing_deg).round() * lon_spacing_deg;
+            // Snapping past the antimeridian wraps around to the other side
+            (snapped + 540.0).rem_euclid(360.0) - 180.0
+        } else {
+            // At the poles all longitudes collapse to a single cell
+            0.0
+        };
+        degraded.estimated_accuracy = self.estimated_accuracy.max(to_accuracy_m);
+        degraded.instantaneous_accuracy = self.instantaneous_accuracy.max(to_accuracy_m);
+        degraded
+    }
+
+    /// Returns the latitude in degrees, minutes and seconds, such as `48°07'02.3"N`.
+    ///
+    /// See `to_dms` for the format.
+    pub fn latitude_dms(&self) -> String {
+        to_dms(self.latitude, true)
+    }
+
+    /// Returns the longitude in degrees, minutes and seconds, such as `11°31'00.0"E`.
+    ///
+    /// See `to_dms` for the format.
+    pub fn longitude_dms(&self) -> String {
+        to_dms(self.longitude, false)
+    }
+
+    /// Returns the great-circle distance to another position, ignoring altitude.
+    ///
+    /// The haversine formula on a spherical Earth of mean radius is accurate to about 0.5%,
+    /// far below GNSS noise over the distances between successive fixes.
+    ///
+    /// # Arguments
+    /// * `other` - The position to measure to
+    ///
+    /// # Returns
+    /// * `f64` - Distance in meters (0 for identical positions)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*21").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// gnss.calculate_fused_position();
+    /// let start = gnss.fused_position().cloned().unwrap();
+    /// let mut end = start.clone();
+    /// end.latitude += 0.01;
+    /// assert!((start.distance_to(&end) - 1112.0).abs() < 1.0);
+    /// assert_eq!(start.bearing_to(&end), 0.0);
+    /// ```
+    pub fn distance_to(&self, other: &FusedPosition) -> f64 {
+        haversine_distance(self.latitude, self.longitude, other.latitude, other.longitude)
+    }
+
+    /// Returns the initial great-circle bearing to another position.
+    ///
+    /// Paths across the antimeridian take the short way around. The bearing to an identical
+    /// position is 0.
+    ///
+    /// # Arguments
+    /// * `other` - The position to head to
+    ///
+    /// # Returns
+    /// * `f64` - Bearing in degrees clockwise from true north, in [0, 360)
+    pub fn bearing_to(&self, other: &FusedPosition) -> f64 {
+        initial_bearing(self.latitude, self.longitude, other.latitude, other.longitude)
+    }
+}
+
+/// Diagnostics of the last fusion epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusionDiagnostics {
+    /// Per contributing system, its altitude minus the fused altitude in meters, sorted by system
+    pub altitude_residuals: Vec<(String, f64)>,
+    /// Spread between the highest and lowest contributing system altitude in meters
+    pub altitude_spread: f64,
+    /// Weight the position filter gave this epoch's fix, from 0 to 1 (None without a filter)
+    pub filter_gain: Option<f64>,
+}
+
+/// UTC time of day parsed from an NMEA hhmmss.ss field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcTime {
+    /// Hour (0-23)
+    pub hour: u8,
+    /// Minute (0-59)
+    pub minute: u8,
+    /// Second (0-60, 60 during a leap second)
+    pub second: u8,
+    /// Millisecond (0-999)
+    pub millis: u16,
+}
+
+impl UtcTime {
+    /// Returns the milliseconds since midnight.
+    fn millis_of_day(&self) -> u32 {
+        ((u32::from(self.hour) * 60 + u32::from(self.minute)) * 60 + u32::from(self.second)) * 1_000
+            + u32::from(self.millis)
+    }
+}
+
+impl std::fmt::Display for UtcTime {
+    /// Formats as ISO 8601, e.g. `12:35:19.500`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}.{:03}", self.hour, self.minute, self.second, self.millis)
+    }
+}
+
+/// UTC date and time of an epoch, combined from the date and time sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcDateTime {
+    /// Year, e.g. 2024
+    pub year: u16,
+    /// Month (1-12)
+    pub month: u8,
+    /// Day of the month (1-31)
+    pub day: u8,
+    /// Hour (0-23)
+    pub hour: u8,
+    /// Minute (0-59)
+    pub minute: u8,
+    /// Second (0-60, 60 during a leap second)
+    pub second: u8,
+    /// Millisecond (0-999)
+    pub millis: u16,
+}
+
+impl UtcDateTime {
+    /// Returns the milliseconds elapsed since the Unix epoch (1970-01-01T00:00:00Z).
+    ///
+    /// A leap second counts as the first second of the following minute.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::UtcDateTime;
+    /// let t = UtcDateTime { year: 2000, month: 1, day: 1, hour: 0, minute: 0, second: 1, millis: 500 };
+    /// assert_eq!(t.unix_millis(), 946_684_801_500);
+    /// ```
+    pub fn unix_millis(&self) -> i64 {
+        let days = days_from_civil(i64::from(self.year), i64::from(self.month), i64::from(self.day));
+        let seconds = days * 86_400 + i64::from(self.hour) * 3_600 + i64::from(self.minute) * 60 + i64::from(self.second);
+        seconds * 1_000 + i64::from(self.millis)
+    }
+}
+
+impl std::fmt::Display for UtcDateTime {
+    /// Formats as ISO 8601, e.g. `2002-07-04T20:15:30.000Z`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second, self.millis
+        )
+    }
+}
+
+/// Smoothed offset between host receive time and the NMEA epoch time.
+///
+/// The offset approximates serial latency plus the receiver's output delay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingEstimate {
+    /// Smoothed `host_receive_time - nmea_epoch_time` in seconds
+    pub offset: f64,
+    /// Smoothed mean deviation of the samples from the estimate in seconds
+    pub jitter: f64,
+    /// Number of epochs measured
+    pub samples: u64,
+}
+
+impl TimingEstimate {
+    /// Folds one offset sample into the estimate.
+    ///
+    /// The offset uses an exponential average with gain 1/8, the jitter the interarrival
+    /// jitter filter of RFC 3550 with gain 1/16.
+    fn update(&mut self, sample: f64) {
+        let deviation = (sample - self.offset).abs();
+        self.offset += (sample - self.offset) / 8.0;
+        self.jitter += (deviation - self.jitter) / 16.0;
+        self.samples += 1;
+    }
+}
+
+/// Satellite geometry summary computed from tracked elevations and azimuths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeometrySummary {
+    /// Number of satellites remaining after applying the mask and exclusions
+    pub remaining_sats: usize,
+    /// Horizontal Dilution of Precision computed from the remaining geometry
+    pub computed_hdop: Option<f64>,
+    /// Vertical Dilution of Precision computed from the remaining geometry
+    pub computed_vdop: Option<f64>,
+}
+
+/// Point-in-time copy of the reported solution, used to detect changes between epochs.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GnssSnapshot {
+    /// UTC time of the epoch (hhmmss.ss)
+    pub time: Option<String>,
+    /// Latitude in decimal degrees
+    pub latitude: Option<f64>,
+    /// Longitude in decimal degrees
+    pub longitude: Option<f64>,
+    /// Altitude above mean sea level in meters
+    pub altitude: Option<f64>,
+    /// Number of satellites used for the fix
+    pub num_satellites: Option<u8>,
+    /// Position Dilution of Precision
+    pub pdop: Option<f64>,
+    /// Horizontal Dilution of Precision
+    pub hdop: Option<f64>,
+    /// Vertical Dilution of Precision
+    pub vdop: Option<f64>,
+    /// Fix quality
+    pub fix_quality: Option<FixQuality>,
+}
+
+/// Tolerances below which a float difference between snapshots is treated as jitter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffTolerance {
+    /// Horizontal movement in meters
+    pub position_m: f64,
+    /// Altitude change in meters
+    pub altitude_m: f64,
+    /// DOP change (applies to PDOP, HDOP and VDOP)
+    pub dop: f64,
+}
+
+impl Default for DiffTolerance {
+    fn default() -> Self {
+        Self { position_m: 0.5, altitude_m: 0.5, dop: 0.1 }
+    }
+}
+
+/// A single field that changed between two snapshots, with its previous and current value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FieldChange {
+    /// Latitude and longitude moved beyond the position tolerance
+    Position { old: Option<(f64, f64)>, new: Option<(f64, f64)> },
+    /// Altitude changed beyond the altitude tolerance
+    Altitude { old: Option<f64>, new: Option<f64> },
+    /// Number of satellites used changed
+    NumSatellites { old: Option<u8>, new: Option<u8> },
+    /// PDOP changed beyond the DOP tolerance
+    Pdop { old: Option<f64>, new: Option<f64> },
+    /// HDOP changed beyond the DOP tolerance
+    Hdop { old: Option<f64>, new: Option<f64> },
+    /// VDOP changed beyond the DOP tolerance
+    Vdop { old: Option<f64>, new: Option<f64> },
+    /// Fix quality transitioned
+    FixQuality { old: Option<FixQuality>, new: Option<FixQuality> },
+}
+
+/// Fields that changed between two snapshots, in `GnssSnapshot` field order.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotDiff {
+    /// Changed fields
+    pub changes: Vec<FieldChange>,
+}
+
+impl SnapshotDiff {
+    /// Checks whether no field changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl GnssSnapshot {
+    /// Lists the fields that changed since `previous`, using the default tolerances.
+    ///
+    /// The epoch time is not compared, since it changes every epoch.
+    ///
+    /// # Arguments
+    /// * `previous` - The snapshot of the previous epoch
+    ///
+    /// # Returns
+    /// * `SnapshotDiff` - The changed fields with old and new values
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{FieldChange, GnssSnapshot};
+    /// let previous = GnssSnapshot { num_satellites: Some(8), ..Default::default() };
+    /// let current = GnssSnapshot { num_satellites: Some(9), ..Default::default() };
+    /// let diff = current.diff(&previous);
+    /// assert_eq!(diff.changes, vec![FieldChange::NumSatellites { old: Some(8), new: Some(9) }]);
+    /// ```
+    pub fn diff(&self, previous: &GnssSnapshot) -> SnapshotDiff {
+        self.diff_with(previous, &DiffTolerance::default())
+    }
+
+    /// Lists the fields that changed since `previous`, using custom tolerances.
+    ///
+    /// A value appearing or disappearing always counts as a change.
+    ///
+    /// # Arguments
+    /// * `previous` - The snapshot of the previous epoch
+    /// * `tolerance` - Differences at or below these tolerances are ignored
+    ///
+    /// # Returns
+    /// * `SnapshotDiff` - The changed fields with old and new values
+    pub fn diff_with(&self, previous: &GnssSnapshot, tolerance: &DiffTolerance) -> SnapshotDiff {
+        let mut changes = Vec::new();
+
+        let old_position = previous.latitude.zip(previous.longitude);
+        let new_position = self.latitude.zip(self.longitude);
+        let position_changed = match (old_position, new_position) {
+            (Some((lat1, lon1)), Some((lat2, lon2))) => {
+                haversine_distance(lat1, lon1, lat2, lon2) > tolerance.position_m
+            }
+            (old, new) => old.is_some() != new.is_some(),
+        };
+        if position_changed {
+            changes.push(FieldChange::Position { old: old_position, new: new_position });
+        }
+        if float_changed(previous.altitude, self.altitude, tolerance.altitude_m) {
+            changes.push(FieldChange::Altitude { old: previous.altitude, new: self.altitude });
+        }
+        if previous.num_satellites != self.num_satellites {
+            changes.push(FieldChange::NumSatellites { old: previous.num_satellites, new: self.num_satellites });
+        }
+        if float_changed(previous.pdop, self.pdop, tolerance.dop) {
+            changes.push(FieldChange::Pdop { old: previous.pdop, new: self.pdop });
+        }
+        if float_changed(previous.hdop, self.hdop, tolerance.dop) {
+            changes.push(FieldChange::Hdop { old: previous.hdop, new: self.hdop });
+        }
+        if float_changed(previous.vdop, self.vdop, tolerance.dop) {
+            changes.push(FieldChange::Vdop { old: previous.vdop, new: self.vdop });
+        }
+        if previous.fix_quality != self.fix_quality {
+            changes.push(FieldChange::FixQuality { old: previous.fix_quality, new: self.fix_quality });
+        }
+
+        SnapshotDiff { changes }
+    }
+}
+
+/// State of one system at the end of an epoch, as recorded in `EpochData`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemEpoch {
+    /// System name (e.g. "GPS")
+    pub system: String,
+    /// Latitude in decimal degrees
+    pub latitude: Option<f64>,
+    /// Longitude in decimal degrees
+    pub longitude: Option<f64>,
+    /// Altitude in meters, referenced to the system's `altitude_datum`
+    pub altitude: Option<f64>,
+    /// Position Dilution of Precision
+    pub pdop: Option<f64>,
+    /// Horizontal Dilution of Precision
+    pub hdop: Option<f64>,
+    /// Vertical Dilution of Precision
+    pub vdop: Option<f64>,
+    /// PRNs used for the fix
+    pub satellites_used: Vec<u16>,
+    /// Tracked satellites in PRN order, or None if satellite snapshots are elided
+    pub satellites: Option<Vec<SatelliteInfo>>,
+}
+
+/// Everything known about one epoch, sealed when the next epoch starts.
+///
+/// Records are kept by `GnssData` once `set_epoch_recording` is enabled and drained with
+/// `take_epochs`. With the `serde` feature they serialize one record per line as NDJSON.
+///
+/// A record takes about 250 bytes, plus 180 bytes per system, 2 bytes per used satellite,
+/// 56 bytes per warning and, unless satellite snapshots are elided, 10 bytes per tracked
+/// satellite: about 1.4 kB for six systems without satellite snapshots.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EpochData {
+    /// UTC time of the epoch (hhmmss.ss)
+    pub time: Option<String>,
+    /// Latitude reported by the receiver in decimal degrees
+    pub latitude: Option<f64>,
+    /// Longitude reported by the receiver in decimal degrees
+    pub longitude: Option<f64>,
+    /// Altitude above mean sea level reported by the receiver in meters
+    pub altitude: Option<f64>,
+    /// Fix quality reported by GGA
+    pub fix_quality: Option<FixQuality>,
+    /// Number of satellites used for the fix
+    pub num_satellites: Option<u8>,
+    /// Per-system state, in `GnssSystem::ALL` order
+    pub systems: Vec<SystemEpoch>,
+    /// Data-quality warnings raised during the epoch
+    pub warnings: Vec<DataWarning>,
+    /// Fused position calculated for the epoch, if any
+    pub fused_position: Option<FusedPosition>,
+}
+
+/// Builds a `GnssData` that tracks only the selected constellations.
+///
+/// Sentences for systems that are not enabled are ignored, and fusion and the fused accuracy
+/// consider only the enabled systems. `GnssData::new` enables every supported system.
+///
+/// # Example
+/// ```
+/// use nema_parser::gnss_multignss_parser::{GnssDataBuilder, GnssSystem};
+/// let gnss = GnssDataBuilder::new()
+///     .with_system(GnssSystem::Gps, 2.0)
+///     .with_system(GnssSystem::Galileo, 3.0)
+///     .build();
+/// assert_eq!(gnss.get_system_fixed_accuracy("GALILEO"), Some(3.0));
+/// assert!(gnss.system("GLONASS").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GnssDataBuilder {
+    /// Enabled systems with their fixed accuracies in meters, in the order they were added
+    systems: Vec<(GnssSystem, f64)>,
+}
+
+impl GnssDataBuilder {
+    /// Creates a builder with no systems enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables a system with its fixed accuracy.
+    ///
+    /// Enabling a system again replaces its accuracy. Use `GnssSystem::from_name` to enable a
+    /// system by name.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system to enable
+    /// * `accuracy` - The system's fixed accuracy in meters
+    pub fn with_system(mut self, system: GnssSystem, accuracy: f64) -> Self {
+        self.systems.retain(|(enabled, _)| *enabled != system);
+        self.systems.push((system, accuracy));
+        self
+    }
+
+    /// Creates the `GnssData` with the enabled systems and the default configuration.
+    pub fn build(self) -> GnssData {
+        let systems = self.systems.into_iter()
+            .map(|(system, accuracy)| (system.name(), GnssSystemData { fixed_accuracy: accuracy, accuracy, ..Default::default() }))
+            .collect();
+
+        GnssData {
+            systems,
+            max_satellites_per_system: DEFAULT_MAX_SATELLITES_PER_SYSTEM,
+            max_satellites_total: DEFAULT_MAX_SATELLITES_TOTAL,
+            course_gate_factor: DEFAULT_COURSE_GATE_FACTOR,
+            course_window: DEFAULT_COURSE_WINDOW,
+            acquisition_start: Some(SystemClock.now()),
+            hot_start_max: DEFAULT_HOT_START_MAX,
+            warm_start_max: DEFAULT_WARM_START_MAX,
+            decimal_separator: '.',
+            altitude_spread_limit: DEFAULT_ALTITUDE_SPREAD_LIMIT_M,
+            outlier_threshold: DEFAULT_OUTLIER_THRESHOLD_M,
+            dop_floor: DEFAULT_DOP_FLOOR,
+            dop_ceiling: DEFAULT_DOP_CEILING,
+            late_tolerance: DEFAULT_LATE_TOLERANCE,
+            position_precedence: DEFAULT_POSITION_PRECEDENCE.iter().map(|s| s.to_string()).collect(),
+            speed_precedence: DEFAULT_SPEED_PRECEDENCE.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl GnssData {
+    /// Creates a new `GnssData` instance with all supported GNSS systems initialized.
+    ///
+    /// Use `GnssDataBuilder` to enable only some systems.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    ///
+    /// let gnss = GnssData::new();
+    /// ```
+    pub fn new() -> Self {
+        GnssDataBuilder::new()
+            .with_system(GnssSystem::Gps, 2.0)
+            .with_system(GnssSystem::Glonass, 4.0)
+            .with_system(GnssSystem::Galileo, 3.0)
+            .with_system(GnssSystem::Beidou, 3.0)
+            .with_system(GnssSystem::Qzss, 3.0)
+            .with_system(GnssSystem::Navic, 4.0)
+            .build()
+    }
+
+    /// Clears all parsed data, statistics and warnings, keeping the configuration.
+    ///
+    /// Acquisition restarts now, so `time_to_first_fix` measures from this call.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_warmup_epochs(2);
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// gnss.reset();
+    /// assert_eq!(gnss.latitude(), None);
+    /// assert_eq!(gnss.get_warmup_epochs(), 2);
+    /// ```
+    pub fn reset(&mut self) {
+        self.reset_at(self.clock.0.now());
+    }
+
+    /// Sets the clock that stamps sentences fed without an explicit receive time.
+    ///
+    /// Acquisition restarts at the clock's current time, so `time_to_first_fix` is measured
+    /// on the new clock. The clock is kept by `reset` and shared with clones.
+    ///
+    /// # Arguments
+    /// * `clock` - The time source, `SystemClock` by default
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use nema_parser::gnss_multignss_parser::{GnssData, ManualClock};
+    /// let clock = ManualClock::new(UNIX_EPOCH);
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_clock(clock.clone());
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// clock.advance(Duration::from_secs(3));
+    /// assert_eq!(gnss.gsv_cycle_age("GPS", gnss.now()), Some(Duration::from_secs(3)));
+    /// ```
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.acquisition_start = Some(clock.now());
+        self.clock = SharedClock(Arc::new(clock));
+    }
+
+    /// Returns the current time of the clock set with `set_clock`.
+    ///
+    /// # Returns
+    /// * `Timestamp` - The clock's current time
+    pub fn now(&self) -> Timestamp {
+        self.clock.0.now()
+    }
+
+    /// Clears all parsed data, statistics and warnings, restarting acquisition at a given host time.
+    ///
+    /// # Arguments
+    /// * `start` - Host clock time acquisition restarts at
+    pub fn reset_at(&mut self, start: SystemTime) {
+        let fresh = GnssData {
+            warmup_epochs: self.warmup_epochs,
+            max_satellites_per_system: self.max_satellites_per_system,
+            max_satellites_total: self.max_satellites_total,
+            course_gate_factor: self.course_gate_factor,
+            course_window: self.course_window,
+            dead_reckoning_policy: self.dead_reckoning_policy,
+            fault_policy: self.fault_policy,
+            datum_policy: self.datum_policy,
+            custom_handlers: std::mem::take(&mut self.custom_handlers),
+            hot_start_max: self.hot_start_max,
+            warm_start_max: self.warm_start_max,
+            decimal_separator: self.decimal_separator,
+            altitude_spread_limit: self.altitude_spread_limit,
+            outlier_threshold: self.outlier_threshold,
+            dop_floor: self.dop_floor,
+            dop_ceiling: self.dop_ceiling,
+            late_tolerance: self.late_tolerance,
+            navic_prns: self.navic_prns.clone(),
+            keep_best_only: self.keep_best_only,
+            best_fix_staleness: self.best_fix_staleness,
+            altitude_hold: self.altitude_hold,
+            position_filter: self.position_filter,
+            clock: self.clock.clone(),
+            satellite_history: self.satellite_history,
+            checksum_validation: self.checksum_validation,
+            strict_framing: self.strict_framing,
+            contributing_order: self.contributing_order,
+            privacy_accuracy: self.privacy_accuracy,
+            epoch_capacity: self.epoch_capacity,
+            elide_epoch_satellites: self.elide_epoch_satellites,
+            #[cfg(feature = "geoid")]
+            geoid_model: self.geoid_model.take(),
+            position_precedence: std::mem::take(&mut self.position_precedence),
+            speed_precedence: std::mem::take(&mut self.speed_precedence),
+            acquisition_start: Some(start),
+            ..GnssSystem::ALL.into_iter()
+                .filter_map(|system| self.systems.get(system.name()).map(|sys| (system, sys.fixed_accuracy)))
+                .fold(GnssDataBuilder::new(), |builder, (system, accuracy)| builder.with_system(system, accuracy))
+                .build()
+        };
+        *self = fresh;
+    }
+
+    /// Starts a new precedence epoch when `time` differs from the current epoch time.
+    fn observe_epoch(&mut self, time: Option<&&str>) {
+        let Some(time) = time.and_then(|s| s.parse::<f64>().ok()) else {
+            return;
+        };
+        if self.epoch_time != Some(time) {
+            if let Some(previous) = self.epoch_time {
+                self.features.observe_epoch_interval(previous, time);
+            }
+            if self.epoch_time.is_some() && self.epoch_capacity > 0 {
+                self.seal_epoch();
+            }
+            self.epoch_time = Some(time);
+            self.epoch_generation += 1;
+            // GNS mode indicators describe a single epoch
+            for sys in self.systems.values_mut() {
+                sys.fix_mode = None;
+            }
+            self.position_claim = None;
+            self.speed_claim = None;
+            self.epoch_positions.clear();
+        }
+    }
+
+    /// Records the position a sentence type reported within the epoch carrying `time`,
+    /// replacing an earlier report of the same type.
+    fn record_epoch_position(&mut self, sentence_type: &'static str, time: Option<&&str>, lat: Option<f64>, lon: Option<f64>) {
+        let (Some(lat), Some(lon)) = (lat, lon) else {
+            return;
+        };
+        self.observe_epoch(time);
+        self.epoch_positions.retain(|(reported_by, _, _)| *reported_by != sentence_type);
+        self.epoch_positions.push((sentence_type, lat, lon));
+    }
+
+    /// Claims the position for a sentence type within the epoch carrying `time`.
+    ///
+    /// # Returns
+    /// * `bool` - True if the sentence may set the position
+    fn claim_position(&mut self, sentence_type: &str, time: Option<&&str>) -> bool {
+        self.observe_epoch(time);
+        let rank = precedence_rank(&self.position_precedence, sentence_type);
+        claim_precedence(&mut self.position_claim, rank)
+    }
+
+    /// Claims the position for a sentence that reports a fix, and records its coordinates for
+    /// the epoch.
+    ///
+    /// A sentence without a fix only observes the epoch: its coordinates are stale or
+    /// placeholders, so it neither claims the position nor takes part in cross-checks.
+    ///
+    /// # Returns
+    /// * `bool` - True if the sentence may set the position
+    fn claim_fixed_position(&mut self, sentence_type: &'static str, time: Option<&&str>, lat: Option<f64>, lon: Option<f64>, fix: bool) -> bool {
+        if !fix {
+            self.observe_epoch(time);
+            return false;
+        }
+        let claims_position = self.claim_position(sentence_type, time);
+        self.record_epoch_position(sentence_type, time, lat, lon);
+        claims_position
+    }
+
+    /// Claims speed and track for a sentence type within the epoch carrying `time`.
+    ///
+    /// # Returns
+    /// * `bool` - True if the sentence may set speed and track
+    fn claim_speed(&mut self, sentence_type: &str, time: Option<&&str>) -> bool {
+        self.observe_epoch(time);
+        let rank = precedence_rank(&self.speed_precedence, sentence_type);
+        claim_precedence(&mut self.speed_claim, rank)
+    }
+
+    /// Returns the largest distance between the positions different sentence types reported
+    /// this epoch.
+    ///
+    /// GGA, RMC, GNS and GLL of one epoch should agree to within centimeters; a large spread
+    /// points to a field-offset parsing bug or a receiver fault. The latest report of each type
+    /// is compared, regardless of precedence.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The maximum pairwise distance in meters, or None if fewer than two sentence types reported a position
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// assert_eq!(gnss.coordinate_consistency(), None);
+    /// gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74").unwrap();
+    /// assert_eq!(gnss.coordinate_consistency(), Some(0.0));
+    /// ```
+    pub fn coordinate_consistency(&self) -> Option<f64> {
+        let positions = &self.epoch_positions;
+        positions.iter().enumerate()
+            .flat_map(|(i, (_, lat1, lon1))| {
+                positions[i + 1..].iter().map(move |(_, lat2, lon2)| haversine_distance(*lat1, *lon1, *lat2, *lon2))
+            })
+            .reduce(f64::max)
+    }
+
+    /// Checks whether positions reported by different sentence types this epoch disagree.
+    ///
+    /// # Arguments
+    /// * `threshold_m` - Largest acceptable distance between the positions in meters
+    ///
+    /// # Returns
+    /// * `bool` - True if `coordinate_consistency` exceeds the threshold
+    pub fn has_coordinate_conflict(&self, threshold_m: f64) -> bool {
+        self.coordinate_consistency().is_some_and(|spread| spread > threshold_m)
+    }
+
+    /// Gets the position precedence, highest first.
+    ///
+    /// # Returns
+    /// * `&[String]` - Sentence types in precedence order
+    pub fn get_position_precedence(&self) -> &[String] {
+        &self.position_precedence
+    }
+
+    /// Sets the position precedence applied within an epoch, highest first.
+    ///
+    /// # Arguments
+    /// * `precedence` - Sentence types (e.g. "GGA") in precedence order; unlisted types rank lowest
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_position_precedence(&["RMC", "GGA"]);
+    /// gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123519,4900.000,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*54").unwrap();
+    /// assert!((gnss.latitude().unwrap() - 48.1173).abs() < 1e-6);
+    /// ```
+    pub fn set_position_precedence(&mut self, precedence: &[&str]) {
+        self.position_precedence = precedence.iter().map(|s| s.to_string()).collect();
+        self.position_claim = None;
+    }
+
+    /// Gets the speed and track precedence, highest first.
+    ///
+    /// # Returns
+    /// * `&[String]` - Sentence types in precedence order
+    pub fn get_speed_precedence(&self) -> &[String] {
+        &self.speed_precedence
+    }
+
+    /// Sets the speed and track precedence applied within an epoch, highest first.
+    ///
+    /// # Arguments
+    /// * `precedence` - Sentence types (e.g. "RMC") in precedence order; unlisted types rank lowest
+    pub fn set_speed_precedence(&mut self, precedence: &[&str]) {
+        self.speed_precedence = precedence.iter().map(|s| s.to_string()).collect();
+        self.speed_claim = None;
+    }
+
+    /// Parses and updates GNSS data from a GGA sentence.
+    fn update_gga(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        if self.is_late_position(parts.get(1)) {
+            return;
+        }
+        let lat = parse_lat(parts.get(2), parts.get(3));
+        let lon = parse_lon(parts.get(4), parts.get(5));
+        let altitude = parts.get(9).and_then(|s| s.parse().ok());
+        let no_fix = parts.get(6).is_some_and(|quality| quality.parse::<u8>() == Ok(0));
+        let claims_position = self.claim_fixed_position("GGA", parts.get(1), lat, lon, !no_fix);
+
+        self.time = parts.get(1).map(|s| s.to_string());
+        self.fix_quality = parts.get(6).and_then(|s| s.parse().ok());
+        self.features.rtk |= matches!(self.fix_quality, Some(4 | 5));
+        self.num_satellites = parts.get(7).and_then(|s| s.parse().ok());
+        // Without a fix the HDOP is a placeholder (often 99.99)
+        let has_fix = self.fix_quality.is_some_and(|quality| quality > 0);
+        let hdop = parts.get(8).and_then(|s| s.parse().ok()).filter(|_| has_fix);
+        self.gga_hdop = self.plausible_dop("GGA", hdop);
+        self.dgps_age = parts.get(13).and_then(|s| s.parse().ok()).filter(|age: &f64| age.is_finite() && *age >= 0.0);
+        self.dgps_station = parts.get(14).and_then(|s| s.parse().ok());
+        if no_fix {
+            self.mark_fix_lost(system);
+            return;
+        }
+        if !claims_position {
+            return;
+        }
+        self.latitude = lat;
+        self.longitude = lon;
+        self.altitude = altitude;
+        self.geoid_separation = parts.get(11).and_then(|s| s.parse().ok());
+        let dead_reckoned = self.is_dead_reckoned();
+
+        for (system_data, tracked) in self.position_targets(system) {
+            if tracked {
+                system_data.latitude = lat;
+                system_data.longitude = lon;
+                system_data.altitude = altitude;
+                system_data.altitude_datum = AltitudeDatum::Msl;
+                system_data.dead_reckoned = dead_reckoned;
+                system_data.fix_lost = false;
+            } else {
+                system_data.latitude = None;
+                system_data.longitude = None;
+                system_data.altitude = None;
+            }
+        }
+    }
+
+    /// Parses and updates GNSS data from an RMC sentence.
+    fn update_rmc(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        if self.is_late_position(parts.get(1)) {
+            return;
+        }
+        let lat = parse_lat(parts.get(3), parts.get(4));
+        let lon = parse_lon(parts.get(5), parts.get(6));
+        // NMEA 2.3 mode indicator: 'N' is no fix even with status 'A', 'E' is dead reckoning
+        let mode = parts.get(12).and_then(|mode| mode.chars().next());
+        let void = parts.get(2).is_some_and(|status| status.starts_with('V')) || mode == Some('N');
+        let claims_position = self.claim_fixed_position("RMC", parts.get(1), lat, lon, !void);
+        self.features.observe_mode_indicator(parts.get(12));
+        self.rmc_status = parts.get(2).and_then(|s| s.chars().next());
+        self.rmc_mode = mode;
+        // NMEA 4.1 appends the navigational status after the mode indicator
+        self.nav_status = parts.get(13).and_then(|status| status.chars().next());
+        self.magnetic_variation = parts.get(10).and_then(|s| s.parse::<f64>().ok())
+            .zip(parts.get(11).and_then(|hemi| hemisphere_sign(hemi, 'E', 'W')))
+            .map(|(variation, sign)| variation * sign);
+        self.time = parts.get(1).map(|s| s.to_string());
+        if self.claim_speed("RMC", None) {
+            self.speed_knots = parts.get(7).and_then(|s| s.parse().ok());
+            self.track_angle = parts.get(8).and_then(|s| s.parse().ok());
+        }
+        self.date = parts.get(9).map(|s| s.to_string());
+        self.date_year = None;
+        self.date_reported_at = parts.get(1).and_then(|time| parse_utc_time(time));
+        if void {
+            self.mark_fix_lost(system);
+            return;
+        }
+        if !claims_position {
+            return;
+        }
+        self.latitude = lat;
+        self.longitude = lon;
+
+        for (system_data, tracked) in self.position_targets(system) {
+            if tracked {
+                system_data.latitude = lat;
+                system_data.longitude = lon;
+                if let Some(mode) = mode {
+                    system_data.dead_reckoned = mode == 'E';
+                }
+                system_data.fix_lost = false;
+            } else {
+                system_data.latitude = None;
+                system_data.longitude = None;
+            }
+        }
+    }
+
+    /// Parses and updates GNSS data from a GNS sentence.
+    ///
+    /// From "GN" the mode indicator field holds one character per constellation (GPS, GLONASS,
+    /// Galileo, BeiDou, QZSS, NavIC); from a constellation talker (GPGNS, GLGNS, ...) its first
+    /// character is that constellation's mode. Each is stored as the system's `fix_mode` until
+    /// the next epoch starts. The HDOP is stored in `gga_hdop`, as GNS and GGA report the same
+    /// combined HDOP. Empty fields never erase values already set by a GGA sentence of the same
+    /// epoch.
+    fn update_gns(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        if self.is_late_position(parts.get(1)) {
+            return;
+        }
+        // Start the sentence's epoch before storing its modes, even when it carries no position
+        self.observe_epoch(parts.get(1));
+        let lat = parse_lat(parts.get(2), parts.get(3));
+        let lon = parse_lon(parts.get(4), parts.get(5));
+        let altitude: Option<f64> = parts.get(9).and_then(|s| s.parse().ok());
+        let claims_position = lat.is_some() && lon.is_some() && self.claim_position("GNS", parts.get(1));
+        self.record_epoch_position("GNS", parts.get(1), lat, lon);
+
+        if let Some(time) = parts.get(1).filter(|s| !s.is_empty()) {
+            self.time = Some(time.to_string());
+        }
+        if claims_position {
+            self.latitude = lat;
+            self.longitude = lon;
+            if altitude.is_some() {
+                self.altitude = altitude;
+            }
+            if let Some(separation) = parts.get(10).and_then(|s| s.parse().ok()) {
+                self.geoid_separation = Some(separation);
+            }
+        }
+        if let Some(num_satellites) = parts.get(7).and_then(|s| s.parse().ok()) {
+            self.num_satellites = Some(num_satellites);
+        }
+
+        let modes = parts.get(6).copied().unwrap_or("");
+        // Without a fix the HDOP is a placeholder, as in GGA
+        let has_fix = modes.chars().any(|mode| mode != 'N');
+        self.features.rtk |= modes.chars().any(|mode| matches!(mode, 'R' | 'F'));
+        let hdop = parts.get(8).and_then(|s| s.parse().ok()).filter(|_| has_fix);
+        if let Some(hdop) = self.plausible_dop("GNS", hdop) {
+            self.gga_hdop = Some(hdop);
+        }
+        let received_at = self.last_receive_time;
+        let system_modes: Vec<(GnssSystem, char)> = match system {
+            Some(system) => modes.chars().next().map(|mode| (system, mode)).into_iter().collect(),
+            None => GnssSystem::ALL.into_iter().zip(modes.chars()).collect(),
+        };
+        for (target, mode) in system_modes {
+            if let Some(sys) = self.systems.get_mut(target.name()) {
+                sys.fix_mode = Some(mode);
+                if mode != 'N' {
+                    sys.last_update = received_at;
+                }
+                // A constellation talker's position is its own, as in GGA, even before its satellites arrive
+                let tracked = system.is_some() || !sys.satellites_info.is_empty();
+                if mode != 'N' && tracked && claims_position {
+                    sys.latitude = lat;
+                    sys.longitude = lon;
+                    sys.dead_reckoned = mode == 'E';
+                    sys.fix_lost = false;
+                    if altitude.is_some() {
+                        sys.altitude = altitude;
+                        sys.altitude_datum = AltitudeDatum::Msl;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parses and updates GNSS data from a VTG sentence.
+    ///
+    /// The speed in knots is used, falling back to the km/h field when it is empty. When both
+    /// are present they are cross-checked and a `DataWarning::SpeedMismatch` is raised if they
+    /// disagree by more than `SPEED_MISMATCH_KMH`. A sentence whose mode indicator is 'N'
+    /// reports no fix and updates nothing.
+    fn update_vtg(&mut self, parts: &[&str], _system: Option<GnssSystem>) {
+        let vtg = VtgFields::locate(parts);
+        self.features.observe_mode_indicator(vtg.mode.as_ref());
+        if vtg.mode.is_some_and(|mode| mode.starts_with('N')) {
+            return;
+        }
+        self.track_angle_magnetic = vtg.magnetic_course;
+        if !self.claim_speed("VTG", None) {
+            return;
+        }
+        let (knots, kmh) = (vtg.knots, vtg.kmh);
+        self.track_angle = vtg.true_course;
+        self.speed_knots = knots.or(kmh.map(|kmh| kmh / KMH_PER_KNOT));
+        if let (Some(knots), Some(kmh)) = (knots, kmh) {
+            if (knots * KMH_PER_KNOT - kmh).abs() > SPEED_MISMATCH_KMH {
+                self.push_warning(DataWarning::SpeedMismatch { knots, kmh });
+            }
+        }
+    }
+
+    /// Parses the true heading from an HDT sentence; an empty heading clears it.
+    fn update_hdt(&mut self, parts: &[&str]) {
+        self.heading_true = parse_heading(parts.get(1));
+        self.heading_mode = None;
+    }
+
+    /// Parses the true heading and mode indicator from a THS sentence.
+    ///
+    /// A sentence with mode 'V' (not valid) is ignored, keeping the previous heading.
+    fn update_ths(&mut self, parts: &[&str]) {
+        let mode = parts.get(2).and_then(|mode| mode.chars().next());
+        if mode == Some('V') {
+            return;
+        }
+        self.heading_true = parse_heading(parts.get(1));
+        self.heading_mode = mode;
+    }
+
+    /// Parses the UTC date and local time zone from a ZDA sentence.
+    ///
+    /// The date is stored in `date` in the DDMMYY format RMC uses, with the four-digit year
+    /// kept for `utc_datetime`.
+    fn update_zda(&mut self, parts: &[&str]) {
+        let field = |index: usize| parts.get(index).copied();
+        let number = |index: usize| field(index)?.parse::<u16>().ok();
+        let (Some(day), Some(month), Some(year)) = (number(2), number(3), number(4)) else {
+            return;
+        };
+        self.date = Some(format!("{:02}{:02}{:02}", day, month, year % 100));
+        self.date_year = Some(year);
+        self.date_reported_at = field(1).and_then(parse_utc_time);
+
+        // Zone minutes carry the sign of the zone hours, which may be "-00"
+        self.local_zone_offset = field(5).zip(field(6)).and_then(|(hours, minutes)| {
+            let magnitude = hours.trim_start_matches(['-', '+']).parse::<i16>().ok()? * 60 + minutes.parse::<i16>().ok()?;
+            Some(if hours.starts_with('-') { -magnitude } else { magnitude })
+        });
+    }
+
+    /// Stores the status text of a TXT sentence, dropping the oldest message past
+    /// `MAX_TEXT_MESSAGES`.
+    ///
+    /// A text split over several sentences is stored once its last part arrives; parts out of
+    /// sequence discard the partial message.
+    fn update_txt(&mut self, parts: &[&str]) {
+        let number = |index: usize| parts.get(index).and_then(|s| s.parse::<u8>().ok());
+        let (Some(total), Some(sentence), Some(severity)) = (number(1), number(2), number(3)) else {
+            return;
+        };
+        // The text may itself contain commas
+        let text = parts.get(4..).map(|text| text.join(",")).unwrap_or_default();
+
+        let pending = match self.pending_txt.take() {
+            _ if sentence == 1 => PendingTxt { total, received: 1, severity, text },
+            Some(mut pending) if pending.total == total && pending.received + 1 == sentence => {
+                pending.received = sentence;
+                pending.text.push_str(&text);
+                pending
+            }
+            _ => return,
+        };
+        if pending.received < pending.total {
+            self.pending_txt = Some(pending);
+            return;
+        }
+
+        if let Some(status) = AntennaStatus::from_text(&pending.text) {
+            self.antenna_status = Some(status);
+        }
+        if self.text_messages.len() >= MAX_TEXT_MESSAGES {
+            self.text_messages.remove(0);
+        }
+        self.text_messages.push(TxtMessage { severity: pending.severity, text: pending.text });
+    }
+
+    /// Parses the error statistics from a GST sentence.
+    ///
+    /// A GST from the "GN" talker describes the combined solution and is stored in
+    /// `error_statistics`; one from a constellation talker sets that system's error sigmas.
+    /// Receivers without a fix leave the sigmas empty; the statistics are then cleared rather
+    /// than kept from an earlier fix.
+    fn update_gst(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        let field = |index: usize| -> Option<f64> {
+            parts.get(index)?.parse().ok()
+        };
+        if let Some(system) = system {
+            if let Some(sys) = self.systems.get_mut(system.name()) {
+                sys.lat_error_std = field(6);
+                sys.lon_error_std = field(7);
+                sys.alt_error_std = field(8);
+            }
+            return;
+        }
+        let stats = ErrorStatistics {
+            time: parts.get(1).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+            rms_residual: field(2),
+            sigma_latitude: field(6),
+            sigma_longitude: field(7),
+            sigma_altitude: field(8),
+        };
+        self.error_statistics = (stats.horizontal_sigma().is_some() || stats.sigma_altitude.is_some()).then_some(stats);
+    }
+
+    /// Parses the RAIM integrity report of a GBS sentence into `integrity`.
+    ///
+    /// The system of the failed satellite comes from the NMEA 4.10 system ID, else the talker,
+    /// else the PRN range. A GBS from the "GN" talker replaces the fault flags of every system;
+    /// one from a constellation talker only its own.
+    fn update_gbs(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        let field = |index: usize| -> Option<f64> {
+            parts.get(index)?.parse().ok()
+        };
+        let failed_prn = parts.get(5).and_then(|s| s.parse::<u16>().ok());
+        let system_id = parts.get(9)
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|id| u8::from_str_radix(id, 16).ok());
+        let failed_system = failed_prn.and_then(|prn| {
+            system_id.and_then(GnssSystem::from_system_id).or(system).or_else(|| self.system_for_prn(prn))
+        });
+
+        for (name, sys) in self.systems.iter_mut() {
+            if system.is_none_or(|system| system.name() == *name) {
+                sys.integrity_fault = failed_system.is_some_and(|failed| failed.name() == *name);
+            }
+        }
+        self.integrity = Some(IntegrityInfo {
+            time: parts.get(1).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+            latitude_error: field(2),
+            longitude_error: field(3),
+            altitude_error: field(4),
+            failed_prn,
+            failed_system,
+            missed_detection_probability: field(6),
+            bias: field(7),
+            bias_std: field(8),
+        });
+    }
+
+    /// Parses the datum of a DTM sentence, warning when it changes to one other than WGS84.
+    ///
+    /// A DTM from the "GN" talker applies to every system; one from a constellation talker to
+    /// its system alone.
+    fn update_dtm(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        let text = |index: usize| parts.get(index).filter(|s| !s.is_empty()).map(|s| s.to_string());
+        // Offsets are in minutes with a hemisphere letter
+        let offset = |value: usize, positive: char, negative: char| -> Option<f64> {
+            let minutes = parts.get(value)?.parse::<f64>().ok()?;
+            Some(minutes / 60.0 * hemisphere_sign(parts.get(value + 1)?, positive, negative)?)
+        };
+        let Some(local_datum) = text(1) else {
+            return;
+        };
+        let datum = DatumInfo {
+            local_datum,
+            subdivision: text(2),
+            latitude_offset: offset(3, 'N', 'S'),
+            longitude_offset: offset(5, 'E', 'W'),
+            altitude_offset: parts.get(7).and_then(|s| s.parse().ok()),
+            reference_datum: text(8).unwrap_or_else(|| "W84".to_string()),
+        };
+        let previous = self.datum.as_ref().map(|previous| previous.local_datum.as_str());
+        if !datum.is_wgs84() && previous != Some(datum.local_datum.as_str()) {
+            self.push_warning(DataWarning::NonWgs84Datum { datum: datum.local_datum.clone() });
+        }
+        for (name, sys) in self.systems.iter_mut() {
+            if system.is_none_or(|system| system.name() == *name) {
+                sys.datum = Some(datum.clone());
+            }
+        }
+        self.datum = Some(datum);
+    }
+
+    /// Parses and updates GNSS system data from a GSA sentence.
+    fn update_gsa(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        let mut gps_ids: Vec<u16> = Vec::new();
+        for i in 3..=14 {
+            if let Some(Ok(prn)) = parts.get(i).map(|s| s.parse()) {
+                gps_ids.push(prn);
+            }
+        }
+
+        // NMEA 4.10 appends the system ID after the DOPs; a field with other than hex digits is not one
+        let system_id = parts.get(18)
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|id| u8::from_str_radix(id, 16).ok());
+        self.features.system_ids |= system_id.is_some();
+
+        // The DOPs sit at fixed positions after the 12 PRN slots, with or without a system ID
+        let dop_values: Vec<Option<f64>> = (15..=17).map(|i| parts.get(i).and_then(|s| s.parse().ok())).collect();
+
+        let pdop = self.plausible_dop("GSA", dop_values.first().copied().flatten());
+        let hdop = self.plausible_dop("GSA", dop_values.get(1).copied().flatten());
+        let vdop = self.plausible_dop("GSA", dop_values.get(2).copied().flatten());
+        // The system ID names the constellation of every PRN, even where PRN ranges overlap
+        let system = system.or(system_id.and_then(GnssSystem::from_system_id));
+        let selection_mode = parts.get(1).and_then(|s| s.chars().next());
+        let fix_type = parts.get(2).and_then(|s| s.parse().ok()).and_then(FixType::from_gsa);
+        self.fix_type = fix_type;
+
+        let mut updated_systems = Vec::new();
+        let mut unattributed = Vec::new();
+        for prn in &gps_ids {
+            // A constellation talker's PRNs belong to its system, including the QZSS PRNs 33-42 some
+            // receivers use under the QZ talker; "GN" PRNs are attributed by range
+            let target = system.or_else(|| self.system_for_prn(*prn));
+            match target {
+                Some(target) => {
+                    let name = target.name();
+                    // PRNs of systems that are not enabled are dropped
+                    let Some(sys) = self.systems.get_mut(name) else {
+                        continue;
+                    };
+                    sys.record_used_satellite(*prn, self.epoch_generation);
+                    if !updated_systems.contains(&name) {
+                        updated_systems.push(name);
+                    }
+                }
+                None => unattributed.push(*prn),
+            }
+        }
+        // The DOPs of a constellation talker's GSA are its system's, even without PRNs
+        if let Some(system) = system.filter(|system| !updated_systems.contains(&system.name())) {
+            if let Some(sys) = self.systems.get_mut(system.name()) {
+                sys.begin_used_satellites(self.epoch_generation);
+            }
+            updated_systems.push(system.name());
+        }
+        let single_system = system.is_some() || (updated_systems.len() == 1 && unattributed.is_empty());
+        for name in &updated_systems {
+            self.features.observe_system(GnssSystem::from_name(name));
+            self.touch_system(name);
+        }
+        for prn in unattributed {
+            self.push_warning(DataWarning::UnattributedPrn { sentence_type: "GSA".to_string(), prn });
+        }
+        // Without a fix the satellites and DOPs of the last fix no longer apply
+        if fix_type == Some(FixType::None) {
+            if !single_system {
+                self.combined_pdop = None;
+                self.combined_hdop = None;
+                self.combined_vdop = None;
+            }
+            for sys_name in updated_systems {
+                if let Some(sys) = self.systems.get_mut(sys_name) {
+                    sys.satellites_used.clear();
+                    sys.pdop = None;
+                    sys.hdop = None;
+                    sys.vdop = None;
+                    sys.accuracy = sys.fixed_accuracy;
+                    sys.selection_mode = selection_mode;
+                    sys.fix_type = fix_type;
+                    sys.mark_used_satellites();
+                }
+            }
+            return;
+        }
+        // DOPs of a GSA that spans several systems describe the combined solution only
+        if !single_system {
+            self.combined_pdop = pdop;
+            self.combined_hdop = hdop;
+            self.combined_vdop = vdop;
+            for sys_name in updated_systems {
+                if let Some(sys) = self.systems.get_mut(sys_name) {
+                    sys.selection_mode = selection_mode;
+                    sys.fix_type = fix_type;
+                    sys.mark_used_satellites();
+                }
+            }
+            return;
+        }
+        // Only update error values for the system that received satellites in this GSA sentence
+        for sys_name in updated_systems {
+            if let Some(sys) = self.systems.get_mut(sys_name) {
+                sys.pdop = pdop;
+                sys.hdop = hdop;
+                sys.vdop = vdop;
+                sys.selection_mode = selection_mode;
+                sys.fix_type = fix_type;
+                sys.mark_used_satellites();
+                // Dynamically update accuracy using HDOP and fixed_accuracy
+                if let Some(hdop_val) = hdop {
+                    sys.accuracy = hdop_val * sys.fixed_accuracy;
+                } else {
+                    sys.accuracy = sys.fixed_accuracy;
+                }
+            }
+        }
+    }
+
+    /// Attributes a PRN reported under the "GN" talker to a system by its numbering range.
+    fn system_for_prn(&self, prn: u16) -> Option<GnssSystem> {
+        match prn {
+            prn if self.get_navic_prn_range().contains(&prn) => Some(GnssSystem::Navic),
+            1..=32 => Some(GnssSystem::Gps),
+            65..=96 => Some(GnssSystem::Glonass),
+            // QZSS occupies 193-202, but 201 and up are taken by BeiDou
+            193..=200 => Some(GnssSystem::Qzss),
+            201..=236 => Some(GnssSystem::Beidou),
+            301..=336 => Some(GnssSystem::Galileo),
+            _ => None,
+        }
+    }
+
+    /// Parses and updates satellite information from a GSV sentence for the specified system.
+    fn update_gsv(&mut self, parts: &[&str], system: &str) {
+        let per_system_cap = self.max_satellites_per_system;
+        let track_locks = self.satellite_history;
+        let received_at = self.current_time();
+        let mut evicted = Vec::new();
+        // NMEA 4.10 appends a signal ID after the satellite blocks; each signal has its own sequence
+        let signal = parts.len().checked_sub(4)
+            .filter(|fields| fields % 4 == 1)
+            .and_then(|_| parts.last())
+            .and_then(|field| field.chars().next());
+        self.features.signal_ids |= signal.is_some();
+        self.features.observe_system(GnssSystem::from_name(system));
+        if let Some(sys_data) = self.systems.get_mut(system) {
+            let total_msgs = parts.get(1).and_then(|s| s.parse::<u8>().ok());
+            let msg_num = parts.get(2).and_then(|s| s.parse::<u8>().ok());
+            let in_view = parts.get(3).and_then(|s| s.parse::<u16>().ok());
+            let satellites = parts.get(4..).unwrap_or_default().chunks_exact(4).filter_map(|fields| {
+                Some(SatelliteInfo {
+                    prn: fields[0].parse().ok()?,
+                    elevation: fields[1].parse().ok(),
+                    azimuth: fields[2].parse().ok(),
+                    snr: fields[3].parse().ok(),
+                    signals: Vec::new(),
+                    used: false,
+                })
+            });
+            // Satellites are staged until the sequence completes, so readers never see half a cycle
+            let sequence = sys_data.stage_gsv_sentence(signal, (total_msgs, msg_num, in_view), satellites);
+            // Zero satellites in view: the constellation went dark, drop everything it tracked.
+            // A signal ID limits this to one signal, handled as an empty sequence below.
+            if in_view == Some(0) && signal.is_none() {
+                sys_data.clear_satellites();
+                sys_data.lock_starts.clear();
+                return;
+            }
+            // A complete sequence replaces the satellites of the previous one
+            let Some(sequence) = sequence else {
+                return;
+            };
+            let cycle_time = received_at;
+            sys_data.last_gsv_cycle = Some(cycle_time);
+            let prns: Vec<u16> = sequence.iter().map(|info| info.prn).collect();
+            let expected = if per_system_cap > 0 { sequence.len().min(per_system_cap) } else { sequence.len() };
+            sys_data.satellites_info.reserve(expected.saturating_sub(sys_data.satellites_info.len()));
+            for info in sequence {
+                sys_data.record_satellite(info, signal);
+                // Keep the per-system map bounded
+                while per_system_cap > 0 && sys_data.satellites_info.len() > per_system_cap {
+                    if let Some(sat) = sys_data.evict_weakest_satellite() {
+                        evicted.push((system.to_string(), sat));
+                    }
+                }
+            }
+            if let Some(signal_id) = signal {
+                sys_data.drop_signal_except(signal_id, &prns);
+            }
+            sys_data.prune_uncommitted_satellites();
+            if track_locks {
+                sys_data.close_lock_runs(prns, cycle_time);
+            }
+        }
+        evicted.extend(self.enforce_total_satellite_cap());
+        self.stats.satellite_evictions += evicted.len() as u64;
+        for (system, sat) in evicted {
+            self.push_warning(DataWarning::SatelliteEvicted { system, prn: sat.prn, snr: sat.snr });
+        }
+    }
+
+    /// Evicts satellites from the most populated systems until the overall cap is respected.
+    ///
+    /// # Returns
+    /// * `Vec<(String, SatelliteInfo)>` - The evicted satellites with their system names
+    fn enforce_total_satellite_cap(&mut self) -> Vec<(String, SatelliteInfo)> {
+        let mut evicted = Vec::new();
+        if self.max_satellites_total == 0 {
+            return evicted;
+        }
+        while self.systems.values().map(|sys| sys.satellites_info.len()).sum::<usize>() > self.max_satellites_total {
+            // Ties go to the system latest in canonical order
+            let Some((name, _)) = self.systems_in_order()
+                .max_by_key(|(_, sys)| sys.satellites_info.len()) else {
+                break;
+            };
+            let Some(fullest) = self.systems.get_mut(name) else {
+                break;
+            };
+            match fullest.evict_weakest_satellite() {
+                Some(sat) => evicted.push((name.to_string(), sat)),
+                None => break,
+            }
+        }
+        evicted
+    }
+
+    /// Parses and updates latitude/longitude from a GLL sentence for the specified system.
+    ///
+    /// A GLL with status 'V' or mode indicator 'N' (NMEA 2.3+) carries no fix: its coordinates
+    /// are ignored and the systems it applies to leave fusion until a sentence reports a fix.
+    fn update_gll(&mut self, parts: &[&str], system: Option<GnssSystem>) {
+        if self.is_late_position(parts.get(5)) {
+            return;
+        }
+        let lat = parse_lat(parts.get(1), parts.get(2));
+        let lon = parse_lon(parts.get(3), parts.get(4));
+        let mode = parts.get(7).and_then(|s| s.chars().next());
+        let void = parts.get(6).is_some_and(|status| status.starts_with('V')) || mode == Some('N');
+        let claims_position = self.claim_fixed_position("GLL", parts.get(5), lat, lon, !void);
+        self.features.observe_mode_indicator(parts.get(7));
+        // GLL carries the UTC time in field 5; it drives the timestamp when no GGA/RMC is emitted
+        if let Some(time) = parts.get(5).filter(|s| !s.is_empty()) {
+            self.time = Some(time.to_string());
+        }
+        if void {
+            self.mark_fix_lost(system);
+            return;
+        }
+        if !claims_position {
+            return;
+        }
+        self.latitude = lat;
+        self.longitude = lon;
+        let dead_reckoned = mode == Some('E');
+        for (sys, tracked) in self.position_targets(system) {
+            if tracked {
+                sys.latitude = lat;
+                sys.longitude = lon;
+                sys.dead_reckoned = dead_reckoned;
+                sys.fix_lost = false;
+            } else {
+                sys.latitude = None;
+                sys.longitude = None;
+            }
+        }
+    }
+
+    /// Keeps the systems a position sentence without a fix applies to out of fusion.
+    ///
+    /// The coordinates of such a sentence are stale or placeholders, so the stored positions
+    /// are left alone; the systems become eligible again once a sentence reports a fix.
+    fn mark_fix_lost(&mut self, system: Option<GnssSystem>) {
+        for (system_data, _) in self.position_targets(system) {
+            system_data.fix_lost = true;
+        }
+    }
+
+    /// Returns the receive time of the last sentence fed, or the clock time before any was fed.
+    fn current_time(&self) -> Timestamp {
+        self.last_receive_time.unwrap_or_else(|| self.clock.0.now())
+    }
+
+    /// Marks a system as updated at the receive time of the sentence being fed.
+    fn touch_system(&mut self, name: &str) {
+        let received_at = self.last_receive_time;
+        if let Some(sys) = self.systems.get_mut(name) {
+            sys.last_update = received_at;
+        }
+    }
+
+    /// Clears the data of systems that have not been updated within `max_age`.
+    ///
+    /// A constellation the antenna loses sight of otherwise keeps its satellites and its last
+    /// position forever, and keeps contributing that position to the fused result. An expired
+    /// system loses its satellites, position, DOPs and error statistics, so it stops
+    /// contributing until its sentences arrive again. Its configured accuracy is kept.
+    ///
+    /// Ages are measured against the clock set with `set_clock`, or against the receive time
+    /// of the last sentence fed if that is later. A receiver that goes silent thus expires its
+    /// systems as the clock advances; to replay a log with `feed_nmea_at`, use a
+    /// `ManualClock` that follows the log. Systems that were never updated are left alone.
+    ///
+    /// # Arguments
+    /// * `max_age` - How long a system may go without updates before its data is cleared
+    ///
+    /// # Returns
+    /// * `Vec<GnssSystem>` - The systems cleared by this call, in canonical order
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, GnssSystem};
+    /// use std::time::{Duration, SystemTime};
+    /// let mut gnss = GnssData::new();
+    /// let start = SystemTime::now();
+    /// gnss.feed_nmea_at("$GLGSV,1,1,01,70,45,090,40*5F", start).unwrap();
+    /// gnss.feed_nmea_at("$GPGSV,1,1,01,01,45,090,40*45", start + Duration::from_secs(10)).unwrap();
+    /// assert_eq!(gnss.expire_stale(Duration::from_secs(5)), vec![GnssSystem::Glonass]);
+    /// assert!(gnss.system("GLONASS").unwrap().satellites().is_empty());
+    /// assert!(!gnss.system("GPS").unwrap().satellites().is_empty());
+    /// ```
+    pub fn expire_stale(&mut self, max_age: Duration) -> Vec<GnssSystem> {
+        let now = self.current_time().max(self.clock.0.now());
+        let mut expired = Vec::new();
+        for system in GnssSystem::ALL {
+            let Some(sys) = self.systems.get_mut(system.name()) else { continue };
+            let stale = sys.last_update.is_some_and(|updated| now.duration_since(updated).is_ok_and(|age| age > max_age));
+            if stale {
+                sys.expire();
+                expired.push(system);
+            }
+        }
+        expired
+    }
+
+    /// Returns the systems a position sentence applies to, each with whether it takes the
+    /// position (true) or has its position cleared (false).
+    ///
+    /// A constellation talker's position belongs to that system alone, even before its
+    /// satellites are reported. A "GN" position applies to every system with satellites and
+    /// clears the others.
+    fn position_targets(&mut self, system: Option<GnssSystem>) -> impl Iterator<Item = (&mut GnssSystemData, bool)> {
+        self.systems.iter_mut().filter_map(move |(name, sys)| match system {
+            Some(system) => (system.name() == *name).then_some((sys, true)),
+            None => {
+                let tracked = !sys.satellites_info.is_empty();
+                Some((sys, tracked))
+            }
+        })
+    }
+
+    /// Feeds a single NMEA sentence to the parser, updates internal state and reports what was
+    /// recognized or what is wrong with the sentence.
+    ///
+    /// Handlers skip fields they cannot parse, so an error does not mean that nothing was
+    /// updated. It only means that data was dropped, with the exception of checksum validation,
+    /// which rejects the sentence as a whole, and of strict framing, which rejects a sentence
+    /// with bytes after its checksum. When a sentence has several problems, the first one is
+    /// reported in this order: empty sentence, checksum, trailing garbage, unknown sentence or
+    /// unsupported talker, missing fields, invalid number.
+    ///
+    /// `SentenceType` implements `Hash`, so results can be tallied per type to compare sentence
+    /// rates or failure ratios.
+    ///
+    /// # Arguments
+    /// * `sentence` - A string slice containing the NMEA sentence.
+    ///
+    /// # Returns
+    /// * `Result<SentenceType, NmeaError>` - The sentence type that was parsed, or the problem found
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, NmeaError, SentenceType};
+    /// let mut gnss = GnssData::new();
+    /// let parsed = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59");
+    /// assert_eq!(parsed, Ok(SentenceType::Gga));
+    /// let parsed = gnss.feed_nmea("$GPXYZ,1,2,3*50");
+    /// assert_eq!(parsed, Err(NmeaError::UnknownSentence { address: "GPXYZ".to_string() }));
+    /// ```
+    pub fn feed_nmea(&mut self, sentence: &str) -> Result<SentenceType, NmeaError> {
+        self.feed_nmea_at(sentence, self.clock.0.now())
+    }
+
+    /// Feeds a single NMEA sentence without reporting the outcome.
+    ///
+    /// This is the signature `feed_nmea` had before it returned a `Result`. The sentence is
+    /// applied exactly as `feed_nmea` applies it.
+    ///
+    /// # Arguments
+    /// * `sentence` - A string slice containing the NMEA sentence.
+    #[deprecated(note = "use `feed_nmea`, which reports what was parsed")]
+    pub fn feed_nmea_unchecked(&mut self, sentence: &str) {
+        // Nobody reads the result, so the fields are not checked against the descriptor
+        let _ = self.feed_sentence(sentence, self.clock.0.now(), false, |_| {});
+    }
+
+    /// Feeds a single NMEA sentence received at a given host time and reports what was
+    /// recognized or what is wrong with it, as `feed_nmea` does.
+    ///
+    /// The receive time of the first sentence of each epoch is compared with the UTC date
+    /// and time the epoch carries to maintain `timing_offset`.
+    ///
+    /// # Arguments
+    /// * `sentence` - A string slice containing the NMEA sentence.
+    /// * `received_at` - Host clock time at which the sentence was received
+    ///
+    /// # Returns
+    /// * `Result<SentenceType, NmeaError>` - The sentence type that was parsed, or the problem found
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// // 23 March 1994 12:35:19 UTC, received 250 ms later
+    /// let received_at = UNIX_EPOCH + Duration::from_millis(764_426_119_250);
+    /// gnss.feed_nmea_at("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74", received_at).unwrap();
+    /// let timing = gnss.timing_offset().unwrap();
+    /// assert!((timing.offset - 0.25).abs() < 1e-6);
+    /// ```
+    pub fn feed_nmea_at(&mut self, sentence: &str, received_at: SystemTime) -> Result<SentenceType, NmeaError> {
+        self.feed_sentence(sentence, received_at, true, |_| {})
+    }
+
+    /// Feeds raw bytes read from a receiver, such as the chunks returned by a serial port read.
+    ///
+    /// The bytes are split into lines on CR and LF. The unterminated tail of `data` is buffered
+    /// until a later call completes it, so a sentence may be split across reads at any byte.
+    /// Complete lines starting with '$', '!' (encapsulated sentences such as AIS) or a `\` TAG
+    /// block are fed as `feed_nmea` would; other lines are dropped. A line growing past
+    /// `MAX_BUFFERED_LINE` bytes is dropped too.
+    ///
+    /// # Arguments
+    /// * `data` - Bytes received from the receiver
+    ///
+    /// # Returns
+    /// * `usize` - Number of sentences fed
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert_eq!(gnss.feed_bytes(b"$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,"), 0);
+    /// assert_eq!(gnss.feed_bytes(b"545.4,M,46.9,M,,*59\r\n"), 1);
+    /// assert!(gnss.latitude().is_some());
+    /// ```
+    pub fn feed_bytes(&mut self, data: &[u8]) -> usize {
+        let received_at = self.clock.0.now();
+        let mut line = std::mem::take(&mut self.partial_line);
+        let mut fed = 0;
+        for &byte in data {
+            if byte != b'\r' && byte != b'\n' {
+                // The rest of an overlong line no longer starts with '$' and is dropped with it
+                if line.len() == MAX_BUFFERED_LINE {
+                    line.clear();
+                }
+                line.push(byte);
+                continue;
+            }
+            if line.first().is_some_and(|first| matches!(first, b'$' | b'!' | b'\\')) {
+                // Nobody reads the result, so the fields are not checked against the descriptor
+                let _ = self.feed_sentence(&String::from_utf8_lossy(&line), received_at, false, |_| {});
+                fed += 1;
+            }
+            line.clear();
+        }
+        self.partial_line = line;
+        fed
+    }
+
+    /// Feeds a single NMEA sentence and lets a callback read its raw fields.
+    ///
+    /// This gives access to fields the parser does not model, such as the DGPS station ID in
+    /// GGA field 14, without splitting the sentence again. The callback runs once the sentence
+    /// has been applied, for supported and unsupported sentence types alike, but not for a
+    /// sentence rejected by checksum validation or strict framing. The fields are borrowed for the duration of
+    /// the callback only.
+    ///
+    /// A panic in the callback is caught and raises a `DataWarning::ObserverPanicked`; the
+    /// sentence stays applied and the result is returned as if the callback had returned.
+    /// Panics can only be caught when the crate is built with `panic = "unwind"`, the default.
+    ///
+    /// # Arguments
+    /// * `sentence` - A string slice containing the NMEA sentence.
+    /// * `inspect` - Callback receiving the fields of the sentence
+    ///
+    /// # Returns
+    /// * `Result<SentenceType, NmeaError>` - The sentence type that was parsed, or the problem found
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// let mut station = None;
+    /// gnss.feed_nmea_with_fields("$GNGGA,123519,4807.038,N,01131.000,E,2,08,0.9,545.4,M,46.9,M,1.8,0131*7E", |fields| {
+    ///     station = fields.get(14).map(str::to_string);
+    /// }).unwrap();
+    /// assert_eq!(station.as_deref(), Some("0131"));
+    /// ```
+    pub fn feed_nmea_with_fields<F>(&mut self, sentence: &str, inspect: F) -> Result<SentenceType, NmeaError>
+    where
+        F: FnOnce(&SentenceFields<'_>),
+    {
+        self.feed_sentence(sentence, self.clock.0.now(), true, inspect)
+    }
+
+    /// Feeds a sentence received at `received_at`, handing its fields to `inspect` once applied.
+    ///
+    /// With `check_fields` false, missing fields and invalid numbers are not reported; they
+    /// never change what is applied.
+    fn feed_sentence<F>(&mut self, sentence: &str, received_at: SystemTime, check_fields: bool, inspect: F) -> Result<SentenceType, NmeaError>
+    where
+        F: FnOnce(&SentenceFields<'_>),
+    {
+        let previous_time = self.time.clone();
+        self.last_receive_time = Some(received_at);
+        let (tag_source, sentence) = split_tag_block(sentence);
+        // '!' starts encapsulated sentences such as AIS, which share the checksum rules
+        let sentence = sentence.trim_start_matches(['$', '!']);
+        let frame = split_frame(sentence);
+        let normalized;
+        let fields = if self.decimal_separator == ',' {
+            // Comma decimals come with semicolon-delimited fields; map both back to NMEA
+            normalized = frame.payload.replace(',', ".").replace(';', ",");
+            normalized.as_str()
+        } else {
+            frame.payload
+        };
+        let parts = split_fields(fields);
+
+        // Account the sentence to its TAG block source, or to its talker ID
+        let talker = parts.first().and_then(|address| address.get(0..2));
+        let checksum = frame.checksum_matches();
+        let checksum_error = || NmeaError::ChecksumMismatch {
+            expected: frame.checksum.unwrap_or_default().to_string(),
+            computed: nmea_checksum(frame.payload),
+        };
+        if let Some(source) = tag_source.or(talker) {
+            let checksum_failed = checksum == Some(false);
+            self.stats.record_sentence(source, checksum_failed, received_at);
+            if checksum_failed {
+                if let NmeaError::ChecksumMismatch { expected, computed } = checksum_error() {
+                    self.push_warning(DataWarning::ChecksumMismatch {
+                        sentence_type: parts[0].to_string(),
+                        expected,
+                        computed,
+                    });
+                }
+            }
+        }
+        let empty = sentence.trim_end_matches(['\r', '\n']).is_empty();
+        if self.checksum_validation && checksum != Some(true) {
+            self.stats.rejected_sentences += 1;
+            return Err(if empty { NmeaError::EmptySentence } else { checksum_error() });
+        }
+        // Line noise after the checksum is dropped, or rejects the sentence in strict framing
+        if !frame.trailing.is_empty() {
+            self.stats.trailing_garbage_sentences += 1;
+            if self.strict_framing {
+                self.stats.rejected_sentences += 1;
+                return Err(if checksum == Some(false) {
+                    checksum_error()
+                } else {
+                    NmeaError::TrailingGarbage { garbage: frame.trailing.to_string() }
+                });
+            }
+        }
+
+        // Read with '.' decimals, comma-decimal numbers would shift every following field
+        if self.decimal_separator == '.' && has_comma_decimals(&parts) {
+            self.stats.rejected_sentences += 1;
+            self.push_warning(DataWarning::CommaDecimalSeparator { sentence_type: parts[0].to_string() });
+            return Err(NmeaError::CommaDecimalSeparator { address: parts[0].to_string() });
+        }
+
+        let parsed = self.dispatch(&parts);
+        let sentence_type = parts.first().and_then(|address| address.get(2..5));
+        self.stats.record_dispatch(sentence_type.filter(|_| parsed.is_some()));
+        // A panicking callback must not abort the bookkeeping below
+        let inspected = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| inspect(&SentenceFields { parts: &parts })));
+        if inspected.is_err() {
+            let sentence_type = sentence_type.unwrap_or_default().to_string();
+            self.push_warning(DataWarning::ObserverPanicked { sentence_type });
+        }
+
+        if self.time != previous_time {
+            self.record_timing_sample(received_at);
+        }
+        if self.signal_ramp.is_none() && self.stats.time_to_first_fix.is_none() {
+            let with_snr = self.systems.values()
+                .flat_map(|sys| sys.satellites_info.values())
+                .filter(|sat| sat.snr.is_some())
+                .count();
+            if with_snr >= RAMP_SATELLITES {
+                self.signal_ramp = self.time_since_acquisition_start();
+            }
+        }
+
+        if empty {
+            return Err(NmeaError::EmptySentence);
+        }
+        if checksum == Some(false) {
+            return Err(checksum_error());
+        }
+        let descriptor = sentence_type.and_then(builtin_descriptor);
+        let address = || parts[0].to_string();
+        let parsed = match (parsed, descriptor) {
+            (Some(parsed), _) => parsed,
+            (None, Some(_)) => return Err(NmeaError::UnsupportedTalker { address: address() }),
+            (None, None) => return Err(NmeaError::UnknownSentence { address: address() }),
+        };
+        if let Some(descriptor) = descriptor.filter(|_| check_fields) {
+            descriptor.check_fields(&parts)?;
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the host time elapsed from acquisition start to the last sentence received.
+    fn time_since_acquisition_start(&self) -> Option<Duration> {
+        let elapsed = self.last_receive_time?.duration_since(self.acquisition_start?);
+        Some(elapsed.unwrap_or(Duration::ZERO))
+    }
+
+    /// Measures the offset of a new epoch's UTC time against the host receive time.
+    fn record_timing_sample(&mut self, received_at: SystemTime) {
+        let (Some(date), Some(time)) = (&self.date, &self.time) else {
+            return;
+        };
+        let Some(epoch) = nmea_datetime_to_unix(date, time) else {
+            return;
+        };
+        let received = match received_at.duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs_f64(),
+            Err(before) => -before.duration().as_secs_f64(),
+        };
+        let sample = received - epoch;
+        match &mut self.timing {
+            Some(timing) => timing.update(sample),
+            None => self.timing = Some(TimingEstimate { offset: sample, jitter: 0.0, samples: 1 }),
+        }
+    }
+
+    /// Returns the smoothed offset between sentence receipt and the UTC time it carries.
+    ///
+    /// Measuring requires the combined date and time, so no estimate exists until an RMC
+    /// has provided the date.
+    ///
+    /// # Returns
+    /// * `Option<TimingEstimate>` - The estimate, or None before the first measured epoch
+    pub fn timing_offset(&self) -> Option<TimingEstimate> {
+        self.timing
+    }
+
+    /// Returns the UTC time of the current epoch as reported (hhmmss.ss).
+    pub fn time(&self) -> Option<&str> {
+        self.time.as_deref()
+    }
+
+    /// Returns the date in DDMMYY format, from RMC or ZDA.
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    /// Returns the local time zone of the last ZDA sentence, in minutes east of UTC.
+    pub fn local_zone_offset(&self) -> Option<i16> {
+        self.local_zone_offset
+    }
+
+    /// Returns the latitude of the last position sentence in decimal degrees.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// assert!((gnss.latitude().unwrap() - 48.1173).abs() < 1e-9);
+    /// assert_eq!(gnss.hdop(), Some(0.9));
+    /// ```
+    pub fn latitude(&self) -> Option<f64> {
+        self.latitude
+    }
+
+    /// Returns the longitude of the last position sentence in decimal degrees.
+    pub fn longitude(&self) -> Option<f64> {
+        self.longitude
+    }
+
+    /// Returns the altitude above mean sea level of the last GGA or GNS in meters.
+    pub fn altitude(&self) -> Option<f64> {
+        self.altitude
+    }
+
+    /// Returns the geoid separation (ellipsoid height minus MSL height) in meters.
+    pub fn geoid_separation(&self) -> Option<f64> {
+        self.geoid_separation
+    }
+
+    /// Returns the raw GGA fix quality indicator; see `fix_quality_kind` for the decoded value.
+    pub fn fix_quality(&self) -> Option<u8> {
+        self.fix_quality
+    }
+
+    /// Returns the number of satellites used for the fix, from GGA or GNS.
+    pub fn num_satellites(&self) -> Option<u8> {
+        self.num_satellites
+    }
+
+    /// Returns the age of the differential corrections in seconds, from GGA.
+    pub fn dgps_age(&self) -> Option<f64> {
+        self.dgps_age
+    }
+
+    /// Returns the reference station ID of the differential corrections, from GGA.
+    pub fn dgps_station(&self) -> Option<u16> {
+        self.dgps_station
+    }
+
+    /// Returns the speed over ground in knots.
+    pub fn speed_knots(&self) -> Option<f64> {
+        self.speed_knots
+    }
+
+    /// Returns the track angle over ground in degrees.
+    pub fn track_angle(&self) -> Option<f64> {
+        self.track_angle
+    }
+
+    /// Returns the magnetic track angle over ground in degrees, from the last VTG sentence.
+    pub fn track_angle_magnetic(&self) -> Option<f64> {
+        self.track_angle_magnetic
+    }
+
+    /// Returns the true heading in degrees from the last HDT or valid THS sentence.
+    ///
+    /// Unlike the track angle, the heading is where the vehicle points, as measured by a
+    /// dual-antenna receiver or a gyrocompass, and is known at standstill.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPHDT,274.07,T*03").unwrap();
+    /// assert_eq!(gnss.heading_true(), Some(274.07));
+    /// ```
+    pub fn heading_true(&self) -> Option<f64> {
+        self.heading_true
+    }
+
+    /// Returns the mode indicator of the heading: 'A' autonomous, 'E' estimated (dead
+    /// reckoning), 'M' manual input or 'S' simulator.
+    ///
+    /// # Returns
+    /// * `Option<char>` - The mode from the last valid THS sentence, or None if the heading came
+    ///   from an HDT sentence, which carries no mode
+    pub fn heading_mode(&self) -> Option<char> {
+        self.heading_mode
+    }
+
+    /// Returns the status of the last RMC sentence ('A' = valid, 'V' = void).
+    pub fn rmc_status(&self) -> Option<char> {
+        self.rmc_status
+    }
+
+    /// Returns the FAA mode indicator of the last RMC sentence (NMEA 2.3 and later).
+    ///
+    /// 'A' is autonomous, 'D' differential, 'E' estimated (dead reckoning) and 'N' no fix.
+    pub fn rmc_mode(&self) -> Option<char> {
+        self.rmc_mode
+    }
+
+    /// Returns the navigational status of the last RMC sentence (NMEA 4.1 and later).
+    ///
+    /// 'S' is safe, 'C' caution, 'U' unsafe and 'V' not valid for navigation.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNRMC,123519.00,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,D,S*4D").unwrap();
+    /// assert_eq!((gnss.rmc_mode(), gnss.nav_status()), (Some('D'), Some('S')));
+    /// ```
+    pub fn nav_status(&self) -> Option<char> {
+        self.nav_status
+    }
+
+    /// Returns the magnetic variation of the last RMC sentence in degrees, positive east.
+    pub fn magnetic_variation(&self) -> Option<f64> {
+        self.magnetic_variation
+    }
+
+    /// Converts a true heading to a magnetic heading with the RMC magnetic variation.
+    ///
+    /// # Arguments
+    /// * `heading` - True heading in degrees
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The magnetic heading in [0, 360), or None without a magnetic variation
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74").unwrap();
+    /// assert_eq!(gnss.magnetic_variation(), Some(-3.1));
+    /// assert!((gnss.true_to_magnetic(358.0).unwrap() - 1.1).abs() < 1e-9);
+    /// ```
+    pub fn true_to_magnetic(&self, heading: f64) -> Option<f64> {
+        let magnetic = (heading - self.magnetic_variation?).rem_euclid(360.0);
+        // rem_euclid can round a tiny negative result up to 360
+        Some(if magnetic >= 360.0 { 0.0 } else { magnetic })
+    }
+
+    /// Returns the PDOP of the combined solution, from a GSA spanning several systems.
+    pub fn pdop(&self) -> Option<f64> {
+        self.combined_pdop
+    }
+
+    /// Returns the HDOP of the combined solution: from a GSA spanning several systems, else
+    /// from GGA or GNS.
+    pub fn hdop(&self) -> Option<f64> {
+        self.combined_hdop.or(self.gga_hdop)
+    }
+
+    /// Returns the VDOP of the combined solution, from a GSA spanning several systems.
+    pub fn vdop(&self) -> Option<f64> {
+        self.combined_vdop
+    }
+
+    /// Returns the fix type of the last GSA sentence, from any system.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{FixType, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*3F").unwrap();
+    /// assert_eq!(gnss.fix_type(), Some(FixType::Fix3D));
+    /// assert_eq!(gnss.system("GPS").unwrap().fix_type(), Some(FixType::Fix3D));
+    /// ```
+    pub fn fix_type(&self) -> Option<FixType> {
+        self.fix_type
+    }
+
+    /// Returns the HDOP reported in GGA or GNS field 8 (None without a fix).
+    pub fn gga_hdop(&self) -> Option<f64> {
+        self.gga_hdop
+    }
+
+    /// Returns the error statistics of the combined solution from the last GST sentence.
+    pub fn error_statistics(&self) -> Option<&ErrorStatistics> {
+        self.error_statistics.as_ref()
+    }
+
+    /// Returns the RAIM integrity report from the last GBS sentence.
+    pub fn integrity(&self) -> Option<&IntegrityInfo> {
+        self.integrity.as_ref()
+    }
+
+    /// Checks the expected position errors of the last GBS sentence against a threshold.
+    ///
+    /// # Arguments
+    /// * `threshold_m` - Largest acceptable expected error in meters
+    ///
+    /// # Returns
+    /// * `bool` - True if the GBS reports latitude and longitude errors and no expected error
+    ///   exceeds the threshold; false otherwise, including before any GBS
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert!(!gnss.integrity_ok(10.0));
+    /// gnss.feed_nmea("$GNGBS,123519.00,2.5,1.8,6.0,,,,*5A").unwrap();
+    /// assert!(gnss.integrity_ok(10.0));
+    /// assert!(!gnss.integrity_ok(5.0));
+    /// ```
+    pub fn integrity_ok(&self, threshold_m: f64) -> bool {
+        let Some(integrity) = &self.integrity else {
+            return false;
+        };
+        let (Some(latitude_error), Some(longitude_error)) = (integrity.latitude_error, integrity.longitude_error) else {
+            return false;
+        };
+        [Some(latitude_error), Some(longitude_error), integrity.altitude_error]
+            .into_iter()
+            .flatten()
+            .all(|error| error <= threshold_m)
+    }
+
+    /// Returns the datum announced by the last DTM sentence.
+    pub fn datum(&self) -> Option<&DatumInfo> {
+        self.datum.as_ref()
+    }
+
+    /// Checks whether positions are reported in WGS84, as assumed until a DTM says otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert!(gnss.is_wgs84());
+    /// gnss.feed_nmea("$GPDTM,W72,,0.012,N,0.006,W,4.5,W84*70").unwrap();
+    /// assert!(!gnss.is_wgs84());
+    /// assert_eq!(gnss.datum().unwrap().local_datum, "W72");
+    /// ```
+    pub fn is_wgs84(&self) -> bool {
+        self.datum.as_ref().is_none_or(DatumInfo::is_wgs84)
+    }
+
+    /// Returns the reported position converted to WGS84 with the offsets of the last DTM sentence.
+    ///
+    /// # Returns
+    /// * `Option<(f64, f64, Option<f64>)>` - The WGS84 latitude, longitude and altitude above
+    ///   mean sea level, or None without a position or when the datum is neither WGS84 nor a
+    ///   reported offset from it
+    pub fn wgs84_position(&self) -> Option<(f64, f64, Option<f64>)> {
+        let (latitude, longitude) = (self.latitude?, self.longitude?);
+        match &self.datum {
+            Some(datum) => datum.to_wgs84(latitude, longitude, self.altitude),
+            None => Some((latitude, longitude, self.altitude)),
+        }
+    }
+
+    /// Returns the fused position of the last `calculate_fused_position` or
+    /// `calculate_advanced_fused_position` call.
+    pub fn fused_position(&self) -> Option<&FusedPosition> {
+        self.fused_position.as_ref()
+    }
+
+    /// Returns the data of one system.
+    ///
+    /// # Arguments
+    /// * `system` - The system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    ///
+    /// # Returns
+    /// * `Option<&GnssSystemData>` - The system's data, or None for an unknown name
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// assert_eq!(gnss.system("GPS").unwrap().satellites().len(), 4);
+    /// assert_eq!(gnss.satellites().count(), 4);
+    /// ```
+    pub fn system(&self, system: &str) -> Option<&GnssSystemData> {
+        self.systems.get(system)
+    }
+
+    /// Iterates the systems in canonical order (GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC).
+    pub fn systems(&self) -> impl Iterator<Item = (&'static str, &GnssSystemData)> {
+        self.systems_in_order()
+    }
+
+    /// Iterates the satellites in view of every system, in canonical system order.
+    pub fn satellites(&self) -> impl Iterator<Item = (&'static str, &SatelliteInfo)> {
+        self.systems_in_order().flat_map(|(name, system_data)| system_data.satellites_info.values().map(move |sat| (name, sat)))
+    }
+
+    /// Returns the UTC time of the current epoch, parsed from `time`.
+    ///
+    /// Fractional seconds are kept to the millisecond; further digits are truncated.
+    ///
+    /// # Returns
+    /// * `Option<UtcTime>` - The time, or None if `time` is missing, empty or malformed
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, UtcTime};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519.50,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*72").unwrap();
+    /// assert_eq!(gnss.parsed_time(), Some(UtcTime { hour: 12, minute: 35, second: 19, millis: 500 }));
+    /// ```
+    pub fn parsed_time(&self) -> Option<UtcTime> {
+        self.time.as_deref().and_then(parse_utc_time)
+    }
+
+    /// Returns the speed over ground in km/h, converted from `speed_knots`.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The speed, or None if no speed was reported
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74").unwrap();
+    /// assert!((gnss.speed_kmh().unwrap() - 41.4848).abs() < 1e-9);
+    /// ```
+    pub fn speed_kmh(&self) -> Option<f64> {
+        self.speed_knots.map(|knots| knots * KMH_PER_KNOT)
+    }
+
+    /// Returns the speed over ground in m/s, converted from `speed_knots`.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The speed, or None if no speed was reported
+    pub fn speed_ms(&self) -> Option<f64> {
+        self.speed_knots.map(|knots| knots * KMH_PER_KNOT / 3.6)
+    }
+
+    /// Returns the UTC date and time of the current epoch.
+    ///
+    /// The date comes from the last RMC or ZDA sentence and the time from the current epoch
+    /// (GGA, RMC, GNS or GLL), or from the ZDA itself before any of those arrived. RMC carries a
+    /// two-digit year, read as 1980-2079; ZDA carries all four digits.
+    ///
+    /// Receivers report the time of a new day before the date: a GGA at 00:00:00 arrives while
+    /// the stored date still belongs to the previous day. A time more than 12 hours before the
+    /// time the date was reported at is therefore taken as the next day.
+    ///
+    /// # Returns
+    /// * `Option<UtcDateTime>` - The date and time, or None without a valid date and time
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPZDA,201530.00,04,07,2002,00,00*60").unwrap();
+    /// assert_eq!(gnss.utc_datetime().unwrap().to_string(), "2002-07-04T20:15:30.000Z");
+    /// ```
+    pub fn utc_datetime(&self) -> Option<UtcDateTime> {
+        let date = self.date.as_deref()?;
+        let field = |range: std::ops::Range<usize>| date.get(range)?.parse::<u16>().ok();
+        let (day, month, yy) = (field(0..2)?, field(2..4)?, field(4..6)?);
+        if !(1..=31).contains(&day) || !(1..=12).contains(&month) {
+            return None;
+        }
+        let year = self.date_year.unwrap_or(if yy >= 80 { 1900 + yy } else { 2000 + yy });
+        let time = self.parsed_time().or(self.date_reported_at)?;
+
+        let mut days = days_from_civil(i64::from(year), i64::from(month), i64::from(day));
+        if self.date_reported_at.is_some_and(|reported| time.millis_of_day() + 43_200_000 < reported.millis_of_day()) {
+            days += 1;
+        }
+        let (year, month, day) = civil_from_days(days);
+        Some(UtcDateTime {
+            year: u16::try_from(year).ok()?,
+            month,
+            day,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+            millis: time.millis,
+        })
+    }
+
+    /// Routes a sentence to its built-in or runtime-registered handler.
+    ///
+    /// # Returns
+    /// * `Option<SentenceType>` - The type of the sentence, or None as soon as no handler can accept it
+    fn dispatch(&mut self, parts: &[&str]) -> Option<SentenceType> {
+        let address = parts.first()?;
+        let talker = address.get(0..2)?;
+        let sentence_type = address.get(2..5)?;
+
+        if let Some(descriptor) = builtin_descriptor(sentence_type) {
+            let system = GnssSystem::from_talker(talker);
+            match descriptor.handler {
+                SentenceHandler::PerSystem(handler) => {
+                    handler(self, parts, system?.name());
+                    self.touch_system(system?.name());
+                }
+                SentenceHandler::CombinedOrPerSystem(handler) if talker == "GN" || system.is_some() => {
+                    handler(self, parts, system);
+                    if let Some(system) = system {
+                        self.touch_system(system.name());
+                    }
+                }
+                SentenceHandler::Any(handler) => handler(self, parts),
+                _ => return None,
+            }
+            SentenceType::builtin(sentence_type, system)
+        } else {
+            let handler = *self.custom_handlers.get(sentence_type)?;
+            // A panicking handler is deregistered rather than taking the parser down with it
+            let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| handler(self, parts)));
+            if outcome.is_err() {
+                self.custom_handlers.remove(sentence_type);
+                self.push_warning(DataWarning::ObserverPanicked { sentence_type: sentence_type.to_string() });
+            }
+            Some(SentenceType::Custom(sentence_type.to_string()))
+        }
+    }
+
+    /// Registers a handler for a sentence type the parser does not support natively.
+    ///
+    /// Registered handlers are consulted for any talker and counted in `ParserStats` like
+    /// built-in ones. Registering the same type again replaces the previous handler.
+    ///
+    /// Handlers run inline, in the middle of `feed_nmea`, so a slow handler delays the caller's
+    /// read loop. A handler may update the parser it receives but must not feed sentences to it.
+    /// A handler that panics is deregistered and a `DataWarning::ObserverPanicked` is raised; the
+    /// parser keeps working, although changes the handler made before panicking are kept.
+    /// Panics can only be caught when the crate is built with `panic = "unwind"`, the default.
+    ///
+    /// # Arguments
+    /// * `sentence_type` - Three-letter sentence type following the talker ID (e.g. "ROT")
+    /// * `handler` - Function receiving the parser and the sentence fields
+    ///
+    /// # Returns
+    /// * `bool` - false if the sentence type is handled natively and the handler was not registered
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert!(gnss.register_sentence_handler("ROT", |_, parts| {
+    ///     println!("Rate of turn: {}", parts[1]);
+    /// }));
+    /// gnss.feed_nmea("$HEROT,-0.3,A*05").unwrap();
+    /// assert_eq!(gnss.get_stats().sentence_count("ROT"), 1);
+    /// assert!(!gnss.register_sentence_handler("GGA", |_, _| {}));
+    /// ```
+    pub fn register_sentence_handler(&mut self, sentence_type: &str, handler: CustomSentenceHandler) -> bool {
+        if builtin_descriptor(sentence_type).is_some() {
+            return false;
+        }
+        self.custom_handlers.insert(sentence_type.to_string(), handler);
+        true
+    }
+
+    /// Calculates a fused position from all available GNSS systems using weighted averaging.
+    ///
+    /// The fused position is stored in `self.fused_position`.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.calculate_fused_position();
+    /// if let Some(fused) = gnss.fused_position() {
+    ///     println!("Fused position: {}, {}", fused.latitude, fused.longitude);
+    /// }
+    /// ```
+    pub fn calculate_fused_position(&mut self) {
+        let fused = self.weighted_fused_position();
+        self.store_fused_position(fused);
+    }
+
+    /// Returns the HDOP fusion uses for a system, following the DOP sources in the module docs.
+    fn fusion_hdop(&self, system_data: &GnssSystemData) -> Option<f64> {
+        system_data.hdop.or(self.combined_hdop).or(self.penalized_gga_hdop())
+    }
+
+    /// Returns the accuracy fusion uses for a system, inflated by `STALE_DGPS_PENALTY` while a
+    /// DGPS fix relies on corrections older than `DGPS_MAX_AGE_S`, by the factor of a
+    /// `DeadReckoningPolicy::Inflate` policy for a dead-reckoned position, and by the factor of a
+    /// `FaultPolicy::Inflate` policy for a system with a satellite flagged as failed.
+    fn fusion_accuracy(&self, system_data: &GnssSystemData) -> f64 {
+        let stale_dgps = self.fix_quality_kind() == FixQuality::Dgps
+            && self.dgps_age.is_some_and(|age| age > DGPS_MAX_AGE_S);
+        let mut accuracy = if stale_dgps { system_data.accuracy * STALE_DGPS_PENALTY } else { system_data.accuracy };
+        if let (DeadReckoningPolicy::Inflate(factor), true) = (self.dead_reckoning_policy, system_data.dead_reckoned) {
+            accuracy *= factor;
+        }
+        if let (FaultPolicy::Inflate(factor), true) = (self.fault_policy, system_data.integrity_fault) {
+            accuracy *= factor;
+        }
+        accuracy
+    }
+
+    /// Returns the combined horizontal accuracy fusion assigns a system, `max(hdop * accuracy,
+    /// accuracy)` with the fusion HDOP and accuracy, or None without an HDOP.
+    fn fusion_horizontal_accuracy(&self, system_data: &GnssSystemData) -> Option<f64> {
+        let hdop = self.fusion_hdop(system_data)?;
+        Some(combined_accuracy(hdop, self.fusion_accuracy(system_data)))
+    }
+
+    /// Returns the weight `calculate_fused_position` gives a system's position.
+    ///
+    /// Systems tracking fewer than four satellites or not eligible for fusion get no weight.
+    fn fusion_weight(&self, system_data: &GnssSystemData) -> Option<f64> {
+        if system_data.satellites_info.len() < 4 || !self.is_fusion_eligible(system_data) {
+            return None;
+        }
+        self.fusion_horizontal_accuracy(system_data).map(accuracy_weight)
+    }
+
+    /// Returns the GGA HDOP inflated by `GGA_HDOP_PENALTY`.
+    fn penalized_gga_hdop(&self) -> Option<f64> {
+        self.gga_hdop.map(|hdop| hdop * GGA_HDOP_PENALTY)
+    }
+
+    /// Iterates the systems in canonical order (GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC).
+    ///
+    /// The iteration order of the `systems` map changes from run to run, so everything that
+    /// lists systems or sums over them goes through here to give reproducible results.
+    fn systems_in_order(&self) -> impl Iterator<Item = (&'static str, &GnssSystemData)> {
+        GnssSystem::ALL.iter().filter_map(|system| {
+            let name = system.name();
+            self.systems.get(name).map(|system_data| (name, system_data))
+        })
+    }
+
+    /// Computes the weighted-average fused position without storing it.
+    #[allow(deprecated)]
+    fn weighted_fused_position(&self) -> Option<FusedPosition> {
+        let mut valid_positions = Vec::new();
+
+        for (system_name, system_data) in self.systems_in_order() {
+            if self.fusion_weight(system_data).is_some() {
+                let hdop = self.fusion_hdop(system_data);
+                if let (Some((lat, lon)), Some(hdop)) = (system_data.wgs84_coordinates(), hdop) {
+                    // 2D systems report no altitude and are left out of the altitude average
+                    let altitude = self.system_altitude_msl(system_data);
+                    let vdop = system_data.vdop.or(self.combined_vdop).unwrap_or(hdop * 1.5); // Default VDOP if not available
+                    let system_accuracy = self.fusion_accuracy(system_data);
+                    valid_positions.push((system_name.to_string(), lat, lon, altitude, hdop, vdop, system_accuracy));
+                }
+            }
+        }
+        self.drop_assumed_altitudes(valid_positions.iter_mut().map(|(system, _, _, altitude, ..)| (system.as_str(), altitude)));
+
+        if valid_positions.is_empty() {
+            return None;
+        }
+
+        if valid_positions.len() == 1 {
+            let (system, lat, lon, altitude, hdop, vdop, system_accuracy) = &valid_positions[0];
+            // Use system accuracy as multiplier instead of hardcoded 3.0
+            let horizontal_accuracy = combined_accuracy(*hdop, *system_accuracy);
+            let vertical_accuracy = (vdop * system_accuracy * 1.5).max(*system_accuracy * 1.5);
+
+            return Some(FusedPosition {
+                latitude: *lat,
+                longitude: *lon,
+                altitude: altitude.unwrap_or(f64::NAN),
+                altitude_msl: altitude.unwrap_or(f64::NAN),
+                altitude_ellipsoidal: None,
+                estimated_accuracy: horizontal_accuracy,
+                instantaneous_accuracy: horizontal_accuracy,
+                altitude_accuracy: if altitude.is_some() { vertical_accuracy } else { f64::INFINITY },
+                altitude_held: false,
+                altitude_source: AltitudeSource::Unavailable,
+                contributing_systems: vec![system.clone()],
+                time: self.time.clone(),
+                fix_quality: self.fused_fix_quality(std::slice::from_ref(system)),
+                heading: self.heading_true,
+            });
+        }
+
+        // Weighted average using inverse of combined accuracy (DOP + system accuracy) as weights
+        let mut weighted_lat = 0.0;
+        let mut longitude_weights = Vec::with_capacity(valid_positions.len());
+        let mut weighted_alt = 0.0;
+        let mut total_weight = 0.0;
+        let mut total_alt_weight = 0.0;
+        let mut contributing_systems = Vec::new();
+
+        for (system, lat, lon, altitude, hdop, vdop, system_accuracy) in &valid_positions {
+            // Use system accuracy as the multiplier for DOP values instead of hardcoded constants
+            let combined_vertical_accuracy = combined_accuracy(*vdop, system_accuracy * 1.5);
+
+            let weight = accuracy_weight(combined_accuracy(*hdop, *system_accuracy));
+            let alt_weight = accuracy_weight(combined_vertical_accuracy); // Weight for altitude
+
+            weighted_lat += lat * weight;
+            longitude_weights.push((*lon, weight));
+            total_weight += weight;
+            if let Some(altitude) = altitude {
+                weighted_alt += altitude * alt_weight;
+                total_alt_weight += alt_weight;
+            }
+            contributing_systems.push(system.clone());
+        }
+
+        if total_weight > 0.0 {
+            let fused_lat = weighted_lat / total_weight;
+            let fused_lon = weighted_mean_longitude(longitude_weights.into_iter());
+            let fused_alt = if total_alt_weight > 0.0 { weighted_alt / total_alt_weight } else { f64::NAN };
+
+            // Calculate fused accuracy based on weighted system accuracies and DOP values
+            let mut weighted_horizontal_accuracy = 0.0;
+            let mut weighted_vertical_accuracy = 0.0;
+            let mut total_weight = 0.0;
+            let mut total_alt_weight = 0.0;
+
+            for (_, _, _, altitude, hdop, vdop, system_accuracy) in &valid_positions {
+                // Use system accuracy as multiplier instead of hardcoded 2.0
+                let combined_horizontal_accuracy = combined_accuracy(*hdop, *system_accuracy);
+                let combined_vertical_accuracy = combined_accuracy(*vdop, system_accuracy * 1.5);
+
+                let weight = accuracy_weight(combined_horizontal_accuracy);
+                let alt_weight = accuracy_weight(combined_vertical_accuracy);
+
+                weighted_horizontal_accuracy += combined_horizontal_accuracy * weight;
+                total_weight += weight;
+                if altitude.is_some() {
+                    weighted_vertical_accuracy += combined_vertical_accuracy * alt_weight;
+                    total_alt_weight += alt_weight;
+                }
+            }
+
+            let final_horizontal_accuracy = if total_weight > 0.0 {
+                (weighted_horizontal_accuracy / total_weight).max(self.get_fused_accuracy())
+            } else {
+                self.get_fused_accuracy()
+            };
+            let final_vertical_accuracy = if total_alt_weight > 0.0 {
+                (weighted_vertical_accuracy / total_alt_weight)
+                    .max(self.get_fused_accuracy() * 1.5)
+            } else {
+                f64::INFINITY
+            };
+
+            Some(FusedPosition {
+                latitude: fused_lat,
+                longitude: fused_lon,
+                altitude: fused_alt,
+                altitude_msl: fused_alt,
+                altitude_ellipsoidal: None,
+                estimated_accuracy: final_horizontal_accuracy,
+                instantaneous_accuracy: final_horizontal_accuracy,
+                altitude_accuracy: final_vertical_accuracy,
+                altitude_held: false,
+                altitude_source: AltitudeSource::Unavailable,
+                fix_quality: self.fused_fix_quality(&contributing_systems),
+                contributing_systems,
+                time: self.time.clone(),
+                heading: self.heading_true,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Calculates an advanced fused position using a Kalman-like filtering approach.
+    ///
+    /// With three or more systems, a system farther than the outlier threshold from the median
+    /// system position is dropped before weighting (see `set_outlier_threshold`) and a
+    /// `DataWarning::OutlierRejected` is raised for it.
+    ///
+    /// The fused position is stored in `self.fused_position`.
+    pub fn calculate_advanced_fused_position(&mut self) {
+        let (fused, outliers) = self.advanced_fused_position();
+        for (system, distance) in outliers {
+            self.push_warning(DataWarning::OutlierRejected { system, distance });
+        }
+        self.store_fused_position(fused);
+    }
+
+    /// Computes the Kalman-like fused position without storing it.
+    ///
+    /// # Returns
+    /// * `(Option<FusedPosition>, Vec<(String, f64)>)` - The fused position, and the systems
+    ///   dropped as outliers with their distance in meters from the median position
+    #[allow(deprecated)]
+    fn advanced_fused_position(&self) -> (Option<FusedPosition>, Vec<(String, f64)>) {
+        let mut valid_positions = Vec::new();
+
+        for (system_name, system_data) in self.systems_in_order().filter(|(_, sys)| self.is_fusion_eligible(sys)) {
+            let hdop = self.fusion_hdop(system_data);
+            let pdop = system_data.pdop.or(self.combined_pdop).or(self.penalized_gga_hdop());
+            if let (Some((lat, lon)), Some(hdop), Some(pdop)) = (system_data.wgs84_coordinates(), hdop, pdop) {
+                let altitude = self.system_altitude_msl(system_data);
+                let vdop = system_data.vdop.or(self.combined_vdop).unwrap_or(pdop * 0.8); // Default VDOP if not available
+                let system_accuracy = self.fusion_accuracy(system_data);
+                valid_positions.push((system_name.to_string(), lat, lon, altitude, hdop, pdop, vdop, system_accuracy));
+            }
+        }
+        // The median of two positions is their midpoint, which cannot tell which one is off
+        let mut outliers = Vec::new();
+        if self.outlier_threshold > 0.0 && valid_positions.len() >= 3 {
+            let median_lat = median(valid_positions.iter().map(|(_, lat, ..)| *lat).collect());
+            let median_lon = median_longitude(valid_positions.iter().map(|(_, _, lon, ..)| *lon).collect());
+            valid_positions.retain(|(system, lat, lon, ..)| {
+                let distance = haversine_distance(median_lat, median_lon, *lat, *lon);
+                if distance > self.outlier_threshold {
+                    outliers.push((system.clone(), distance));
+                }
+                distance <= self.outlier_threshold
+            });
+        }
+        self.drop_assumed_altitudes(valid_positions.iter_mut().map(|(system, _, _, altitude, ..)| (system.as_str(), altitude)));
+
+        if valid_positions.is_empty() {
+            return (None, outliers);
+        }
+
+        // Kalman-like filtering approach
+        let mut weighted_lat = 0.0;
+        let mut longitude_weights = Vec::with_capacity(valid_positions.len());
+        let mut weighted_alt = 0.0;
+        let mut total_weight = 0.0;
+        let mut total_alt_weight = 0.0;
+        let mut contributing_systems = Vec::new();
+
+        for (system, lat, lon, altitude, hdop, pdop, vdop, system_accuracy) in &valid_positions {
+            // Combined weight using both HDOP, PDOP and system accuracy for horizontal accuracy
+            let combined_dop = (hdop * hdop + pdop * pdop).sqrt();
+            let combined_horizontal_accuracy = combined_dop.max(*system_accuracy);
+            let weight = 1.0 / (combined_horizontal_accuracy + 0.1);
+
+            // Weight for altitude based on VDOP, PDOP and system accuracy
+            let alt_combined_dop = (vdop * vdop + pdop * pdop).sqrt();
+            let combined_vertical_accuracy = alt_combined_dop.max(*system_accuracy * 1.5);
+            let alt_weight = 1.0 / (combined_vertical_accuracy + 0.1);
+
+            weighted_lat += lat * weight;
+            longitude_weights.push((*lon, weight));
+            total_weight += weight;
+            if let Some(altitude) = altitude {
+                weighted_alt += altitude * alt_weight;
+                total_alt_weight += alt_weight;
+            }
+            contributing_systems.push(system.clone());
+        }
+
+        let fused = if total_weight > 0.0 {
+            let fused_lat = weighted_lat / total_weight;
+            let fused_lon = weighted_mean_longitude(longitude_weights.into_iter());
+            let fused_alt = if total_alt_weight > 0.0 { weighted_alt / total_alt_weight } else { f64::NAN };
+
+            // Calculate confidence interval for horizontal accuracy using system accuracies
+            let variance: f64 = valid_positions.iter()
+                .map(|(_, lat, lon, _, hdop, _, _, system_accuracy)| {
+                    let combined_accuracy = hdop.max(*system_accuracy);
+                    let weight = 1.0 / (combined_accuracy + 0.1);
+                    let lat_diff = lat - fused_lat;
+                    // A degree of longitude spans cos(latitude) times the distance of a degree of latitude
+                    let lon_diff = wrap_longitude(lon - fused_lon) * fused_lat.to_radians().cos();
+                    weight * (lat_diff * lat_diff + lon_diff * lon_diff)
+                })
+                .sum::<f64>() / total_weight;
+
+            let estimated_accuracy = (variance.sqrt() * 111000.0).max(self.get_fused_accuracy()); // Convert to meters and apply minimum
+
+            // Calculate altitude variance and accuracy using system accuracies
+            let alt_variance: f64 = if total_alt_weight > 0.0 {
+                valid_positions.iter()
+                    .filter_map(|(_, _, _, altitude, _, _, vdop, system_accuracy)| Some((altitude.as_ref()?, vdop, system_accuracy)))
+                    .map(|(altitude, vdop, system_accuracy)| {
+                        let combined_accuracy = vdop.max(*system_accuracy * 1.5);
+                        let weight = 1.0 / (combined_accuracy + 0.1);
+                        let alt_diff = altitude - fused_alt;
+                        weight * (alt_diff * alt_diff)
+                    })
+                    .sum::<f64>() / total_alt_weight
+            } else {
+                0.0
+            };
+
+            let altitude_accuracy = if total_alt_weight == 0.0 {
+                f64::INFINITY // No contributing system reported an altitude
+            } else if alt_variance > 0.0 {
+                alt_variance.sqrt().max(self.get_fused_accuracy() * 1.5) // Minimum based on fused accuracy
+            } else {
+                (estimated_accuracy * 1.5).max(self.get_fused_accuracy() * 1.5) // Default to 1.5x horizontal accuracy
+            };
+
+            // Per-system GST sigmas replace the DOP heuristic when every contributing system has them
+            let sigma_of = |name: &String, sigma: fn(&GnssSystemData) -> Option<f64>| sigma(self.systems.get(name.as_str())?);
+            let horizontal_sigma = inverse_variance_sigma(
+                contributing_systems.iter().map(|name| sigma_of(name, GnssSystemData::horizontal_error_std)),
+            );
+            let altitude_sigma = inverse_variance_sigma(
+                valid_positions.iter()
+                    .filter(|(_, _, _, altitude, ..)| altitude.is_some())
+                    .map(|(name, ..)| sigma_of(name, |sys| sys.alt_error_std)),
+            );
+
+            // Apply minimum fused accuracy to the heuristic
+            let horizontal_accuracy = horizontal_sigma.unwrap_or(estimated_accuracy.max(self.get_fused_accuracy()));
+            Some(FusedPosition {
+                latitude: fused_lat,
+                longitude: fused_lon,
+                altitude: fused_alt,
+                altitude_msl: fused_alt,
+                altitude_ellipsoidal: None,
+                estimated_accuracy: horizontal_accuracy,
+                instantaneous_accuracy: horizontal_accuracy,
+                altitude_accuracy: altitude_sigma.unwrap_or(altitude_accuracy),
+                altitude_held: false,
+                altitude_source: AltitudeSource::Unavailable,
+                fix_quality: self.fused_fix_quality(&contributing_systems),
+                contributing_systems,
+                time: self.time.clone(),
+                heading: self.heading_true,
+            })
+        } else {
+            None
+        };
+        (fused, outliers)
+    }
+
+    /// Derives the fused fix quality from the best GNS mode of the contributing systems,
+    /// falling back to the GGA fix quality when no per-system mode is known.
+    fn fused_fix_quality(&self, contributing_systems: &[String]) -> Option<FixQuality> {
+        contributing_systems.iter()
+            .filter_map(|name| self.systems.get(name.as_str())?.mode_fix_quality())
+            .max_by_key(|quality| quality.rank())
+            .or_else(|| self.fix_quality.map(FixQuality::from))
+    }
+
+    /// Stores a freshly computed fused position and updates the state derived from it.
+    fn store_fused_position(&mut self, fused: Option<FusedPosition>) {
+        if fused.is_none() {
+            self.fusion_diagnostics = None;
+        }
+        let fused = fused.map(|fused| self.order_contributing_systems(fused));
+        let fused = fused.map(|fused| self.apply_rtk_accuracy(fused));
+        let fused = fused.map(|fused| self.apply_error_statistics(fused));
+        let fused = fused.map(|fused| self.classify_altitude_source(fused));
+        let fused = fused.map(|fused| self.check_altitude_agreement(fused));
+        let fused = fused.map(|fused| self.apply_altitude_hold(fused));
+        let fused = fused.map(|fused| self.fill_ellipsoidal_altitude(fused));
+        let fused = fused.map(|fused| self.apply_position_filter(fused));
+        let fused = self.apply_warmup(fused);
+        self.fused_position = if self.keep_best_only { self.select_best_fix(fused) } else { fused };
+        if self.fused_position.is_some() && self.stats.time_to_first_fix.is_none() {
+            self.record_first_fix();
+        }
+        self.update_derived_course();
+    }
+
+    /// Fills the ellipsoidal altitude from the MSL altitude when the geoid separation is known.
+    #[allow(deprecated)]
+    fn fill_ellipsoidal_altitude(&self, mut fused: FusedPosition) -> FusedPosition {
+        fused.altitude = fused.altitude_msl;
+        fused.altitude_ellipsoidal = self.geoid_separation_at(fused.latitude, fused.longitude)
+            .map(|separation| fused.altitude_msl + separation)
+            .filter(|altitude| altitude.is_finite());
+        fused
+    }
+
+    /// Lowers the estimated accuracies of an RTK fix to centimeter or decimeter level.
+    ///
+    /// The DOP heuristics scale meter-level system accuracies, which describe autonomous
+    /// fixes. An RTK fix is instead estimated as `RTK_FIXED_ACCURACY_M` or
+    /// `RTK_FLOAT_ACCURACY_M` times the solution HDOP (at least 1), and 1.5 times that
+    /// vertically. GST sigmas of the same epoch still take precedence.
+    fn apply_rtk_accuracy(&self, mut fused: FusedPosition) -> FusedPosition {
+        let nominal = match fused.fix_quality {
+            Some(FixQuality::RtkFixed) => RTK_FIXED_ACCURACY_M,
+            Some(FixQuality::RtkFloat) => RTK_FLOAT_ACCURACY_M,
+            _ => return fused,
+        };
+        let hdop = self.gga_hdop.or(self.combined_hdop).unwrap_or(1.0).max(1.0);
+        fused.estimated_accuracy = fused.estimated_accuracy.min(nominal * hdop);
+        fused.instantaneous_accuracy = fused.estimated_accuracy;
+        if fused.altitude_msl.is_finite() {
+            fused.altitude_accuracy = fused.altitude_accuracy.min(nominal * hdop * 1.5);
+        }
+        fused
+    }
+
+    /// Replaces the estimated accuracies with the receiver-reported sigmas of a GST sentence
+    /// from the same epoch.
+    ///
+    /// GST describes the receiver's combined solution, so the sigmas replace the accuracy
+    /// estimated from DOPs and the static system accuracies; the system weights are unchanged.
+    fn apply_error_statistics(&self, mut fused: FusedPosition) -> FusedPosition {
+        let Some(stats) = self.error_statistics.as_ref().filter(|stats| stats.time == fused.time) else {
+            return fused;
+        };
+        if let Some(sigma) = stats.horizontal_sigma() {
+            fused.estimated_accuracy = sigma;
+            fused.instantaneous_accuracy = sigma;
+        }
+        if let Some(sigma) = stats.sigma_altitude.filter(|_| fused.altitude_msl.is_finite()) {
+            fused.altitude_accuracy = sigma;
+        }
+        fused
+    }
+
+    /// Reorders the contributing systems of a fused position as configured.
+    ///
+    /// Fusion lists them in canonical order, which the sorts below keep for ties.
+    fn order_contributing_systems(&self, mut fused: FusedPosition) -> FusedPosition {
+        match self.contributing_order {
+            ContributingOrder::Canonical => {}
+            ContributingOrder::Name => fused.contributing_systems.sort(),
+            ContributingOrder::Accuracy => {
+                let accuracy = |name: &String| self.fusion_horizontal_accuracy(self.systems.get(name.as_str())?);
+                fused.contributing_systems.sort_by(|a, b| {
+                    let (a, b) = (accuracy(a).unwrap_or(f64::INFINITY), accuracy(b).unwrap_or(f64::INFINITY));
+                    a.total_cmp(&b)
+                });
+            }
+        }
+        fused
+    }
+
+    /// Gets the order in which fused positions list their contributing systems.
+    ///
+    /// # Returns
+    /// * `ContributingOrder` - The configured order (canonical by default)
+    pub fn get_contributing_order(&self) -> ContributingOrder {
+        self.contributing_order
+    }
+
+    /// Sets the order in which fused positions list their contributing systems.
+    ///
+    /// The order only affects `contributing_systems`; fusion always sums over the systems
+    /// in canonical order, so the fused values are identical for every setting.
+    ///
+    /// # Arguments
+    /// * `order` - The order to apply to subsequently fused positions
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{ContributingOrder, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_contributing_order(ContributingOrder::Name);
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*6C").unwrap();
+    /// gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*21").unwrap();
+    /// gnss.feed_nmea("$GNGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1*29").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// gnss.calculate_fused_position();
+    /// assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GLONASS", "GPS"]);
+    /// ```
+    pub fn set_contributing_order(&mut self, order: ContributingOrder) {
+        self.contributing_order = order;
+    }
+
+    /// Gets the accuracy exports degrade the fused position to.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The accuracy in meters, or None if privacy mode is disabled (default)
+    pub fn get_privacy_accuracy(&self) -> Option<f64> {
+        self.privacy_accuracy
+    }
+
+    /// Enables or disables privacy mode for exports.
+    ///
+    /// In privacy mode, `to_kml` writes the fused position as returned by
+    /// `FusedPosition::degraded`. The stored `fused_position` keeps its full precision.
+    ///
+    /// # Arguments
+    /// * `accuracy` - Accuracy in meters to degrade exported positions to, or None to disable
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_privacy_accuracy(Some(500.0));
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*21").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// gnss.calculate_fused_position();
+    /// assert!(gnss.to_kml().unwrap().contains("Horizontal accuracy: 500.00 m"));
+    /// ```
+    pub fn set_privacy_accuracy(&mut self, accuracy: Option<f64>) {
+        self.privacy_accuracy = accuracy;
+    }
+
+    /// Gets the geoid model used when no sentence reports the geoid separation.
+    ///
+    /// # Returns
+    /// * `Option<&GeoidGrid>` - The geoid model, or None if none is set (default)
+    #[cfg(feature = "geoid")]
+    pub fn get_geoid_model(&self) -> Option<&crate::geoid::GeoidGrid> {
+        self.geoid_model.as_ref()
+    }
+
+    /// Sets the geoid model used when no sentence reports the geoid separation.
+    ///
+    /// The model converts between ellipsoidal and MSL altitudes for receivers whose GGA leaves
+    /// the geoid separation empty. A separation reported by GGA or GNS takes precedence.
+    ///
+    /// # Arguments
+    /// * `model` - Geoid model, or None to rely on reported separations only
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::geoid::GeoidGrid;
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_geoid_model(Some(GeoidGrid::new(90.0, vec![47.0; 3 * 4]).unwrap()));
+    /// assert!(gnss.get_geoid_model().is_some());
+    /// ```
+    #[cfg(feature = "geoid")]
+    pub fn set_geoid_model(&mut self, model: Option<crate::geoid::GeoidGrid>) {
+        self.geoid_model = model;
+    }
+
+    /// Returns the geoid separation at a position: the reported one, else the geoid model's.
+    fn geoid_separation_at(&self, lat: f64, lon: f64) -> Option<f64> {
+        #[cfg(feature = "geoid")]
+        if let (None, Some(model)) = (self.geoid_separation, &self.geoid_model) {
+            return Some(model.geoid_separation_at(lat, lon));
+        }
+        #[cfg(not(feature = "geoid"))]
+        let _ = (lat, lon);
+        self.geoid_separation
+    }
+
+    /// Checks whether a system's altitude is only assumed, because its GSA reports a 2D fix.
+    fn has_assumed_altitude(&self, system: &str) -> bool {
+        self.systems.get(system).is_some_and(|sys| sys.fix_type == Some(FixType::Fix2D))
+    }
+
+    /// Leaves the assumed altitudes of 2D fixes out of the altitude average when a 3D fix
+    /// measured one.
+    fn drop_assumed_altitudes<'a>(&self, altitudes: impl Iterator<Item = (&'a str, &'a mut Option<f64>)>) {
+        let mut altitudes: Vec<_> = altitudes.collect();
+        if altitudes.iter().any(|(system, altitude)| altitude.is_some() && !self.has_assumed_altitude(system)) {
+            for (system, altitude) in &mut altitudes {
+                if self.has_assumed_altitude(system) {
+                    **altitude = None;
+                }
+            }
+        }
+    }
+
+    /// Derives where the fused altitude comes from.
+    ///
+    /// When every contributing system has a 2D fix the altitude accuracy becomes infinite, as
+    /// the altitude is only the receiver's assumption.
+    fn classify_altitude_source(&self, mut fused: FusedPosition) -> FusedPosition {
+        fused.altitude_source = if !fused.altitude_msl.is_finite() {
+            AltitudeSource::Unavailable
+        } else if fused.contributing_systems.iter().all(|system| self.has_assumed_altitude(system)) {
+            fused.altitude_accuracy = f64::INFINITY;
+            AltitudeSource::Constrained2D
+        } else {
+            AltitudeSource::Measured3D
+        };
+        fused
+    }
+
+    /// Returns a system's altitude above mean sea level, converting an ellipsoidal altitude.
+    ///
+    /// An ellipsoidal altitude is None while the geoid separation is unknown, so that systems
+    /// on different datums are never averaged together.
+    fn system_altitude_msl(&self, system_data: &GnssSystemData) -> Option<f64> {
+        let mut altitude = system_data.altitude?;
+        if let Some(datum) = system_data.datum.as_ref().filter(|datum| !datum.is_wgs84()) {
+            altitude -= datum.altitude_offset?;
+        }
+        match system_data.altitude_datum {
+            AltitudeDatum::Msl => Some(altitude),
+            AltitudeDatum::Ellipsoidal => {
+                let separation = self.geoid_separation_at(system_data.latitude?, system_data.longitude?)?;
+                Some(altitude - separation)
+            }
+        }
+    }
+
+    /// Chooses between a new fix and the stored best fix in keep-best-only mode.
+    ///
+    /// The new fix wins if nothing is stored, its estimated accuracy is at least as good, or
+    /// the stored fix is older than `best_fix_staleness`. Epochs without a fix keep the stored one.
+    fn select_best_fix(&mut self, candidate: Option<FusedPosition>) -> Option<FusedPosition> {
+        let now = self.current_time();
+        let stale = match (self.best_fix_staleness, self.best_fix_time) {
+            (Some(staleness), Some(accepted)) => now.duration_since(accepted).is_ok_and(|age| age > staleness),
+            _ => false,
+        };
+        match (candidate, self.fused_position.take()) {
+            (Some(candidate), Some(best)) if !stale && candidate.estimated_accuracy > best.estimated_accuracy => Some(best),
+            (Some(candidate), _) => {
+                self.best_fix_time = Some(now);
+                Some(candidate)
+            }
+            (None, best) => best,
+        }
+    }
+
+    /// Gets whether only fixes with better accuracy replace the stored fused position.
+    ///
+    /// # Returns
+    /// * `bool` - True in keep-best-only mode (false by default)
+    pub fn get_keep_best_only(&self) -> bool {
+        self.keep_best_only
+    }
+
+    /// Sets keep-best-only mode for best-so-far reporting such as static-point capture.
+    ///
+    /// In this mode `fused_position` is only replaced by a fix with equal or better (lower)
+    /// estimated accuracy, until `reset` is called or the stored fix becomes stale.
+    ///
+    /// # Arguments
+    /// * `keep_best_only` - True to keep the best fix of the session
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_keep_best_only(true);
+    /// assert!(gnss.get_keep_best_only());
+    /// ```
+    pub fn set_keep_best_only(&mut self, keep_best_only: bool) {
+        self.keep_best_only = keep_best_only;
+        self.best_fix_time = None;
+    }
+
+    /// Gets the age after which a kept best fix may be replaced by a worse one.
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - The staleness, or None if the best fix is kept indefinitely
+    pub fn get_best_fix_staleness(&self) -> Option<Duration> {
+        self.best_fix_staleness
+    }
+
+    /// Sets the age after which a kept best fix may be replaced by a worse one.
+    ///
+    /// Ages are measured with the host receive time of the sentences fed.
+    ///
+    /// # Arguments
+    /// * `staleness` - The staleness, or None to keep the best fix indefinitely (default)
+    pub fn set_best_fix_staleness(&mut self, staleness: Option<Duration>) {
+        self.best_fix_staleness = staleness;
+    }
+
+    /// Returns how long the altitude of the last 3D epoch is held for 2D epochs.
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - The hold duration, or None if held altitudes are disabled
+    pub fn get_altitude_hold(&self) -> Option<Duration> {
+        self.altitude_hold
+    }
+
+    /// Sets how long the altitude of the last 3D epoch is held for 2D epochs.
+    ///
+    /// An epoch is 3D when every contributing system reported an altitude. In any other
+    /// epoch, systems without an altitude are left out of the altitude average; with a hold
+    /// set, the fused altitude and its accuracy are instead taken from the last 3D epoch while
+    /// it is younger than `hold`, and `altitude_held` is set on the fused position. Ages are
+    /// measured with the host receive time of the sentences fed.
+    ///
+    /// # Arguments
+    /// * `hold` - The hold duration, or None to always recompute the altitude (default)
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_altitude_hold(Some(Duration::from_secs(30)));
+    /// assert_eq!(gnss.get_altitude_hold(), Some(Duration::from_secs(30)));
+    /// ```
+    pub fn set_altitude_hold(&mut self, hold: Option<Duration>) {
+        self.altitude_hold = hold;
+    }
+
+    /// Returns the drift rate of the horizontal position filter.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The drift rate in m/s, or None if positions are not filtered
+    pub fn get_position_filter(&self) -> Option<f64> {
+        self.position_filter
+    }
+
+    /// Enables or disables filtering of the fused horizontal position.
+    ///
+    /// The filter blends each fix into the previous filtered position, weighted by their
+    /// uncertainties. Between epochs, the filtered uncertainty grows by `drift` meters per
+    /// second of host receive time, so it shrinks over a stationary sequence and grows back
+    /// to the per-epoch accuracy after an outage. The filtered uncertainty becomes the
+    /// `estimated_accuracy` of the fused position; `instantaneous_accuracy` is left unfiltered.
+    /// A low drift rate smooths more but lags behind a moving receiver.
+    ///
+    /// # Arguments
+    /// * `drift` - How fast in m/s the true position may move, or None to disable filtering (default)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_position_filter(Some(0.1));
+    /// assert_eq!(gnss.get_position_filter(), Some(0.1));
+    /// ```
+    pub fn set_position_filter(&mut self, drift: Option<f64>) {
+        self.position_filter = drift.filter(|drift| drift.is_finite() && *drift >= 0.0);
+        self.filter_state = None;
+    }
+
+    /// Blends a fix into the filtered position when the position filter is enabled.
+    fn apply_position_filter(&mut self, mut fused: FusedPosition) -> FusedPosition {
+        fused.instantaneous_accuracy = fused.estimated_accuracy;
+        let Some(drift) = self.position_filter else {
+            return fused;
+        };
+        let now = self.current_time();
+        let measured_variance = fused.instantaneous_accuracy.powi(2);
+        let gain = match self.filter_state {
+            Some((latitude, longitude, variance, filtered_at)) => {
+                let elapsed = now.duration_since(filtered_at).unwrap_or(Duration::ZERO).as_secs_f64();
+                let predicted = variance + (drift * elapsed).powi(2);
+                let gain = if predicted + measured_variance > 0.0 { predicted / (predicted + measured_variance) } else { 1.0 };
+                fused.latitude = latitude + gain * (fused.latitude - latitude);
+                // Blend across the antimeridian along the shorter way
+                fused.longitude = wrap_longitude(longitude + gain * wrap_longitude(fused.longitude - longitude));
+                fused.estimated_accuracy = (predicted * (1.0 - gain)).sqrt();
+                gain
+            }
+            None => 1.0,
+        };
+        self.filter_state = Some((fused.latitude, fused.longitude, fused.estimated_accuracy.powi(2), now));
+        if let Some(diagnostics) = self.fusion_diagnostics.as_mut() {
+            diagnostics.filter_gain = Some(gain);
+        }
+        fused
+    }
+
+    /// Records the altitude of a 3D epoch, or substitutes the held altitude in a 2D epoch.
+    fn apply_altitude_hold(&mut self, mut fused: FusedPosition) -> FusedPosition {
+        let now = self.current_time();
+        let is_3d = fused.altitude_source == AltitudeSource::Measured3D && fused.contributing_systems.iter()
+            .all(|name| self.has_assumed_altitude(name) || self.systems.get(name.as_str()).is_some_and(|sys| self.system_altitude_msl(sys).is_some()));
+        if is_3d {
+            self.held_altitude = Some((fused.altitude_msl, fused.altitude_accuracy, now));
+            return fused;
+        }
+        if let (Some(hold), Some((altitude, accuracy, recorded))) = (self.altitude_hold, self.held_altitude) {
+            if now.duration_since(recorded).unwrap_or(Duration::ZERO) <= hold {
+                fused.altitude_msl = altitude;
+                fused.altitude_accuracy = accuracy;
+                fused.altitude_held = true;
+                fused.altitude_source = AltitudeSource::Constrained2D;
+            }
+        }
+        fused
+    }
+
+    /// Computes per-system altitude residuals against a fused position.
+    ///
+    /// When the spread between contributing system altitudes exceeds `altitude_spread_limit`,
+    /// the altitude accuracy is inflated by `spread / limit` and a warning is raised.
+    fn check_altitude_agreement(&mut self, mut fused: FusedPosition) -> FusedPosition {
+        let measured = fused.altitude_source == AltitudeSource::Measured3D;
+        let mut altitude_residuals: Vec<(String, f64)> = fused.contributing_systems.iter()
+            .filter(|name| !(measured && self.has_assumed_altitude(name)))
+            .filter_map(|name| {
+                let altitude = self.system_altitude_msl(self.systems.get(name.as_str())?)?;
+                Some((name.clone(), altitude - fused.altitude_msl))
+            })
+            .collect();
+        altitude_residuals.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let highest = altitude_residuals.iter().map(|(_, r)| *r).fold(f64::NEG_INFINITY, f64::max);
+        let lowest = altitude_residuals.iter().map(|(_, r)| *r).fold(f64::INFINITY, f64::min);
+        let altitude_spread = if altitude_residuals.is_empty() { 0.0 } else { highest - lowest };
+
+        let limit = self.altitude_spread_limit;
+        if limit > 0.0 && altitude_spread > limit {
+            fused.altitude_accuracy *= altitude_spread / limit;
+            self.push_warning(DataWarning::AltitudeDisagreement { spread: altitude_spread, limit });
+        }
+        self.fusion_diagnostics = Some(FusionDiagnostics { altitude_residuals, altitude_spread, filter_gain: None });
+        fused
+    }
+
+    /// Returns the diagnostics of the last fusion epoch.
+    ///
+    /// # Returns
+    /// * `Option<&FusionDiagnostics>` - The diagnostics, or None if the last epoch produced no position
+    pub fn fusion_diagnostics(&self) -> Option<&FusionDiagnostics> {
+        self.fusion_diagnostics.as_ref()
+    }
+
+    /// Returns the spread between the highest and lowest contributing system altitude.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The spread in meters, or None if the last epoch produced no position
+    pub fn altitude_spread_m(&self) -> Option<f64> {
+        Some(self.fusion_diagnostics.as_ref()?.altitude_spread)
+    }
+
+    /// Gets the altitude spread limit above which altitude accuracy is inflated.
+    ///
+    /// # Returns
+    /// * `f64` - The limit in meters (0 disables the check)
+    pub fn get_altitude_spread_limit(&self) -> f64 {
+        self.altitude_spread_limit
+    }
+
+    /// Sets the altitude spread limit above which altitude accuracy is inflated.
+    ///
+    /// # Arguments
+    /// * `limit_m` - The limit in meters (0 disables the check)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_altitude_spread_limit(30.0);
+    /// assert_eq!(gnss.get_altitude_spread_limit(), 30.0);
+    /// ```
+    pub fn set_altitude_spread_limit(&mut self, limit_m: f64) {
+        self.altitude_spread_limit = limit_m;
+    }
+
+    /// Gets the distance from the median system position beyond which the advanced fusion
+    /// drops a system.
+    ///
+    /// # Returns
+    /// * `f64` - The threshold in meters (0 disables outlier rejection)
+    pub fn get_outlier_threshold(&self) -> f64 {
+        self.outlier_threshold
+    }
+
+    /// Sets the distance from the median system position beyond which the advanced fusion
+    /// drops a system.
+    ///
+    /// The median is taken over the latitudes and longitudes of the eligible systems; it needs
+    /// at least three systems, so fewer are never rejected.
+    ///
+    /// # Arguments
+    /// * `meters` - The threshold in meters (0 disables outlier rejection)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_outlier_threshold(50.0);
+    /// assert_eq!(gnss.get_outlier_threshold(), 50.0);
+    /// ```
+    pub fn set_outlier_threshold(&mut self, meters: f64) {
+        self.outlier_threshold = meters;
+    }
+
+    /// Gets the plausible DOP range; DOPs outside it are treated as missing.
+    ///
+    /// # Returns
+    /// * `(f64, f64)` - The floor and ceiling (defaults `DEFAULT_DOP_FLOOR` and `DEFAULT_DOP_CEILING`)
+    pub fn get_plausible_dop_range(&self) -> (f64, f64) {
+        (self.dop_floor, self.dop_ceiling)
+    }
+
+    /// Sets the plausible DOP range.
+    ///
+    /// A reported DOP below the floor or above the ceiling is dropped as if the field were
+    /// empty, so fusion falls back to the next DOP source or leaves the system out, and a
+    /// `DataWarning::ImplausibleDop` is raised.
+    ///
+    /// # Arguments
+    /// * `floor` - DOP below which values are treated as missing
+    /// * `ceiling` - DOP above which values are treated as missing
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_plausible_dop_range(0.5, 20.0);
+    /// assert_eq!(gnss.get_plausible_dop_range(), (0.5, 20.0));
+    /// ```
+    pub fn set_plausible_dop_range(&mut self, floor: f64, ceiling: f64) {
+        self.dop_floor = floor;
+        self.dop_ceiling = ceiling;
+    }
+
+    /// Gets the PRNs a "GN" GSA attributes to NavIC.
+    ///
+    /// # Returns
+    /// * `RangeInclusive<u16>` - The NavIC PRN range (`DEFAULT_NAVIC_PRNS` by default)
+    pub fn get_navic_prn_range(&self) -> RangeInclusive<u16> {
+        self.navic_prns.clone().unwrap_or(DEFAULT_NAVIC_PRNS)
+    }
+
+    /// Sets the PRNs a "GN" GSA attributes to NavIC.
+    ///
+    /// Receivers disagree on NavIC numbering, so the standard 401-414 can be replaced. The range
+    /// takes precedence over the ranges of the other systems. PRNs from the "GI" talker always
+    /// belong to NavIC, whatever the range.
+    ///
+    /// # Arguments
+    /// * `range` - PRNs to attribute to NavIC
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_navic_prn_range(247..=260);
+    /// gnss.feed_nmea("$GNGSA,A,3,247,248,,,,,,,,,,,1.5,0.8,1.2*2C").unwrap();
+    /// assert_eq!(gnss.system("NAVIC").unwrap().satellites_used(), [247, 248]);
+    /// ```
+    pub fn set_navic_prn_range(&mut self, range: RangeInclusive<u16>) {
+        self.navic_prns = Some(range);
+    }
+
+    /// Gets how far a position sentence's time may trail the newest time seen.
+    ///
+    /// # Returns
+    /// * `Duration` - The tolerance (`DEFAULT_LATE_TOLERANCE` by default)
+    pub fn get_late_tolerance(&self) -> Duration {
+        self.late_tolerance
+    }
+
+    /// Sets how far a position sentence's time may trail the newest time seen.
+    ///
+    /// On congested links a position sentence (GGA, GNS, RMC, GLL) can arrive after sentences
+    /// of the next epoch. One whose UTC time is older than the newest time already seen by more
+    /// than the tolerance is ignored and counted in `ParserStats::late_sentences`, so it cannot
+    /// overwrite newer data. The tolerance absorbs sub-second timestamp jitter between the
+    /// sentences of an epoch on multi-Hz receivers. Times up to 12 hours behind count as older,
+    /// so the day rollover at midnight is not mistaken for a late sentence.
+    ///
+    /// # Arguments
+    /// * `tolerance` - Largest accepted lag behind the newest time; `Duration::MAX` disables the check
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_late_tolerance(Duration::ZERO);
+    /// gnss.feed_nmea("$GPGLL,4916.46,N,12311.12,W,225445,A*33").unwrap();
+    /// gnss.feed_nmea("$GPGLL,4916.45,N,12311.12,W,225444,A*31").unwrap();
+    /// assert_eq!(gnss.time(), Some("225445"));
+    /// assert_eq!(gnss.get_stats().late_sentences(), 1);
+    /// ```
+    pub fn set_late_tolerance(&mut self, tolerance: Duration) {
+        self.late_tolerance = tolerance;
+    }
+
+    /// Checks a position sentence's time against the newest time seen, counting late sentences.
+    ///
+    /// # Returns
+    /// * `bool` - True if the sentence is late and must be ignored
+    fn is_late_position(&mut self, time: Option<&&str>) -> bool {
+        let Some(time) = time.and_then(|time| parse_utc_time(time)) else {
+            return false;
+        };
+        const DAY_MS: u32 = 86_400_000;
+        let newest = *self.newest_position_time.get_or_insert(time);
+        let behind = (DAY_MS + newest.millis_of_day() - time.millis_of_day()) % DAY_MS;
+        if behind == 0 || behind >= DAY_MS / 2 {
+            self.newest_position_time = Some(time);
+            return false;
+        }
+        if u128::from(behind) <= self.late_tolerance.as_millis() {
+            return false;
+        }
+        self.stats.late_sentences += 1;
+        true
+    }
+
+    /// Drops a DOP outside the plausible range, raising a warning.
+    fn plausible_dop(&mut self, sentence_type: &str, dop: Option<f64>) -> Option<f64> {
+        let value = dop?;
+        if (self.dop_floor..=self.dop_ceiling).contains(&value) {
+            return Some(value);
+        }
+        self.push_warning(DataWarning::ImplausibleDop { sentence_type: sentence_type.to_string(), value });
+        None
+    }
+
+    /// Records the time to first fix and classifies the start.
+    ///
+    /// The classification uses the signal ramp-up: the time until `RAMP_SATELLITES` satellites
+    /// reported an SNR, or the time to first fix if that never happened first.
+    fn record_first_fix(&mut self) {
+        let Some(ttff) = self.time_since_acquisition_start() else {
+            return;
+        };
+        let ramp = self.signal_ramp.unwrap_or(ttff).min(ttff);
+        self.stats.time_to_first_fix = Some(ttff);
+        self.stats.start_type = Some(if ramp <= self.hot_start_max {
+            StartType::Hot
+        } else if ramp <= self.warm_start_max {
+            StartType::Warm
+        } else {
+            StartType::Cold
+        });
+    }
+
+    /// Returns the time from creation or the last reset to the first fused fix.
+    ///
+    /// Times are taken from the host receive time of the sentences fed (see `feed_nmea_at`).
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - The time to first fix, or None before the first fused fix
+    pub fn time_to_first_fix(&self) -> Option<Duration> {
+        self.stats.time_to_first_fix
+    }
+
+    /// Returns the cold/warm/hot start classification made at the first fused fix.
+    ///
+    /// # Returns
+    /// * `Option<StartType>` - The classification, or None before the first fused fix
+    pub fn start_type(&self) -> Option<StartType> {
+        self.stats.start_type
+    }
+
+    /// Gets the signal ramp-up thresholds used to classify the start.
+    ///
+    /// # Returns
+    /// * `(Duration, Duration)` - Longest ramp-up classified as hot and as warm
+    pub fn get_start_thresholds(&self) -> (Duration, Duration) {
+        (self.hot_start_max, self.warm_start_max)
+    }
+
+    /// Sets the signal ramp-up thresholds used to classify the start.
+    ///
+    /// A ramp-up up to `hot_max` is a hot start, up to `warm_max` a warm start, and anything
+    /// longer a cold start. Defaults are `DEFAULT_HOT_START_MAX` and `DEFAULT_WARM_START_MAX`.
+    ///
+    /// # Arguments
+    /// * `hot_max` - Longest ramp-up classified as a hot start
+    /// * `warm_max` - Longest ramp-up classified as a warm start
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_start_thresholds(Duration::from_secs(5), Duration::from_secs(40));
+    /// assert_eq!(gnss.get_start_thresholds(), (Duration::from_secs(5), Duration::from_secs(40)));
+    /// ```
+    pub fn set_start_thresholds(&mut self, hot_max: Duration, warm_max: Duration) {
+        self.hot_start_max = hot_max;
+        self.warm_start_max = warm_max;
+    }
+
+    /// Derives course-made-good from consecutive fused fixes.
+    ///
+    /// A bearing is only taken once the position has moved from the last anchor fix by more
+    /// than `course_gate_factor` times the fix accuracy; smaller movements are treated as noise
+    /// and clear the derived course. Accepted bearings are smoothed with a circular mean over
+    /// the last `course_window` values.
+    fn update_derived_course(&mut self) {
+        let Some(fused) = &self.fused_position else {
+            self.course_anchor = None;
+            self.course_history.clear();
+            self.derived_course = None;
+            return;
+        };
+        let current = (fused.latitude, fused.longitude, fused.estimated_accuracy);
+        let Some((anchor_lat, anchor_lon, anchor_accuracy)) = self.course_anchor else {
+            self.course_anchor = Some(current);
+            return;
+        };
+
+        let moved = haversine_distance(anchor_lat, anchor_lon, current.0, current.1);
+        let gate = self.course_gate_factor * anchor_accuracy.max(current.2);
+        if moved <= gate {
+            self.course_history.clear();
+            self.derived_course = None;
+            return;
+        }
+
+        self.course_history.push(initial_bearing(anchor_lat, anchor_lon, current.0, current.1));
+        let window = self.course_window.max(1);
+        if self.course_history.len() > window {
+            let excess = self.course_history.len() - window;
+            self.course_history.drain(..excess);
+        }
+        let (sin_sum, cos_sum) = self.course_history.iter()
+            .fold((0.0, 0.0), |(s, c), bearing: &f64| (s + bearing.to_radians().sin(), c + bearing.to_radians().cos()));
+        self.derived_course = Some(sin_sum.atan2(cos_sum).to_degrees().rem_euclid(360.0));
+        self.course_anchor = Some(current);
+    }
+
+    /// Applies the warm-up gate to a freshly computed fused position.
+    ///
+    /// Every epoch that yields a position increments the consecutive-epoch counter, while an
+    /// epoch without a position (a dropout) resets it. Positions are suppressed until more than
+    /// `warmup_epochs` consecutive good epochs have been seen.
+    fn apply_warmup(&mut self, fused: Option<FusedPosition>) -> Option<FusedPosition> {
+        if fused.is_none() {
+            self.consecutive_good_epochs = 0;
+            return None;
+        }
+        self.consecutive_good_epochs = self.consecutive_good_epochs.saturating_add(1);
+        if self.consecutive_good_epochs > self.warmup_epochs {
+            fused
+        } else {
+            None
+        }
+    }
+
+    /// Gets the fused data accuracy in meters.
+    ///
+    /// # Returns
+    /// * `f64` - The fused accuracy value in meters
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let gnss = GnssData::new();
+    /// // The fused accuracy is calculated using RSS formula from active system accuracies
+    /// assert!((gnss.get_fused_accuracy() - 1.19).abs() < 0.01);
+    /// ```
+    pub fn get_fused_accuracy(&self) -> f64 {
+        let mut active_systems = Vec::new();
+        for (_, system_data) in self.systems_in_order() {
+            if !system_data.satellites_info.is_empty() &&
+               system_data.latitude.is_some() &&
+               system_data.longitude.is_some() {
+                active_systems.push(system_data.accuracy);
+            }
+        }
+        if active_systems.is_empty() {
+            // If no active systems, use all system dynamic accuracies
+            active_systems = self.systems_in_order().map(|(_, sys)| sys.accuracy).collect();
+        }
+        let sum_of_inverse_squares: f64 = active_systems.iter()
+            .map(|accuracy| 1.0 / accuracy.powi(2))
+            .sum();
+        if sum_of_inverse_squares == 0.0 {
+            return 0.0;
+        }
+        1.0 / sum_of_inverse_squares.sqrt()
+    }
+
+    /// Sets the fused data accuracy in meters.
+    ///
+    /// # Arguments
+    /// * `accuracy` - The fused accuracy value in meters
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_fused_accuracy(3.0);
+    /// // Fused accuracy is always dynamically calculated, not set by this function
+    /// let fused_accuracy = gnss.get_fused_accuracy();
+    /// assert!((fused_accuracy - 1.1882).abs() < 0.01);
+    /// ```
+    pub fn set_fused_accuracy(&mut self, _accuracy: f64) {
+        // No-op: fused accuracy is now always dynamic
+    }
+
+    /// Gets the dynamic accuracy for a specific GNSS system in meters.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The system accuracy value in meters, or None if system doesn't exist
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let gnss = GnssData::new();
+    /// assert_eq!(gnss.get_system_accuracy("GPS"), Some(2.0));
+    /// assert_eq!(gnss.get_system_accuracy("GLONASS"), Some(4.0));
+    /// ```
+    pub fn get_system_accuracy(&self, system: &str) -> Option<f64> {
+        self.systems.get(system).map(|sys| sys.accuracy)
+    }
+
+    /// Gets the fixed accuracy for a specific GNSS system in meters.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The fixed accuracy value in meters, or None if the system doesn't exist
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let gnss = GnssData::new();
+    /// assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(2.0));
+    /// assert_eq!(gnss.get_system_fixed_accuracy("GLONASS"), Some(4.0));
+    /// ```
+    pub fn get_system_fixed_accuracy(&self, system: &str) -> Option<f64> {
+        self.systems.get(system).map(|sys| sys.fixed_accuracy)
+    }
+
+    /// Sets the accuracy for a specific GNSS system in meters.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    /// * `accuracy` - The system accuracy value in meters
+    ///
+    /// # Returns
+    /// * `bool` - True if the system exists and accuracy was set, false otherwise
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert!(gnss.set_system_fixed_accuracy("GPS", 1.5));
+    /// assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(1.5));
+    /// assert!(!gnss.set_system_fixed_accuracy("INVALID", 1.0));
+    /// ```
+    pub fn set_system_fixed_accuracy(&mut self, system: &str, accuracy: f64) -> bool {
+        if let Some(sys) = self.systems.get_mut(system) {
+            sys.fixed_accuracy = accuracy;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reports whether a system's receiver is in automatic 2D/3D selection mode.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    ///
+    /// # Returns
+    /// * `Option<bool>` - True for automatic ('A'), false for manual ('M'), or None if unknown
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,09,10,11,12,1.2,0.9,2.1*26").unwrap();
+    /// assert_eq!(gnss.is_auto_mode("GPS"), Some(true));
+    /// assert_eq!(gnss.is_auto_mode("GLONASS"), None);
+    /// ```
+    pub fn is_auto_mode(&self, system: &str) -> Option<bool> {
+        match self.systems.get(system)?.selection_mode? {
+            'A' => Some(true),
+            'M' => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns how long ago a system last completed a GSV cycle.
+    ///
+    /// A growing age means the receiver stopped sending satellite detail for that system,
+    /// even if position sentences keep arriving.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    /// * `now` - The reference time to measure the age against, usually `now()`
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - The age, or None if the system is unknown or never completed a cycle
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert_eq!(gnss.gsv_cycle_age("GPS", gnss.now()), None);
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// assert!(gnss.gsv_cycle_age("GPS", gnss.now()).is_some());
+    /// ```
+    pub fn gsv_cycle_age(&self, system: &str, now: Timestamp) -> Option<Duration> {
+        let completed = self.systems.get(system)?.last_gsv_cycle?;
+        Some(now.duration_since(completed).unwrap_or(Duration::ZERO))
+    }
+
+    /// Returns how long a satellite has been continuously in view.
+    ///
+    /// The duration is measured from the GSV cycle that started the current visibility run
+    /// for the PRN. A cycle that omits the PRN ends the run, so a reacquired satellite starts
+    /// over; a long lock means a low risk of cycle slips. Requires satellite history.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    /// * `prn` - Satellite PRN
+    /// * `now` - The reference time to measure the lock against, usually `now()`
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - The lock duration, or None if history is disabled or the satellite is not in view
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_satellite_history(true);
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// let lock = gnss.lock_duration("GPS", 1, gnss.now() + Duration::from_secs(5)).unwrap();
+    /// assert!(lock >= Duration::from_secs(5));
+    /// ```
+    pub fn lock_duration(&self, system: &str, prn: u16, now: Timestamp) -> Option<Duration> {
+        let start = *self.systems.get(system)?.lock_starts.get(&prn)?;
+        Some(now.duration_since(start).unwrap_or(Duration::ZERO))
+    }
+
+    /// Gets whether per-satellite history is kept.
+    ///
+    /// # Returns
+    /// * `bool` - True if satellite history is enabled (false by default)
+    pub fn get_satellite_history(&self) -> bool {
+        self.satellite_history
+    }
+
+    /// Enables or disables per-satellite history, such as continuous lock runs.
+    ///
+    /// Disabling the history discards what was collected.
+    ///
+    /// # Arguments
+    /// * `enabled` - True to keep satellite history
+    pub fn set_satellite_history(&mut self, enabled: bool) {
+        self.satellite_history = enabled;
+        if !enabled {
+            for system_data in self.systems.values_mut() {
+                system_data.lock_starts.clear();
+            }
+        }
+    }
+
+    /// Gets all system accuracies as a HashMap.
+    ///
+    /// # Returns
+    /// * `HashMap<String, f64>` - Map of system names to their accuracy values in meters
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let gnss = GnssData::new();
+    /// let accuracies = gnss.get_all_system_accuracies();
+    /// assert_eq!(accuracies.get("GPS"), Some(&2.0));
+    /// assert_eq!(accuracies.get("GLONASS"), Some(&4.0));
+    /// ```
+    pub fn get_all_system_accuracies(&self) -> HashMap<String, f64> {
+        self.systems.iter()
+            .map(|(name, sys)| (name.to_string(), sys.fixed_accuracy))
+            .collect()
+    }
+
+    /// Gets the latest status messages from TXT sentences, oldest first.
+    ///
+    /// At most `MAX_TEXT_MESSAGES` messages are kept; older ones are dropped.
+    ///
+    /// # Returns
+    /// * `&[TxtMessage]` - The stored messages
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNTXT,01,01,01,ANTENNA OPEN*3B").unwrap();
+    /// let message = &gnss.text_messages()[0];
+    /// assert_eq!((message.severity, message.text.as_str()), (1, "ANTENNA OPEN"));
+    /// ```
+    pub fn text_messages(&self) -> &[TxtMessage] {
+        &self.text_messages
+    }
+
+    /// Iterates over the stored TXT messages, newest first.
+    ///
+    /// # Returns
+    /// * `impl Iterator<Item = &TxtMessage>` - The stored messages, most recent first
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, TxtSeverity};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNTXT,01,01,02,ANTSTATUS=OK*25").unwrap();
+    /// gnss.feed_nmea("$GNTXT,01,01,01,ANTENNA OPEN*3B").unwrap();
+    /// let latest = gnss.recent_messages().next().unwrap();
+    /// assert_eq!(latest.level(), TxtSeverity::Warning);
+    /// ```
+    pub fn recent_messages(&self) -> impl Iterator<Item = &TxtMessage> {
+        self.text_messages.iter().rev()
+    }
+
+    /// Gets the latest antenna status reported in a TXT message.
+    ///
+    /// The status is kept when the stored messages are cleared or dropped.
+    ///
+    /// # Returns
+    /// * `Option<AntennaStatus>` - The antenna status, or None if the receiver has not reported one
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{AntennaStatus, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNTXT,01,01,01,ANTENNA OPEN*3B").unwrap();
+    /// assert_eq!(gnss.antenna_status(), Some(AntennaStatus::Open));
+    /// ```
+    pub fn antenna_status(&self) -> Option<AntennaStatus> {
+        self.antenna_status
+    }
+
+    /// Clears the stored TXT messages.
+    pub fn clear_text_messages(&mut self) {
+        self.text_messages.clear();
+    }
+
+    /// Drains and returns the pending data-quality warnings, oldest first.
+    ///
+    /// At most `MAX_PENDING_WARNINGS` warnings are kept between calls; older ones are dropped.
+    ///
+    /// # Returns
+    /// * `Vec<DataWarning>` - The pending warnings
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{DataWarning, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+    /// assert!(matches!(gnss.take_warnings()[0], DataWarning::ChecksumMismatch { .. }));
+    /// assert!(gnss.take_warnings().is_empty());
+    /// ```
+    pub fn take_warnings(&mut self) -> Vec<DataWarning> {
+        self.warnings.drain(..).collect()
+    }
+
+    /// Gets the number of sealed epoch records kept.
+    ///
+    /// # Returns
+    /// * `usize` - The capacity (0 means recording is disabled, the default)
+    pub fn get_epoch_recording(&self) -> usize {
+        self.epoch_capacity
+    }
+
+    /// Enables or disables recording an `EpochData` record at every epoch boundary.
+    ///
+    /// An epoch is sealed when a GGA, GNS, RMC or GLL carries a new UTC time, so the current
+    /// epoch is only recorded once the next one starts. When `capacity` records are waiting
+    /// to be taken, the oldest is dropped.
+    ///
+    /// # Arguments
+    /// * `capacity` - Number of records kept, or 0 to disable recording and drop kept records
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_epoch_recording(10);
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*53").unwrap();
+    /// let epochs = gnss.take_epochs();
+    /// assert_eq!(epochs.len(), 1);
+    /// assert_eq!(epochs[0].time.as_deref(), Some("123519"));
+    /// ```
+    pub fn set_epoch_recording(&mut self, capacity: usize) {
+        self.epoch_capacity = capacity;
+        while self.epochs.len() > capacity {
+            self.epochs.pop_front();
+        }
+    }
+
+    /// Gets whether epoch records leave out the tracked satellites.
+    ///
+    /// # Returns
+    /// * `bool` - true if `SystemEpoch::satellites` is None in new records (default false)
+    pub fn get_epoch_satellite_elision(&self) -> bool {
+        self.elide_epoch_satellites
+    }
+
+    /// Sets whether epoch records leave out the tracked satellites, to save memory.
+    ///
+    /// # Arguments
+    /// * `elide` - true to record None instead of the satellite snapshots
+    pub fn set_epoch_satellite_elision(&mut self, elide: bool) {
+        self.elide_epoch_satellites = elide;
+    }
+
+    /// Drains and returns the sealed epoch records, oldest first.
+    ///
+    /// # Returns
+    /// * `Vec<EpochData>` - The records sealed since the last call
+    pub fn take_epochs(&mut self) -> Vec<EpochData> {
+        self.epochs.drain(..).collect()
+    }
+
+    /// Records the state of the epoch that is ending, dropping the oldest record when full.
+    fn seal_epoch(&mut self) {
+        let systems = self.systems_in_order()
+            .map(|(name, sys)| SystemEpoch {
+                system: name.to_string(),
+                latitude: sys.latitude,
+                longitude: sys.longitude,
+                altitude: sys.altitude,
+                pdop: sys.pdop,
+                hdop: sys.hdop,
+                vdop: sys.vdop,
+                satellites_used: sys.satellites_used.clone(),
+                satellites: (!self.elide_epoch_satellites).then(|| sys.satellites_info.values().cloned().collect()),
+            })
+            .collect();
+        let epoch = EpochData {
+            time: self.time.clone(),
+            latitude: self.latitude,
+            longitude: self.longitude,
+            altitude: self.altitude,
+            fix_quality: self.fix_quality.map(FixQuality::from),
+            num_satellites: self.num_satellites,
+            systems,
+            warnings: std::mem::take(&mut self.epoch_warnings),
+            fused_position: self.fused_position.clone().filter(|fused| fused.time == self.time),
+        };
+        if self.epochs.len() >= self.epoch_capacity {
+            self.epochs.pop_front();
+        }
+        self.epochs.push_back(epoch);
+    }
+
+    /// Queues a data-quality warning, dropping the oldest one when the queue is full.
+    fn push_warning(&mut self, warning: DataWarning) {
+        if self.epoch_capacity > 0 {
+            self.epoch_warnings.push(warning.clone());
+        }
+        if self.warnings.len() >= MAX_PENDING_WARNINGS {
+            self.warnings.pop_front();
+        }
+        self.warnings.push_back(warning);
+    }
+
+    /// Gets the parser statistics collected so far.
+    ///
+    /// # Returns
+    /// * `&ParserStats` - Reference to the statistics
+    pub fn get_stats(&self) -> &ParserStats {
+        &self.stats
+    }
+
+    /// Summarizes the receiver's capabilities as inferred from the sentences fed so far.
+    ///
+    /// Detection is passive. Constellations are those that reported satellites. The sentence
+    /// types come from the statistics. Signal IDs in GSV and system IDs in GSA imply NMEA 4.10 or
+    /// later, and mode indicators in RMC, GLL or VTG imply NMEA 2.3 or later. The update rate
+    /// follows from the shortest interval between epoch times.
+    ///
+    /// # Returns
+    /// * `ReceiverProfile` - The inferred profile
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, GnssSystem};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// gnss.feed_nmea("$GPGLL,4916.45,N,12311.12,W,225444,A*31").unwrap();
+    /// gnss.feed_nmea("$GPGLL,4916.46,N,12311.12,W,225445,A*33").unwrap();
+    /// let profile = gnss.receiver_profile();
+    /// assert_eq!(profile.constellations, vec![GnssSystem::Gps]);
+    /// assert_eq!(profile.update_rate_hz, Some(1.0));
+    /// assert_eq!(profile.min_nmea_version, None);
+    /// ```
+    pub fn receiver_profile(&self) -> ReceiverProfile {
+        let features = &self.features;
+        let min_nmea_version = if features.signal_ids || features.system_ids {
+            Some("4.10")
+        } else if features.mode_indicators {
+            Some("2.3")
+        } else {
+            None
+        };
+        ReceiverProfile {
+            constellations: GnssSystem::ALL.iter().copied().filter(|system| features.systems.contains(system)).collect(),
+            sentence_types: self.stats.sentence_counts().into_iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(sentence_type, _)| sentence_type)
+                .collect(),
+            signal_ids: features.signal_ids,
+            system_ids: features.system_ids,
+            mode_indicators: features.mode_indicators,
+            min_nmea_version,
+            update_rate_hz: features.shortest_epoch_interval.map(|interval| 1_000.0 / f64::from(interval)),
+            rtk: features.rtk,
+        }
+    }
+
+    /// Sets the capacity limits for tracked satellites.
+    ///
+    /// When a GSV sentence would exceed a limit, the weakest satellite (lowest SNR, then least
+    /// recently updated) is evicted and counted in `ParserStats::satellite_evictions`.
+    ///
+    /// # Arguments
+    /// * `per_system` - Maximum satellites per system (0 means unlimited)
+    /// * `total` - Maximum satellites across all systems (0 means unlimited)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_satellite_capacity(32, 96);
+    /// assert_eq!(gnss.get_satellite_capacity(), (32, 96));
+    /// ```
+    pub fn set_satellite_capacity(&mut self, per_system: usize, total: usize) {
+        self.max_satellites_per_system = per_system;
+        self.max_satellites_total = total;
+    }
+
+    /// Gets the capacity limits for tracked satellites.
+    ///
+    /// # Returns
+    /// * `(usize, usize)` - Per-system and overall satellite limits (0 means unlimited)
+    pub fn get_satellite_capacity(&self) -> (usize, usize) {
+        (self.max_satellites_per_system, self.max_satellites_total)
+    }
+
+    /// Gets the decimal separator expected in numeric fields.
+    ///
+    /// # Returns
+    /// * `char` - '.' (the NMEA default) or ','
+    pub fn get_decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+
+    /// Sets the decimal separator expected in numeric fields.
+    ///
+    /// Some locale-aware middleware rewrites NMEA numbers with a comma decimal separator.
+    /// A comma cannot be told apart from the NMEA field delimiter, so with ',' configured the
+    /// fields must be delimited by ';', as such middleware does. Sentences are mapped back to
+    /// standard NMEA before parsing, so every handler, including registered ones, sees periods.
+    /// The checksum is still verified over the sentence as received.
+    ///
+    /// # Arguments
+    /// * `separator` - '.' (default) or ','
+    ///
+    /// # Returns
+    /// * `bool` - false if the separator is not supported and the setting was left unchanged
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert!(gnss.set_decimal_separator(','));
+    /// gnss.feed_nmea("$GNGGA;123519,00;4807,038;N;01131,000;E;1;08;0,9;545,4;M;46,9;M;;").unwrap();
+    /// assert_eq!(gnss.altitude(), Some(545.4));
+    /// assert!(!gnss.set_decimal_separator(' '));
+    /// ```
+    pub fn set_decimal_separator(&mut self, separator: char) -> bool {
+        if separator != '.' && separator != ',' {
+            return false;
+        }
+        self.decimal_separator = separator;
+        true
+    }
+
+    /// Gets whether sentences without a valid checksum are dropped.
+    ///
+    /// # Returns
+    /// * `bool` - True if checksum validation is enabled (false by default)
+    pub fn get_checksum_validation(&self) -> bool {
+        self.checksum_validation
+    }
+
+    /// Sets whether sentences without a valid checksum are dropped.
+    ///
+    /// With validation enabled, a sentence whose `*hh` checksum is missing or does not match
+    /// its payload is ignored and counted in `ParserStats::rejected_sentences`. Otherwise such
+    /// sentences are parsed and only counted as checksum failures.
+    ///
+    /// # Arguments
+    /// * `enabled` - True to drop sentences failing validation
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_checksum_validation(true);
+    /// // Truncated on a noisy line
+    /// assert!(gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,54").is_err());
+    /// assert_eq!(gnss.latitude(), None);
+    /// assert_eq!(gnss.get_stats().rejected_sentences(), 1);
+    /// ```
+    pub fn set_checksum_validation(&mut self, enabled: bool) {
+        self.checksum_validation = enabled;
+    }
+
+    /// Gets whether sentences with bytes after the checksum are dropped.
+    ///
+    /// # Returns
+    /// * `bool` - True if strict framing is enabled (false by default)
+    pub fn get_strict_framing(&self) -> bool {
+        self.strict_framing
+    }
+
+    /// Sets whether sentences with bytes after the checksum are dropped.
+    ///
+    /// Only line terminators may follow the two `*hh` checksum digits. Noisy lines sometimes
+    /// append other bytes; by default they are stripped and the sentence is parsed. With strict
+    /// framing the sentence is ignored and counted in `ParserStats::rejected_sentences`. Either
+    /// way it is counted in `ParserStats::trailing_garbage_sentences`.
+    ///
+    /// # Arguments
+    /// * `enabled` - True to drop sentences with trailing garbage
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, NmeaError};
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_strict_framing(true);
+    /// let parsed = gnss.feed_nmea("$GPGLL,4916.45,N,12311.12,W,225444,A*31@#");
+    /// assert_eq!(parsed, Err(NmeaError::TrailingGarbage { garbage: "@#".to_string() }));
+    /// assert_eq!(gnss.latitude(), None);
+    /// assert_eq!(gnss.get_stats().trailing_garbage_sentences(), 1);
+    /// ```
+    pub fn set_strict_framing(&mut self, enabled: bool) {
+        self.strict_framing = enabled;
+    }
+
+    /// Gets the number of warm-up epochs suppressed before a fused position is reported.
+    ///
+    /// # Returns
+    /// * `u32` - The configured warm-up epoch count (0 disables the warm-up)
+    pub fn get_warmup_epochs(&self) -> u32 {
+        self.warmup_epochs
+    }
+
+    /// Sets the number of consecutive good epochs to suppress before reporting a fused position.
+    ///
+    /// An epoch is one call to a fusion method. While warming up, `fused_position` stays `None`;
+    /// any epoch that fails to produce a position restarts the warm-up.
+    ///
+    /// # Arguments
+    /// * `epochs` - Number of consecutive good epochs to suppress (0 disables the warm-up)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_warmup_epochs(3);
+    /// assert_eq!(gnss.get_warmup_epochs(), 3);
+    /// ```
+    pub fn set_warmup_epochs(&mut self, epochs: u32) {
+        self.warmup_epochs = epochs;
+        self.consecutive_good_epochs = 0;
+    }
+
+    /// Checks whether the current position is a valid fix.
+    ///
+    /// The position is valid when latitude and longitude are set, at least one of the RMC
+    /// status and the GGA fix quality reports a fix, and neither reports an invalid one
+    /// (status 'V' or fix quality 0). Sentences without a fix do not update the position, so
+    /// after a fix is lost, the last valid position is kept but no longer reported as valid.
+    /// An estimated position (see `is_dead_reckoned`) or an RMC navigational status of 'V' is
+    /// not a valid fix either.
+    ///
+    /// # Returns
+    /// * `bool` - True if the position is backed by a valid fix
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPRMC,123519,A,4807.038,N,01131.000,E,,,230394,003.1,W*66").unwrap();
+    /// assert!(gnss.position_valid());
+    /// gnss.feed_nmea("$GPRMC,123520,V,4807.038,N,01131.000,E,,,230394,003.1,W*7B").unwrap();
+    /// assert_eq!(gnss.rmc_status(), Some('V'));
+    /// assert!(gnss.latitude().is_some() && !gnss.position_valid());
+    /// ```
+    pub fn position_valid(&self) -> bool {
+        self.reports_fix() && !self.is_dead_reckoned() && self.nav_status != Some('V')
+    }
+
+    /// Checks whether the RMC status and GGA fix quality report a fix for the current position,
+    /// estimated or not.
+    fn reports_fix(&self) -> bool {
+        if self.latitude.is_none() || self.longitude.is_none() {
+            return false;
+        }
+        let status_valid = self.rmc_status.map(|status| status == 'A');
+        let quality_valid = self.fix_quality.map(|quality| quality > 0);
+        status_valid != Some(false) && quality_valid != Some(false) && (status_valid.is_some() || quality_valid.is_some())
+    }
+
+    /// Checks whether the receiver reports a fix that fusion can use.
+    ///
+    /// True when the RMC status and GGA fix quality report a fix and at least one system has a
+    /// position it may contribute; estimated positions count as the dead-reckoning policy allows.
+    /// A GGA with fix quality 0, or an RMC or GLL with status 'V', keeps the systems it applies
+    /// to out of fusion until a sentence reports a fix again.
+    ///
+    /// # Returns
+    /// * `bool` - True if a fused position can be computed from a valid fix
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// assert!(gnss.has_fix());
+    /// gnss.feed_nmea("$GNGGA,123520,4807.038,N,01131.000,E,0,00,,,M,,M,,*46").unwrap();
+    /// assert!(!gnss.has_fix());
+    /// ```
+    pub fn has_fix(&self) -> bool {
+        self.reports_fix() && self.systems.values().any(|sys| {
+            sys.latitude.is_some() && sys.longitude.is_some() && self.is_fusion_eligible(sys)
+        })
+    }
+
+    /// Checks whether the last GGA reported a dead-reckoned position (fix quality 6), or the
+    /// last RMC an estimated one (mode indicator 'E').
+    ///
+    /// A dead-reckoned position is estimated from motion sensors without a GNSS fix; it can be
+    /// displayed with a flag but should not be treated as a real fix.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,6,08,0.9,545.4,M,46.9,M,,*5E").unwrap();
+    /// assert!(gnss.is_dead_reckoned());
+    /// ```
+    pub fn is_dead_reckoned(&self) -> bool {
+        self.fix_quality_kind() == FixQuality::DeadReckoning || self.rmc_mode == Some('E')
+    }
+
+    /// Returns the GGA fix quality as a `FixQuality`.
+    ///
+    /// # Returns
+    /// * `FixQuality` - The fix quality, or `Invalid` before any GGA or for unknown values
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{FixQuality, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// assert_eq!(gnss.fix_quality_kind(), FixQuality::Invalid);
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,9,08,0.9,545.4,M,46.9,M,,*51").unwrap();
+    /// assert_eq!(gnss.fix_quality_kind(), FixQuality::Sbas);
+    /// ```
+    pub fn fix_quality_kind(&self) -> FixQuality {
+        self.fix_quality.map(FixQuality::from).unwrap_or(FixQuality::Invalid)
+    }
+
+    /// Returns the height above the ellipsoid of the last GGA or GNS position.
+    ///
+    /// This is `altitude + geoid_separation`, with the separation taken from a geoid model
+    /// (with the `geoid` feature) when the receiver reports none.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The ellipsoidal height in meters, or None if the altitude or the
+    ///   geoid separation is unknown
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// assert!((gnss.altitude_ellipsoid().unwrap() - 592.3).abs() < 1e-9);
+    /// ```
+    pub fn altitude_ellipsoid(&self) -> Option<f64> {
+        let separation = self.geoid_separation_at(self.latitude?, self.longitude?)?;
+        Some(self.altitude? + separation)
+    }
+
+    /// Collapses the current state into a single status code.
+    ///
+    /// Precedence rules, first match wins:
+    /// 1. `NoData` until any sentence has been handled.
+    /// 2. The fix quality is taken from the fused position when one exists, otherwise from
+    ///    the last GGA. RTK fixed or float gives `Rtk`; DGPS or SBAS gives `Dgps`.
+    /// 3. An autonomous (GPS or PPS) fix gives `Fix3d` when at least 4 satellites are used
+    ///    or a fused position exists, and `Fix2d` otherwise.
+    /// 4. Anything else (invalid, dead reckoning, manual, simulation) gives `Searching`.
+    ///
+    /// # Returns
+    /// * `GnssStatus` - The status code
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, GnssStatus};
+    /// let mut gnss = GnssData::new();
+    /// assert_eq!(gnss.status_code(), GnssStatus::NoData);
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// assert_eq!(gnss.status_code(), GnssStatus::Fix3d);
+    /// ```
+    pub fn status_code(&self) -> GnssStatus {
+        if self.stats.sentence_counts.values().all(|&count| count == 0) {
+            return GnssStatus::NoData;
+        }
+        let quality = match &self.fused_position {
+            Some(fused) => fused.fix_quality.or(self.fix_quality.map(FixQuality::from)),
+            None => self.fix_quality.map(FixQuality::from),
+        };
+        match quality {
+            Some(FixQuality::RtkFixed | FixQuality::RtkFloat) => GnssStatus::Rtk,
+            Some(FixQuality::Dgps | FixQuality::Sbas) => GnssStatus::Dgps,
+            Some(FixQuality::Gps | FixQuality::Pps) => {
+                if self.num_satellites.unwrap_or(0) >= 4 || self.fused_position.is_some() {
+                    GnssStatus::Fix3d
+                } else {
+                    GnssStatus::Fix2d
+                }
+            }
+            _ => GnssStatus::Searching,
+        }
+    }
+
+    /// Gets whether dead-reckoned positions are excluded from fusion.
+    ///
+    /// # Returns
+    /// * `bool` - True if the policy is `DeadReckoningPolicy::Exclude` (not by default)
+    pub fn get_exclude_dead_reckoning(&self) -> bool {
+        self.excludes_dead_reckoning()
+    }
+
+    /// Sets whether dead-reckoned positions are excluded from fusion.
+    ///
+    /// Shorthand for `set_dead_reckoning_policy` with `Exclude` or `Include`.
+    ///
+    /// # Arguments
+    /// * `exclude` - True to leave dead-reckoned positions out of the fused position
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_exclude_dead_reckoning(true);
+    /// assert!(gnss.get_exclude_dead_reckoning());
+    /// ```
+    pub fn set_exclude_dead_reckoning(&mut self, exclude: bool) {
+        self.dead_reckoning_policy = if exclude { DeadReckoningPolicy::Exclude } else { DeadReckoningPolicy::Include };
+    }
+
+    /// Gets how fusion treats dead-reckoned positions.
+    ///
+    /// # Returns
+    /// * `DeadReckoningPolicy` - The policy (`Include` by default)
+    pub fn get_dead_reckoning_policy(&self) -> DeadReckoningPolicy {
+        self.dead_reckoning_policy
+    }
+
+    /// Sets how fusion treats dead-reckoned positions.
+    ///
+    /// A system position is dead-reckoned when it came from a GGA with fix quality 6, or from
+    /// an RMC, GNS or GLL sentence whose mode indicator is 'E'.
+    ///
+    /// # Arguments
+    /// * `policy` - The policy; an `Inflate` factor below 1 is raised to 1
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{DeadReckoningPolicy, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_dead_reckoning_policy(DeadReckoningPolicy::Inflate(5.0));
+    /// assert_eq!(gnss.get_dead_reckoning_policy(), DeadReckoningPolicy::Inflate(5.0));
+    /// ```
+    pub fn set_dead_reckoning_policy(&mut self, policy: DeadReckoningPolicy) {
+        self.dead_reckoning_policy = match policy {
+            DeadReckoningPolicy::Inflate(factor) => DeadReckoningPolicy::Inflate(if factor >= 1.0 { factor } else { 1.0 }),
+            policy => policy,
+        };
+    }
+
+    /// Checks whether the dead-reckoning policy leaves dead-reckoned positions out of fusion.
+    fn excludes_dead_reckoning(&self) -> bool {
+        self.dead_reckoning_policy == DeadReckoningPolicy::Exclude
+    }
+
+    /// Gets how fusion treats a system with a satellite flagged as failed by GBS.
+    ///
+    /// # Returns
+    /// * `FaultPolicy` - The policy (`Include` by default)
+    pub fn get_fault_policy(&self) -> FaultPolicy {
+        self.fault_policy
+    }
+
+    /// Sets how fusion treats a system with a satellite flagged as failed by GBS.
+    ///
+    /// The flag follows the most likely failed satellite of the last GBS sentence.
+    ///
+    /// # Arguments
+    /// * `policy` - The policy; an `Inflate` factor below 1 is raised to 1
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{FaultPolicy, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_fault_policy(FaultPolicy::Exclude);
+    /// assert_eq!(gnss.get_fault_policy(), FaultPolicy::Exclude);
+    /// ```
+    pub fn set_fault_policy(&mut self, policy: FaultPolicy) {
+        self.fault_policy = match policy {
+            FaultPolicy::Inflate(factor) => FaultPolicy::Inflate(if factor >= 1.0 { factor } else { 1.0 }),
+            policy => policy,
+        };
+    }
+
+    /// Gets how fusion treats a system reported in a datum other than WGS84.
+    ///
+    /// # Returns
+    /// * `DatumPolicy` - The policy (`Convert` by default)
+    pub fn get_datum_policy(&self) -> DatumPolicy {
+        self.datum_policy
+    }
+
+    /// Sets how fusion treats a system reported in a datum other than WGS84.
+    ///
+    /// A DTM sentence from a constellation talker sets the datum of that system; one from the
+    /// "GN" talker sets it for every system.
+    ///
+    /// # Arguments
+    /// * `policy` - The policy
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{DatumPolicy, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.set_datum_policy(DatumPolicy::Exclude);
+    /// assert_eq!(gnss.get_datum_policy(), DatumPolicy::Exclude);
+    /// ```
+    pub fn set_datum_policy(&mut self, policy: DatumPolicy) {
+        self.datum_policy = policy;
+    }
+
+    /// Checks whether a system may contribute to the fused position under the dead-reckoning,
+    /// fault and datum policies.
+    fn is_fusion_eligible(&self, system_data: &GnssSystemData) -> bool {
+        system_data.is_fusion_eligible(self.excludes_dead_reckoning())
+            && !(self.fault_policy == FaultPolicy::Exclude && system_data.integrity_fault)
+            && !(self.datum_policy == DatumPolicy::Exclude && system_data.datum.as_ref().is_some_and(|datum| !datum.is_wgs84()))
+    }
+
+    /// Computes a weighted average of any per-system scalar using the fusion weighting.
+    ///
+    /// Each system is weighted by the inverse of its combined horizontal accuracy, with the
+    /// HDOP, accuracy and satellite count requirements `calculate_fused_position` applies to
+    /// positions. Systems for which `f` returns None are skipped.
+    ///
+    /// # Arguments
+    /// * `f` - Extracts the scalar to average from a system
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The weighted average, or None if no system contributed
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,09,10,11,12,1.2,0.9,2.1*26").unwrap();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// assert_eq!(gnss.weighted_system_average(|sys| sys.pdop()), Some(1.2));
+    /// ```
+    pub fn weighted_system_average(&self, f: impl Fn(&GnssSystemData) -> Option<f64>) -> Option<f64> {
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        for (_, system_data) in self.systems_in_order() {
+            if let (Some(value), Some(weight)) = (f(system_data), self.fusion_weight(system_data)) {
+                weighted_sum += value * weight;
+                total_weight += weight;
+            }
+        }
+        if total_weight > 0.0 {
+            Some(weighted_sum / total_weight)
+        } else {
+            None
+        }
+    }
+
+    /// Captures the current solution as a snapshot for change detection.
+    ///
+    /// Position and fix quality come from the fused position when one is available, and from
+    /// the last GGA otherwise. DOP values are the fusion-weighted average over the systems.
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// let snapshot = gnss.snapshot();
+    /// assert_eq!(snapshot.num_satellites, Some(8));
+    /// assert_eq!(snapshot.altitude, Some(545.4));
+    /// ```
+    pub fn snapshot(&self) -> GnssSnapshot {
+        let (latitude, longitude, altitude, fix_quality) = match &self.fused_position {
+            Some(fused) => (
+                Some(fused.latitude),
+                Some(fused.longitude),
+                // NaN when no contributing system reported an altitude
+                Some(fused.altitude_msl).filter(|altitude| altitude.is_finite()),
+                fused.fix_quality.or(self.fix_quality.map(FixQuality::from)),
+            ),
+            None => (self.latitude, self.longitude, self.altitude, self.fix_quality.map(FixQuality::from)),
+        };
+        GnssSnapshot {
+            time: self.time.clone(),
+            latitude,
+            longitude,
+            altitude,
+            num_satellites: self.num_satellites,
+            pdop: self.weighted_system_average(|sys| sys.pdop).or(self.combined_pdop),
+            hdop: self.weighted_system_average(|sys| sys.hdop).or(self.combined_hdop).or(self.gga_hdop),
+            vdop: self.weighted_system_average(|sys| sys.vdop).or(self.combined_vdop),
+            fix_quality,
+        }
+    }
+
+    /// Calls `visit` with the data of each system, without copying or allocating.
+    ///
+    /// Systems are visited in canonical order (GPS, GLONASS, GALILEO, BEIDOU, QZSS, NAVIC).
+    /// Renderers that redraw often can build their display structures from the borrowed data
+    /// instead of taking an owned `snapshot` each frame.
+    ///
+    /// # Arguments
+    /// * `visit` - Called with each system and its data
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// let mut in_view = 0;
+    /// gnss.visit_systems(|_, data| in_view += data.satellites().len());
+    /// assert_eq!(in_view, 4);
+    /// ```
+    pub fn visit_systems<F>(&self, mut visit: F)
+    where
+        F: FnMut(GnssSystem, &GnssSystemData),
+    {
+        for system in GnssSystem::ALL {
+            if let Some(system_data) = self.systems.get(system.name()) {
+                visit(system, system_data);
+            }
+        }
+    }
+
+    /// Calls `visit` with every satellite in view, without copying or allocating.
+    ///
+    /// Satellites are visited system by system in canonical order (GPS, GLONASS, GALILEO,
+    /// BEIDOU, QZSS, NAVIC), and in ascending PRN order within a system.
+    ///
+    /// # Arguments
+    /// * `visit` - Called with each satellite and the system it belongs to
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, GnssSystem};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// let mut strongest = None;
+    /// gnss.visit_satellites(|system, sat| {
+    ///     if sat.snr > strongest.and_then(|(_, _, snr)| snr) {
+    ///         strongest = Some((system, sat.prn, sat.snr));
+    ///     }
+    /// });
+    /// assert_eq!(strongest, Some((GnssSystem::Gps, 2, Some(43))));
+    /// ```
+    pub fn visit_satellites<F>(&self, mut visit: F)
+    where
+        F: FnMut(GnssSystem, &SatelliteInfo),
+    {
+        self.visit_systems(|system, system_data| {
+            for sat in system_data.satellites_info.values() {
+                visit(system, sat);
+            }
+        });
+    }
+
+    /// Exports the fused position as a KML `<Placemark>` for visualization in Google Earth.
+    ///
+    /// KML coordinates are ordered longitude, latitude, altitude. The altitude is above mean
+    /// sea level, so the placemark uses the `absolute` altitude mode; an unknown altitude is
+    /// omitted and the point clamped to the ground instead. Accuracy and contributing
+    /// systems are written to the description. In privacy mode (see `set_privacy_accuracy`),
+    /// the degraded position and accuracy are written instead.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The placemark, or None if there is no fused position
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// assert!(gnss.to_kml().is_none());
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*21").unwrap();
+    /// gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59").unwrap();
+    /// gnss.calculate_fused_position();
+    /// assert!(gnss.to_kml().unwrap().contains("<coordinates>11.5166667,48.1173000,545.40</coordinates>"));
+    /// ```
+    pub fn to_kml(&self) -> Option<String> {
+        let fused = self.fused_position.as_ref()?;
+        let degraded;
+        let fused = match self.privacy_accuracy {
+            Some(accuracy) => {
+                degraded = fused.degraded(accuracy);
+                &degraded
+            }
+            None => fused,
+        };
+        let (altitude_mode, coordinates) = if fused.altitude_msl.is_finite() {
+            ("absolute", format!("{:.7},{:.7},{:.2}", fused.longitude, fused.latitude, fused.altitude_msl))
+        } else {
+            ("clampToGround", format!("{:.7},{:.7}", fused.longitude, fused.latitude))
+        };
+        let lines = [
+            "<Placemark>".to_string(),
+            "  <name>Fused position</name>".to_string(),
+            format!(
+                "  <description>Horizontal accuracy: {:.2} m, altitude accuracy: {:.2} m, systems: {}</description>",
+                fused.estimated_accuracy,
+                fused.altitude_accuracy,
+                fused.contributing_systems.join(", ")
+            ),
+            "  <Point>".to_string(),
+            format!("    <altitudeMode>{}</altitudeMode>", altitude_mode),
+            format!("    <coordinates>{}</coordinates>", coordinates),
+            "  </Point>".to_string(),
+            "</Placemark>".to_string(),
+        ];
+        Some(lines.join("\n"))
+    }
+
+    /// Returns a single merged view of a satellite across all signal bands it was reported on.
+    ///
+    /// The merged entry carries the maximum SNR over the bands and the shared elevation and
+    /// azimuth. Receivers that report a single band yield that band's entry unchanged.
+    ///
+    /// # Arguments
+    /// * `system` - The GNSS system name ("GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC")
+    /// * `prn` - Satellite PRN
+    ///
+    /// # Returns
+    /// * `Option<SatelliteInfo>` - The merged satellite, or None if it is not tracked
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::GnssData;
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// assert_eq!(gnss.merged_satellite("GPS", 2).unwrap().snr, Some(43));
+    /// assert!(gnss.merged_satellite("GPS", 9).is_none());
+    /// ```
+    pub fn merged_satellite(&self, system: &str, prn: u16) -> Option<SatelliteInfo> {
+        let mut merged = self.systems.get(system)?.satellites_info.get(&prn)?.clone();
+        merged.snr = merged.best_snr();
+        Some(merged)
+    }
+
+    /// Returns the best available course over ground.
+    ///
+    /// The RMC track angle is preferred. When no sentence provides a course, the course derived
+    /// from successive fused fixes is returned with [`CourseSource::Derived`]; it is only
+    /// available while the receiver moves clearly more than its position noise.
+    ///
+    /// # Returns
+    /// * `Option<Course>` - The course and its source, or None if unknown
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{CourseSource, GnssData};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74").unwrap();
+    /// let course = gnss.course().unwrap();
+    /// assert_eq!(course.source, CourseSource::Rmc);
+    /// assert_eq!(course.degrees, 84.4);
+    /// ```
+    pub fn course(&self) -> Option<Course> {
+        if let Some(degrees) = self.track_angle {
+            return Some(Course { degrees, source: CourseSource::Rmc });
+        }
+        self.derived_course.map(|degrees| Course { degrees, source: CourseSource::Derived })
+    }
+
+    /// Configures the course derived from successive fused fixes.
+    ///
+    /// # Arguments
+    /// * `gate_factor` - Minimum movement between fixes, as a multiple of the fix accuracy
+    /// * `window` - Number of derived bearings averaged (at least 1)
+    pub fn set_derived_course_config(&mut self, gate_factor: f64, window: usize) {
+        self.course_gate_factor = gate_factor;
+        self.course_window = window.max(1);
+        self.course_history.clear();
+    }
+
+    /// Reports how the satellite geometry would change under a different elevation mask or
+    /// with some constellations excluded.
+    ///
+    /// Only satellites with both elevation and azimuth known are considered. DOP values are
+    /// computed from the line-of-sight vectors of the remaining satellites, so no almanac is needed.
+    ///
+    /// # Arguments
+    /// * `mask_deg` - Elevation mask in degrees; satellites below it are dropped
+    /// * `excluded` - Constellations to leave out of the solution
+    ///
+    /// # Returns
+    /// * `GeometrySummary` - Remaining satellite count and computed DOPs (`None` with fewer than 4 satellites)
+    ///
+    /// # Example
+    /// ```
+    /// use nema_parser::gnss_multignss_parser::{GnssData, GnssSystem};
+    /// let mut gnss = GnssData::new();
+    /// gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78").unwrap();
+    /// let summary = gnss.geometry_what_if(10.0, &[GnssSystem::Glonass]);
+    /// assert_eq!(summary.remaining_sats, 3);
+    /// assert!(summary.computed_hdop.is_none());
+    /// ```
+    pub fn geometry_what_if(&self, mask_deg: f64, excluded: &[GnssSystem]) -> GeometrySummary {
+        let mut line_of_sight = Vec::new();
+        for system in GnssSystem::ALL.iter().filter(|system| !excluded.contains(system)) {
+            if let Some(sys_data) = self.systems.get(system.name()) {
+                for sat in sys_data.satellites_info.values() {
+                    if let (Some(elevation), Some(azimuth)) = (sat.elevation, sat.azimuth) {
+                        if f64::from(elevation) >= mask_deg {
+                            line_of_sight.push((f64::from(elevation), f64::from(azimuth)));
+                        }
+                    }
+                }
+            }
+        }
+
+        let dops = geometry_dop(&line_of_sight);
+        GeometrySummary {
+            remaining_sats: line_of_sight.len(),
+            computed_hdop: dops.map(|(hdop, _)| hdop),
+            computed_vdop: dops.map(|(_, vdop)| vdop),
+        }
+    }
+}
+
+/// Parses latitude from NMEA format to decimal degrees.
+///
+/// # Arguments
+/// * `value` - Latitude value as string (DDMM.MMMM)
+/// * `hemi` - Hemisphere ("N" or "S", in either case)
+///
+/// # Returns
+/// * `Option<f64>` - Latitude in decimal degrees, or None if the hemisphere is missing or invalid
+fn parse_lat(value: Option<&&str>, hemi: Option<&&str>) -> Option<f64> {
+    let sign = hemisphere_sign(hemi?, 'N', 'S')?;
+    let val = value?.parse::<f64>().ok()?;
+    let deg = (val / 100.0).floor();
+    let min = val % 100.0;
+    Some(sign * (deg + min / 60.0))
+}
+
+/// Parses longitude from NMEA format to decimal degrees.
+///
+/// # Arguments
+/// * `value` - Longitude value as string (DDDMM.MMMM)
+/// * `hemi` - Hemisphere ("E" or "W", in either case)
+///
+/// # Returns
+/// * `Option<f64>` - Longitude in decimal degrees, or None if the hemisphere is missing or invalid
+fn parse_lon(value: Option<&&str>, hemi: Option<&&str>) -> Option<f64> {
+    let sign = hemisphere_sign(hemi?, 'E', 'W')?;
+    let val = value?.parse::<f64>().ok()?;
+    let deg = (val / 100.0).floor();
+    let min = val % 100.0;
+    Some(sign * (deg + min / 60.0))
+}
+
+/// Maps a hemisphere field to the sign of its coordinate, ignoring case.
+///
+/// A missing or unknown hemisphere gives None: guessing would silently mirror the position.
+///
+/// # Arguments
+/// * `hemi` - Hemisphere field
+/// * `positive` - Letter of the positive hemisphere ('N' or 'E')
+/// * `negative` - Letter of the negative hemisphere ('S' or 'W')
+fn hemisphere_sign(hemi: &str, positive: char, negative: char) -> Option<f64> {
+    let mut letters = hemi.chars().map(|letter| letter.to_ascii_uppercase());
+    match (letters.next(), letters.next()) {
+        (Some(letter), None) if letter == positive => Some(1.0),
+        (Some(letter), None) if letter == negative => Some(-1.0),
+        _ => None,
+    }
+}
+
+/// Values of a VTG sentence.
+struct VtgFields<'a> {
+    /// True course over ground in degrees
+    true_course: Option<f64>,
+    /// Magnetic course over ground in degrees
+    magnetic_course: Option<f64>,
+    /// Speed over ground in knots
+    knots: Option<f64>,
+    /// Speed over ground in km/h
+    kmh: Option<f64>,
+    /// FAA mode indicator (NMEA 2.3 and later)
+    mode: Option<&'a str>,
+}
+
+impl<'a> VtgFields<'a> {
+    /// Locates the VTG values by the unit letter following each one (T, M, N, K), or by
+    /// position in the pre-NMEA 2.0 format without unit letters.
+    ///
+    /// The mode indicator is the field after the last unit letter.
+    fn locate(parts: &[&'a str]) -> Self {
+        const UNITS: [&str; 4] = ["T", "M", "N", "K"];
+        let number = |index: usize| parts.get(index).and_then(|s| s.parse::<f64>().ok());
+        let labeled = (2..parts.len()).step_by(2).any(|index| UNITS.contains(&parts[index]));
+        if !labeled {
+            return VtgFields { true_course: number(1), magnetic_course: number(2), knots: number(3), kmh: number(4), mode: None };
+        }
+
+        let mut values = [None; 4];
+        let mut seen = [false; 4];
+        let mut mode_index = None;
+        let mut index = 1;
+        while index + 1 < parts.len() {
+            match UNITS.iter().position(|unit| *unit == parts[index + 1]) {
+                Some(unit) if !seen[unit] => {
+                    seen[unit] = true;
+                    values[unit] = number(index);
+                    mode_index = Some(index + 2);
+                    index += 2;
+                }
+                _ => index += 1,
+            }
+        }
+        let [true_course, magnetic_course, knots, kmh] = values;
+        let mode = mode_index.and_then(|index| parts.get(index).copied()).filter(|mode| !mode.is_empty());
+        VtgFields { true_course, magnetic_course, knots, kmh, mode }
+    }
+}
+
+/// Returns the rank of a sentence type in a precedence list; unlisted types rank last.
+fn precedence_rank(precedence: &[String], sentence_type: &str) -> usize {
+    precedence.iter().position(|s| s == sentence_type).unwrap_or(precedence.len())
+}
+
+/// Claims a quantity for a sentence of `rank` (lower is higher precedence) within an epoch.
+///
+/// # Returns
+/// * `bool` - True if no sentence of higher precedence already set the quantity
+fn claim_precedence(claim: &mut Option<usize>, rank: usize) -> bool {
+    if claim.is_some_and(|current| rank > current) {
+        return false;
+    }
+    *claim = Some(rank);
+    true
+}
+
+/// Converts an NMEA date (DDMMYY) and UTC time (hhmmss.ss) to seconds since the Unix epoch.
+///
+/// Two-digit years 80-99 map to 1980-1999 and 00-79 to 2000-2079.
+///
+/// # Returns
+/// * `Option<f64>` - Seconds since 1970-01-01T00:00:00Z, or None if either field is malformed
+fn nmea_datetime_to_unix(date: &str, time: &str) -> Option<f64> {
+    let field = |s: &str, range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let day = field(date, 0..2)?;
+    let month = field(date, 2..4)?;
+    let yy = field(date, 4..6)?;
+    let hours = field(time, 0..2)?;
+    let minutes = field(time, 2..4)?;
+    let seconds: f64 = time.get(4..)?.parse().ok()?;
+    if !(1..=31).contains(&day) || !(1..=12).contains(&month) || hours > 23 || minutes > 59 {
+        return None;
+    }
+    let year = if yy >= 80 { 1900 + yy } else { 2000 + yy };
+    let days = days_from_civil(year, month, day);
+
+    Some((days * 86_400 + hours * 3_600 + minutes * 60) as f64 + seconds)
+}
+
+/// Counts the days from 1970-01-01 to a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Shifted so March starts the year, putting the leap day last
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since 1970-01-01 back to a year, month and day; the inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let m = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * m + 2) / 5 + 1) as u8;
+    let month = if m < 10 { m + 3 } else { m - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Parses an NMEA UTC time (hhmmss with optional fractional seconds) to the millisecond.
+///
+/// Fractional digits past milliseconds are truncated.
+///
+/// # Returns
+/// * `Option<UtcTime>` - The time, or None if the field is malformed or out of range
+fn parse_utc_time(time: &str) -> Option<UtcTime> {
+    let field = |range: std::ops::Range<usize>| time.get(range)?.parse::<u8>().ok();
+    let (hour, minute, second) = (field(0..2)?, field(2..4)?, field(4..6)?);
+    let fraction = match time.get(6..)? {
+        "" => "",
+        rest => rest.strip_prefix('.')?,
+    };
+    if hour > 23 || minute > 59 || second > 60 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let millis = fraction.bytes().chain(std::iter::repeat(b'0')).take(3)
+        .fold(0u16, |acc, digit| acc * 10 + u16::from(digit - b'0'));
+    Some(UtcTime { hour, minute, second, millis })
+}
+
+/// Checks a sentence for the tell-tale of a comma decimal separator.
+///
+/// A comma splits the latitude of a GGA, GNS, RMC or GLL into its integer part and a field of
+/// digits where the hemisphere belongs, pushing the 'N' or 'S' one field to the right.
+///
+/// # Arguments
+/// * `parts` - Sentence fields starting with the address
+fn has_comma_decimals(parts: &[&str]) -> bool {
+    let latitude = match parts.first().and_then(|address| address.get(2..5)) {
+        Some("GGA" | "GNS") => 2,
+        Some("RMC") => 3,
+        Some("GLL") => 1,
+        _ => return false,
+    };
+    let digits = |field: &&str| !field.is_empty() && field.bytes().all(|byte| byte.is_ascii_digit());
+    parts.get(latitude).is_some_and(digits)
+        && parts.get(latitude + 1).is_some_and(digits)
+        && parts.get(latitude + 2).is_some_and(|hemisphere| matches!(*hemisphere, "N" | "S" | "n" | "s"))
+}
+
+/// Splits an optional NMEA 4.0 TAG block (`\s:source,c:123*hh\`) from the front of a sentence.
+///
+/// # Returns
+/// * `(Option<&str>, &str)` - The TAG block `s:` source ID if present, and the remaining sentence
+fn split_tag_block(sentence: &str) -> (Option<&str>, &str) {
+    let Some(tagged) = sentence.strip_prefix('\\') else {
+        return (None, sentence);
+    };
+    let Some((tag, rest)) = tagged.split_once('\\') else {
+        return (None, sentence);
+    };
+    let params = tag.split('*').next().unwrap_or(tag);
+    let source = params
+        .split(',')
+        .find_map(|param| param.strip_prefix("s:"))
+        .filter(|source| !source.is_empty());
+    (source, rest)
+}
+
+/// Validates the `*hh` checksum of an NMEA sentence.
+///
+/// The checksum is the XOR of every byte between the `$` (or `!`) and the `*`. A leading
+/// TAG block is skipped.
+///
+/// # Arguments
+/// * `sentence` - The sentence, with or without trailing line terminators
+///
+/// # Returns
+/// * `bool` - True if the sentence carries a checksum that matches its payload
+///
+/// # Example
+/// ```
+/// use nema_parser::gnss_multignss_parser::validate_checksum;
+/// assert!(validate_checksum("$GPGLL,4916.45,N,12311.12,W,225444,A*31"));
+/// assert!(!validate_checksum("$GPGLL,4916.45,N,12311.12,W,225444,A*32"));
+/// assert!(!validate_checksum("$GPGLL,4916.45,N,12311.12,W,22544"));
+/// ```
+pub fn validate_checksum(sentence: &str) -> bool {
+    let (_, sentence) = split_tag_block(sentence);
+    let frame = split_frame(sentence.trim_start_matches(['$', '!']));
+    frame.trailing.is_empty() && frame.checksum_matches() == Some(true)
+}
+
+/// A sentence split at its `*` checksum delimiter.
+struct Frame<'a> {
+    /// Text between the leading `$` and the `*`, or up to the line terminator without a checksum
+    payload: &'a str,
+    /// Checksum text: the two characters after the `*`, or fewer if the sentence ends sooner
+    checksum: Option<&'a str>,
+    /// Anything but line terminators following the two checksum characters
+    trailing: &'a str,
+}
+
+impl Frame<'_> {
+    /// Checks the checksum against the payload.
+    ///
+    /// # Returns
+    /// * `Option<bool>` - None if the sentence carries no checksum, otherwise whether it matches
+    fn checksum_matches(&self) -> Option<bool> {
+        let checksum = self.checksum?;
+        Some(checksum.len() == 2 && u8::from_str_radix(checksum, 16) == Ok(nmea_checksum(self.payload)))
+    }
+}
+
+/// Splits a sentence with the leading `$` already removed into payload, checksum and trailing bytes.
+fn split_frame(sentence: &str) -> Frame<'_> {
+    let Some((payload, rest)) = sentence.split_once('*') else {
+        return Frame { payload: sentence.trim_end_matches(['\r', '\n']), checksum: None, trailing: "" };
+    };
+    let rest = rest.trim_end_matches(['\r', '\n']);
+    let end = rest.char_indices().nth(2).map_or(rest.len(), |(index, _)| index);
+    let (checksum, trailing) = rest.split_at(end);
+    Frame { payload, checksum: Some(checksum), trailing: trailing.trim_matches(['\r', '\n']) }
+}
+
+/// Computes the NMEA checksum (XOR of all bytes) of a sentence payload between `$` and `*`.
+pub(crate) fn nmea_checksum(payload: &str) -> u8 {
+    payload.bytes().fold(0u8, |acc, byte| acc ^ byte)
+}
+
+/// Combines a DOP with a system accuracy into the accuracy fusion assigns, never better than
+/// the accuracy itself.
+fn combined_accuracy(dop: f64, accuracy: f64) -> f64 {
+    (dop * accuracy).max(accuracy)
+}
+
+/// Returns the fusion weight of an estimate with the given combined accuracy, its inverse
+/// offset by 0.1 m so a zero accuracy cannot divide by zero.
+fn accuracy_weight(combined_accuracy: f64) -> f64 {
+    1.0 / (combined_accuracy + 0.1)
+}
+
+/// Checks whether an optional float changed by more than `tolerance`, or appeared or disappeared.
+///
+/// Non-finite values count as missing.
+fn float_changed(old: Option<f64>, new: Option<f64>, tolerance: f64) -> bool {
+    let (old, new) = (old.filter(|value| value.is_finite()), new.filter(|value| value.is_finite()));
+    match (old, new) {
+        (Some(old), Some(new)) => (new - old).abs() > tolerance,
+        (old, new) => old.is_some() != new.is_some(),
+    }
+}
+
+/// Combines independent 1-sigma errors of averaged estimates into the sigma of their
+/// inverse-variance weighted mean, `1 / sqrt(Σ 1/σ²)`.
+///
+/// Returns None if there are no sigmas or any of them is missing or not positive.
+fn inverse_variance_sigma(sigmas: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    let mut information = 0.0;
+    let mut count = 0;
+    for sigma in sigmas {
+        let sigma = sigma.filter(|sigma| *sigma > 0.0)?;
+        information += 1.0 / (sigma * sigma);
+        count += 1;
+    }
+    (count > 0).then(|| 1.0 / information.sqrt())
+}
+
+/// Kilometers per hour in one knot (one nautical mile of 1852 m per hour).
+const KMH_PER_KNOT: f64 = 1.852;
+
+/// Largest disagreement in km/h tolerated between the two VTG speed fields.
+const SPEED_MISMATCH_KMH: f64 = 0.5;
+
+/// Mean Earth radius in meters used for great-circle computations.
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Computes the great-circle distance between two positions with the haversine formula.
+///
+/// # Returns
+/// * `f64` - Distance in meters
+fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = phi2 - phi1;
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+}
+
+/// Parses a heading field, accepting values in [0, 360].
+fn parse_heading(field: Option<&&str>) -> Option<f64> {
+    field?.parse::<f64>().ok().filter(|heading| (0.0..=360.0).contains(heading))
+}
+
+/// Returns the median of a non-empty set of values, the mean of the middle two for an even count.
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+/// Returns the median of a non-empty set of longitudes, taken the short way around the antimeridian.
+///
+/// Longitudes are compared as offsets from the first one, so +179.99, -179.99 and -179.98
+/// have the median -179.99 rather than a value near 0.
+fn median_longitude(longitudes: Vec<f64>) -> f64 {
+    let reference = longitudes[0];
+    let offsets = longitudes.iter().map(|longitude| wrap_longitude(longitude - reference)).collect();
+    wrap_longitude(reference + median(offsets))
+}
+
+/// Computes the initial great-circle bearing from the first position to the second.
+///
+/// # Returns
+/// * `f64` - Bearing in degrees (0-360)
+fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lambda = (lon2 - lon1).to_radians();
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    // Adding zero turns -0 into 0, and rounding can bring tiny negative angles up to 360
+    let bearing = y.atan2(x).to_degrees().rem_euclid(360.0) + 0.0;
+    if bearing >= 360.0 { 0.0 } else { bearing }
+}
+
+/// Wraps a longitude, or a difference between longitudes, into [-180, 180] degrees.
+///
+/// Values already in range are returned unchanged, so wrapping adds no rounding error.
+fn wrap_longitude(longitude: f64) -> f64 {
+    if (-180.0..=180.0).contains(&longitude) {
+        longitude
+    } else {
+        (longitude + 540.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+/// Returns the weighted mean of longitudes, taken the short way around the antimeridian.
+///
+/// Longitudes are averaged as offsets from the first one, so +179.99 and -179.99 average to
+/// 180 rather than 0.
+///
+/// # Arguments
+/// * `longitudes` - Longitudes in degrees with their weights
+fn weighted_mean_longitude(longitudes: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let mut reference = None;
+    let (mut weighted_offset, mut total_weight) = (0.0, 0.0);
+    for (longitude, weight) in longitudes {
+        let reference = *reference.get_or_insert(longitude);
+        weighted_offset += wrap_longitude(longitude - reference) * weight;
+        total_weight += weight;
+    }
+    wrap_longitude(reference.unwrap_or(0.0) + weighted_offset / total_weight)
+}
+
+/// Formats a coordinate in degrees, minutes and seconds.
+///
+/// Seconds are rounded to one decimal, carrying into the minutes and degrees, and minutes and
+/// seconds are zero-padded to two integer digits. Negative values take the S or W hemisphere
+/// letter. Values that are not finite are formatted as plain numbers.
+///
+/// # Arguments
+/// * `decimal_degrees` - Coordinate in decimal degrees
+/// * `is_latitude` - True for a latitude (N/S), false for a longitude (E/W)
+///
+/// # Returns
+/// * `String` - The formatted coordinate
+///
+/// # Example
+/// ```
+/// use nema_parser::gnss_multignss_parser::to_dms;
+/// assert_eq!(to_dms(48.1173, true), "48°07'02.3\"N");
+/// assert_eq!(to_dms(-11.5166667, false), "11°31'00.0\"W");
+/// ```
+pub fn to_dms(decimal_degrees: f64, is_latitude: bool) -> String {
+    if !decimal_degrees.is_finite() {
+        return decimal_degrees.to_string();
+    }
+    let hemisphere = match (is_latitude, decimal_degrees < 0.0) {
+        (true, false) => 'N',
+        (true, true) => 'S',
+        (false, false) => 'E',
+        (false, true) => 'W',
+    };
+    // Round once, in tenths of an arc second, so the carry propagates
+    let tenths = (decimal_degrees.abs() * 36_000.0).round() as u64;
+    let degrees = tenths / 36_000;
+    let minutes = tenths / 600 % 60;
+    let seconds = tenths % 600;
+    format!("{}°{:02}'{:02}.{}\"{}", degrees, minutes, seconds / 10, seconds % 10, hemisphere)
+}
+
+/// Scales a value and rounds it to the nearest `i32`.
+///
+/// # Returns
+/// * `Option<i32>` - The scaled value, or None if it is not finite or does not fit in an `i32`
+fn scale_to_i32(value: f64, scale: f64) -> Option<i32> {
+    let scaled = (value * scale).round();
+    if scaled.is_finite() && scaled >= f64::from(i32::MIN) && scaled <= f64::from(i32::MAX) {
+        Some(scaled as i32)
+    } else {
+        None
+    }
+}
+
+/// Computes HDOP and VDOP from satellite line-of-sight directions.
+///
+/// Builds the geometry matrix with rows `[east, north, up, 1]` (single receiver clock term)
+/// and inverts `GᵀG` to obtain the DOP values.
+///
+/// # Arguments
+/// * `line_of_sight` - Elevation and azimuth pairs in degrees
+///
+/// # Returns
+/// * `Option<(f64, f64)>` - HDOP and VDOP, or None with fewer than 4 satellites or degenerate geometry
+fn geometry_dop(line_of_sight: &[(f64, f64)]) -> Option<(f64, f64)> {
+    if line_of_sight.len() < 4 {
+        return None;
+    }
+
+    let mut normal = [[0.0_f64; 4]; 4];
+    for (elevation, azimuth) in line_of_sight {
+        let (el, az) = (elevation.to_radians(), azimuth.to_radians());
+        let row = [el.cos() * az.sin(), el.cos() * az.cos(), el.sin(), 1.0];
+        for i in 0..4 {
+            for j in 0..4 {
+                normal[i][j] += row[i] * row[j];
+            }
+        }
+    }
+
+    let cofactor = invert_4x4(normal)?;
+    let hdop_sq = cofactor[0][0] + cofactor[1][1];
+    let vdop_sq = cofactor[2][2];
+    if hdop_sq < 0.0 || vdop_sq < 0.0 {
+        return None;
+    }
+    Some((hdop_sq.sqrt(), vdop_sq.sqrt()))
+}
+
+/// Inverts a 4x4 matrix using Gauss-Jordan elimination with partial pivoting.
+///
+/// # Returns
+/// * `Option<[[f64; 4]; 4]>` - The inverse, or None if the matrix is singular
+fn invert_4x4(mut matrix: [[f64; 4]; 4]) -> Option<[[f64; 4]; 4]> {
+    let mut inverse = [[0.0_f64; 4]; 4];
+    for (i, row) in inverse.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    for col in 0..4 {
+        let pivot = (col..4)
+            .max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale = matrix[col][col];
+        for j in 0..4 {
+            matrix[col][j] /= scale;
+            inverse[col][j] /= scale;
+        }
+        for row in 0..4 {
+            if row != col {
+                let factor = matrix[row][col];
+                for j in 0..4 {
+                    matrix[row][j] -= factor * matrix[col][j];
+                    inverse[row][j] -= factor * inverse[col][j];
+                }
+            }
+        }
+    }
+    Some(inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    // Tests read the parser through its accessors, like downstream code
+    #![warn(deprecated)]
+    use super::*;
+
+    /// Formats decimal degrees as an NMEA `(d)ddmm.mmmm,H` coordinate pair.
+    fn nmea_coordinate(value: f64, is_latitude: bool) -> String {
+        let hemi = match (is_latitude, value < 0.0) {
+            (true, false) => "N",
+            (true, true) => "S",
+            (false, false) => "E",
+            (false, true) => "W",
+        };
+        let value = value.abs();
+        let degrees = value.floor();
+        let minutes = (value - degrees) * 60.0;
+        if is_latitude {
+            format!("{:02}{:07.4},{}", degrees, minutes, hemi)
+        } else {
+            format!("{:03}{:07.4},{}", degrees, minutes, hemi)
+        }
+    }
+
+    /// Builds a GGA sentence for the given position.
+    fn gga_at(lat: f64, lon: f64) -> String {
+        format!("$GNGGA,123519,{},{},1,08,0.9,545.4,M,46.9,M,,*47",
+                nmea_coordinate(lat, true), nmea_coordinate(lon, false))
+    }
+
+    /// Builds a `$`-prefixed sentence with a correct `*hh` checksum.
+    fn with_checksum(body: &str) -> String {
+        let checksum = body.bytes().fold(0u8, |acc, byte| acc ^ byte);
+        format!("${}*{:02X}", body, checksum)
+    }
+
+    #[test]
+    fn test_gnssdata_new() {
+        let gnss = GnssData::new();
+        assert!(gnss.system("GPS").is_some());
+        assert!(gnss.system("GLONASS").is_some());
+        assert!(gnss.system("GALILEO").is_some());
+        assert!(gnss.system("BEIDOU").is_some());
+    }
+
+    #[test]
+    fn test_feed_nmea_gga() {
+        let mut gnss = GnssData::new();
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gga);
+        assert_eq!(gnss.time(), Some("123519"));
+        assert!(gnss.latitude().is_some());
+        assert!(gnss.longitude().is_some());
+        assert_eq!(gnss.fix_quality(), Some(1));
+        assert_eq!(gnss.num_satellites(), Some(8));
+        assert_eq!(gnss.altitude(), Some(545.4));
+    }
+
+    #[test]
+    fn test_feed_nmea_gps_gsv() {
+        let mut gnss = GnssData::new();
+        let gsv = with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39");
+        let _ = gnss.feed_nmea(&gsv);
+        let gps_info = gnss.system("GPS").unwrap().satellites();
+        assert_eq!(gps_info.len(), 4);
+        assert!(gps_info.contains_key(&1));
+        assert!(gps_info.contains_key(&2));
+        assert!(gps_info.contains_key(&3));
+        assert!(gps_info.contains_key(&4));
+        let sat1 = gps_info.get(&1).unwrap();
+        assert_eq!(sat1.prn, 1);
+        assert_eq!(sat1.elevation, Some(40));
+        assert_eq!(sat1.azimuth, Some(83));
+        assert_eq!(sat1.snr, Some(41));
+    }
+
+    #[test]
+    fn test_feed_nmea_glonass_gsv() {
+        let mut gnss = GnssData::new();
+        let gsv = with_checksum("GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17");
+        let _ = gnss.feed_nmea(&gsv);
+        let glonass_info = gnss.system("GLONASS").unwrap().satellites();
+        assert_eq!(glonass_info.len(), 4);
+        assert!(glonass_info.contains_key(&67));
+        assert!(glonass_info.contains_key(&68));
+        assert!(glonass_info.contains_key(&69));
+        assert!(glonass_info.contains_key(&77));
+        let sat67 = glonass_info.get(&67).unwrap();
+        assert_eq!(sat67.prn, 67);
+        assert_eq!(sat67.elevation, Some(14));
+        assert_eq!(sat67.azimuth, Some(186));
+        assert_eq!(sat67.snr, Some(9));
+    }
+
+    #[test]
+    fn test_feed_nmea_galileo_gsv() {
+        let mut gnss = GnssData::new();
+        let gsv = "$GAGSV,1,1,04,301,45,123,35,302,30,045,40,303,60,234,45,304,25,156,38*XX";
+        let _ = gnss.feed_nmea(gsv);
+        let galileo_info = gnss.system("GALILEO").unwrap().satellites();
+        assert_eq!(galileo_info.len(), 4);
+        assert!(galileo_info.contains_key(&301));
+        assert!(galileo_info.contains_key(&302));
+        assert!(galileo_info.contains_key(&303));
+        assert!(galileo_info.contains_key(&304));
+        let sat301 = galileo_info.get(&301).unwrap();
+        assert_eq!(sat301.prn, 301);
+        assert_eq!(sat301.elevation, Some(45));
+        assert_eq!(sat301.azimuth, Some(123));
+        assert_eq!(sat301.snr, Some(35));
+    }
+
+    #[test]
+    fn test_feed_nmea_beidou_gsv() {
+        let mut gnss = GnssData::new();
+        let gsv = "$BDGSV,1,1,04,201,45,123,35,202,30,045,40,203,60,234,45,204,25,156,38*XX";
+        let _ = gnss.feed_nmea(gsv);
+        let beidou_info = gnss.system("BEIDOU").unwrap().satellites();
+        assert_eq!(beidou_info.len(), 4);
+        assert!(beidou_info.contains_key(&201));
+        assert!(beidou_info.contains_key(&202));
+        assert!(beidou_info.contains_key(&203));
+        assert!(beidou_info.contains_key(&204));
+        let sat201 = beidou_info.get(&201).unwrap();
+        assert_eq!(sat201.prn, 201);
+        assert_eq!(sat201.elevation, Some(45));
+        assert_eq!(sat201.azimuth, Some(123));
+        assert_eq!(sat201.snr, Some(35));
+    }
+
+    #[test]
+    fn test_feed_nmea_gsa_gps() {
+        let mut gnss = GnssData::new();
+        let gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,09,10,11,12,1.2,0.9,2.1*39";
+        let _ = gnss.feed_nmea(gsa);
+        let gps_used = gnss.system("GPS").unwrap().satellites_used();
+        assert!(gps_used.contains(&1));
+        assert!(gps_used.contains(&2));
+        assert!(gps_used.contains(&3));
+        assert!(gps_used.contains(&4));
+        assert_eq!(gnss.system("GPS").unwrap().pdop(), Some(1.2));
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), Some(0.9));
+        assert_eq!(gnss.system("GPS").unwrap().vdop(), Some(2.1));
+    }
+
+    #[test]
+    fn test_feed_nmea_gsa_glonass() {
+        let mut gnss = GnssData::new();
+        let gsa = "$GNGSA,A,3,67,68,69,77,78,79,86,87,88,,,,1.8,1.1,1.4*20";
+        let _ = gnss.feed_nmea(gsa);
+
+        // Debug output
+        println!("GLONASS satellites_used: {:?}", gnss.system("GLONASS").unwrap().satellites_used());
+        println!("GLONASS pdop: {:?}", gnss.system("GLONASS").unwrap().pdop());
+        println!("GLONASS hdop: {:?}", gnss.system("GLONASS").unwrap().hdop());
+        println!("GLONASS vdop: {:?}", gnss.system("GLONASS").unwrap().vdop());
+
+        let glonass_used = gnss.system("GLONASS").unwrap().satellites_used();
+        assert!(glonass_used.contains(&67));
+        assert!(glonass_used.contains(&68));
+        assert!(glonass_used.contains(&69));
+        assert!(glonass_used.contains(&77));
+        assert_eq!(gnss.system("GLONASS").unwrap().pdop(), Some(1.8));
+        assert_eq!(gnss.system("GLONASS").unwrap().hdop(), Some(1.1));
+        assert_eq!(gnss.system("GLONASS").unwrap().vdop(), Some(1.4));
+    }
+
+    #[test]
+    fn test_feed_nmea_gsa_galileo() {
+        let mut gnss = GnssData::new();
+        let gsa = "$GNGSA,A,3,301,302,303,304,305,306,,,,,,,2.1,1.3,1.6*XX";
+        let _ = gnss.feed_nmea(gsa);
+        let galileo_used = gnss.system("GALILEO").unwrap().satellites_used();
+        assert!(galileo_used.contains(&301));
+        assert!(galileo_used.contains(&302));
+        assert!(galileo_used.contains(&303));
+        assert!(galileo_used.contains(&304));
+        assert_eq!(gnss.system("GALILEO").unwrap().pdop(), Some(2.1));
+        assert_eq!(gnss.system("GALILEO").unwrap().hdop(), Some(1.3));
+        assert_eq!(gnss.system("GALILEO").unwrap().vdop(), Some(1.6));
+    }
+
+    #[test]
+    fn test_feed_nmea_gsa_beidou() {
+        let mut gnss = GnssData::new();
+        let gsa = "$GNGSA,A,3,201,202,203,204,205,206,,,,,,,1.5,0.8,1.2*XX";
+        let _ = gnss.feed_nmea(gsa);
+        let beidou_used = gnss.system("BEIDOU").unwrap().satellites_used();
+        assert!(beidou_used.contains(&201));
+        assert!(beidou_used.contains(&202));
+        assert!(beidou_used.contains(&203));
+        assert!(beidou_used.contains(&204));
+        assert_eq!(gnss.system("BEIDOU").unwrap().pdop(), Some(1.5));
+        assert_eq!(gnss.system("BEIDOU").unwrap().hdop(), Some(0.8));
+        assert_eq!(gnss.system("BEIDOU").unwrap().vdop(), Some(1.2));
+    }
+
+    #[test]
+    fn test_coordinates_update_for_systems_with_satellites() {
+        let mut gnss = GnssData::new();
+
+        // Add GPS satellites
+        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
+        let _ = gnss.feed_nmea(gps_gsv);
+
+        // Add GLONASS satellites
+        let glonass_gsv = "$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*XX";
+        let _ = gnss.feed_nmea(glonass_gsv);
+
+        // Update coordinates via GGA
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gga);
+
+        // Systems with satellites should have coordinates
+        assert!(gnss.system("GPS").unwrap().latitude().is_some());
+        assert!(gnss.system("GPS").unwrap().longitude().is_some());
+        assert!(gnss.system("GLONASS").unwrap().latitude().is_some());
+        assert!(gnss.system("GLONASS").unwrap().longitude().is_some());
+
+        // Systems without satellites should not have coordinates
+        assert!(gnss.system("GALILEO").unwrap().latitude().is_none());
+        assert!(gnss.system("GALILEO").unwrap().longitude().is_none());
+        assert!(gnss.system("BEIDOU").unwrap().latitude().is_none());
+        assert!(gnss.system("BEIDOU").unwrap().longitude().is_none());
+    }
+
+    #[test]
+    fn test_fusion_is_reproducible() {
+        // Every new GnssData gets a differently seeded systems map
+        let fuse = || {
+            let mut gnss = GnssData::new();
+            let systems = [("GP", 1, 0.9, 4807.038), ("GL", 65, 1.7, 4807.041), ("GA", 301, 1.3, 4807.036), ("BD", 201, 2.2, 4807.044)];
+            for (talker, first_prn, _, _) in systems {
+                let sats: Vec<String> = (first_prn..first_prn + 4).map(|prn| format!("{},40,083,41", prn)).collect();
+                let _ = gnss.feed_nmea(&with_checksum(&format!("{}GSV,1,1,04,{}", talker, sats.join(","))));
+            }
+            for (_, first_prn, hdop, _) in systems {
+                let prns: Vec<String> = (first_prn..first_prn + 4).map(|prn| prn.to_string()).collect();
+                let _ = gnss.feed_nmea(&with_checksum(&format!("GNGSA,A,3,{},,,,,,,,,1.9,{},1.4", prns.join(","), hdop)));
+            }
+            let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,16,0.9,545.4,M,46.9,M,,"));
+            for (talker, _, _, latitude) in systems {
+                let _ = gnss.feed_nmea(&with_checksum(&format!("{}GLL,{},N,01131.000,E,123520,A,A", talker, latitude)));
+            }
+            gnss.calculate_fused_position();
+            let weighted = gnss.fused_position().cloned().unwrap();
+            gnss.calculate_advanced_fused_position();
+            let advanced = gnss.fused_position().cloned().unwrap();
+            [weighted, advanced].map(|fused| {
+                let bits = [fused.latitude, fused.longitude, fused.altitude_msl, fused.estimated_accuracy, fused.altitude_accuracy]
+                    .map(f64::to_bits);
+                (bits, fused.contributing_systems)
+            })
+        };
+
+        let expected = fuse();
+        assert_eq!(expected[0].1, vec!["GPS", "GLONASS", "GALILEO", "BEIDOU"]);
+        for _ in 0..100 {
+            assert_eq!(fuse(), expected);
+        }
+    }
+
+    #[test]
+    fn test_contributing_order_is_configurable_and_stable() {
+        let mut gnss = GnssData::new();
+        for (talker, first_prn, hdop) in [("GP", 1, 1.9), ("GL", 65, 0.6), ("GA", 301, 1.2)] {
+            let sats: Vec<String> = (first_prn..first_prn + 4).map(|prn| format!("{},40,083,41", prn)).collect();
+            let _ = gnss.feed_nmea(&with_checksum(&format!("{}GSV,1,1,04,{}", talker, sats.join(","))));
+            let prns: Vec<String> = (first_prn..first_prn + 4).map(|prn| prn.to_string()).collect();
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GNGSA,A,3,{},,,,,,,,,1.9,{},1.4", prns.join(","), hdop)));
+        }
+        let _ = gnss.feed_nmea(&gga_at(48.1173, 11.5166667));
+
+        // System accuracies scale with HDOP: GPS 1.9 * 3.8 m, GLONASS max(0.6 * 2.4 m, 2.4 m),
+        // GALILEO 1.2 * 3.6 m
+        let orders = [
+            (ContributingOrder::Canonical, ["GPS", "GLONASS", "GALILEO"]),
+            (ContributingOrder::Name, ["GALILEO", "GLONASS", "GPS"]),
+            (ContributingOrder::Accuracy, ["GLONASS", "GALILEO", "GPS"]),
+        ];
+        for (order, expected) in orders {
+            gnss.set_contributing_order(order);
+            for _ in 0..10 {
+                gnss.calculate_fused_position();
+                assert_eq!(gnss.fused_position().unwrap().contributing_systems, expected, "{:?}", order);
+                gnss.calculate_advanced_fused_position();
+                assert_eq!(gnss.fused_position().unwrap().contributing_systems, expected, "{:?}", order);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fused_position_calculation() {
+        let mut gnss = GnssData::new();
+
+        // Add GPS satellites and coordinates
+        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
+        let _ = gnss.feed_nmea(gps_gsv);
+        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
+        let _ = gnss.feed_nmea(gps_gsa);
+
+        // Add GLONASS satellites and coordinates
+        let glonass_gsv = "$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*XX";
+        let _ = gnss.feed_nmea(glonass_gsv);
+        let glonass_gsa = "$GNGSA,A,3,67,68,69,77,78,79,86,87,,,,,1.8,1.1,1.4*3F";
+        let _ = gnss.feed_nmea(glonass_gsa);
+
+        // Update coordinates
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gga);
+
+        // Calculate fused position
+        gnss.calculate_fused_position();
+
+        assert!(gnss.fused_position().is_some());
+        let fused = gnss.fused_position().unwrap();
+        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
+        assert!(fused.contributing_systems.contains(&"GLONASS".to_string()));
+        assert!(fused.estimated_accuracy > 0.0);
+    }
+
+    #[test]
+    fn test_fusion_falls_back_to_gga_hdop_without_gsa() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,04,1.2,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.gga_hdop(), Some(1.2));
+
+        // GPS accuracy 2.0 m times the penalized GGA HDOP
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!((fused.estimated_accuracy - 1.2 * GGA_HDOP_PENALTY * 2.0).abs() < 1e-9);
+        gnss.calculate_advanced_fused_position();
+        assert!(gnss.fused_position().is_some());
+
+        // A GSA takes precedence over the GGA HDOP
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.6,0.8,1.4"));
+        gnss.calculate_fused_position();
+        // The GSA HDOP also scales the system accuracy to 1.6 m, which bounds the estimate
+        assert!((gnss.fused_position().unwrap().estimated_accuracy - 0.8 * 2.0).abs() < 1e-9);
+
+        // A GGA without a fix carries no usable HDOP
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,,,,,0,00,99.99,,,,,,"));
+        assert_eq!(gnss.gga_hdop(), None);
+    }
+
+    /// Feeds GPS, GLONASS, GALILEO and BEIDOU fixes at the given positions, then runs the
+    /// advanced fusion with an outlier threshold.
+    fn advanced_fusion_of(threshold: f64, positions: [(f64, f64); 4]) -> (FusedPosition, Vec<DataWarning>) {
+        let mut gnss = GnssData::new();
+        gnss.set_outlier_threshold(threshold);
+        let systems = [("GP", "01,02,03,04"), ("GL", "67,68,69,77"), ("GA", "301,302,303,304"), ("GB", "201,202,203,204")];
+        for ((talker, prns), (lat, lon)) in systems.into_iter().zip(positions) {
+            let _ = gnss.feed_nmea(&with_checksum(&format!("{}GSA,A,3,{},,,,,,,,,1.2,0.9,2.1", talker, prns)));
+            let _ = gnss.feed_nmea(&with_checksum(&format!("{}GGA,123519,{},{},1,08,0.9,545.4,M,46.9,M,,",
+                talker, nmea_coordinate(lat, true), nmea_coordinate(lon, false))));
+        }
+        gnss.calculate_advanced_fused_position();
+        (gnss.fused_position().cloned().unwrap(), gnss.take_warnings())
+    }
+
+    #[test]
+    fn test_advanced_fusion_rejects_outlier_system() {
+        // BEIDOU is about 500 m north of the others
+        let positions = [(48.1173, 11.5167), (48.11733, 11.5167), (48.11727, 11.5167), (48.1218, 11.5167)];
+        let (fused, warnings) = advanced_fusion_of(DEFAULT_OUTLIER_THRESHOLD_M, positions);
+        assert_eq!(fused.contributing_systems, ["GPS", "GLONASS", "GALILEO"]);
+        assert!((fused.latitude - 48.1173).abs() < 1e-4);
+        assert!(matches!(&warnings[..], [DataWarning::OutlierRejected { system, distance }]
+            if system == "BEIDOU" && (distance - 500.0).abs() < 10.0));
+
+        // Disabled, the outlier drags the fused latitude north
+        let (dragged, warnings) = advanced_fusion_of(0.0, positions);
+        assert_eq!(dragged.contributing_systems, ["GPS", "GLONASS", "GALILEO", "BEIDOU"]);
+        assert!(dragged.latitude > fused.latitude + 0.0005);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_outlier_rejection_across_antimeridian() {
+        // Three systems within a few meters either side of 180°, BEIDOU about 500 m west
+        let positions = [(-16.5, 179.99999), (-16.5, -179.99999), (-16.5, 179.99998), (-16.5, 179.9953)];
+        let (fused, warnings) = advanced_fusion_of(DEFAULT_OUTLIER_THRESHOLD_M, positions);
+        assert_eq!(fused.contributing_systems, ["GPS", "GLONASS", "GALILEO"]);
+        assert!(180.0 - fused.longitude.abs() < 1e-4);
+        assert!(matches!(&warnings[..], [DataWarning::OutlierRejected { system, .. }] if system == "BEIDOU"));
+
+        // An even count: the two systems east of 180° are not pulled toward 0°
+        let positions = [(-16.5, 179.99999), (-16.5, -179.99999), (-16.5, -179.99998), (-16.5, 179.99998)];
+        let (fused, warnings) = advanced_fusion_of(DEFAULT_OUTLIER_THRESHOLD_M, positions);
+        assert_eq!(fused.contributing_systems.len(), 4);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_fused_position_with_altitude() {
+        let mut gnss = GnssData::new();
+
+        // Add GPS satellites and coordinates with altitude
+        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
+        let _ = gnss.feed_nmea(gps_gsv);
+        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
+        let _ = gnss.feed_nmea(gps_gsa);
+
+        // Add GLONASS satellites and coordinates
+        let glonass_gsv = "$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*XX";
+        let _ = gnss.feed_nmea(glonass_gsv);
+        let glonass_gsa = "$GNGSA,A,3,67,68,69,77,78,79,86,87,,,,,1.8,1.1,1.4*3F";
+        let _ = gnss.feed_nmea(glonass_gsa);
+
+        // Update coordinates with altitude data
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gga);
+
+        // Verify altitude is stored in system data
+        assert_eq!(gnss.system("GPS").unwrap().altitude(), Some(545.4));
+        assert_eq!(gnss.system("GLONASS").unwrap().altitude(), Some(545.4));
+
+        // Calculate fused position
+        gnss.calculate_fused_position();
+
+        assert!(gnss.fused_position().is_some());
+        let fused = gnss.fused_position().unwrap();
+
+        // Verify altitude and altitude accuracy are calculated (use approximate comparison for floating point)
+        assert!((fused.altitude_msl - 545.4).abs() < 0.001);
+        assert!(fused.altitude_accuracy > 0.0);
+        assert!(fused.estimated_accuracy > 0.0);
+
+        // Verify contributing systems
+        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
+        assert!(fused.contributing_systems.contains(&"GLONASS".to_string()));
+    }
+
+    #[test]
+    fn test_advanced_fused_position_with_altitude() {
+        let mut gnss = GnssData::new();
+
+        // Add GPS satellites and coordinates
+        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
+        let _ = gnss.feed_nmea(gps_gsv);
+        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
+        let _ = gnss.feed_nmea(gps_gsa);
+
+        // Add GALILEO satellites with different DOP values
+        let galileo_gsv = "$GAGSV,1,1,04,301,45,123,35,302,30,045,40,303,60,234,45,304,25,156,38*XX";
+        let _ = gnss.feed_nmea(galileo_gsv);
+        let galileo_gsa = "$GNGSA,A,3,301,302,303,304,305,306,,,,,,,2.1,1.3,1.6*XX";
+        let _ = gnss.feed_nmea(galileo_gsa);
+
+        // Update coordinates with altitude
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gga);
+
+        // Calculate advanced fused position
+        gnss.calculate_advanced_fused_position();
+
+        assert!(gnss.fused_position().is_some());
+        let fused = gnss.fused_position().unwrap();
+
+        // Verify altitude fusion and accuracy calculation
+        assert!((fused.altitude_msl - 545.4).abs() < 0.001);
+        // Since both systems have identical altitude, variance will be 0, so altitude_accuracy will be 1.5x horizontal accuracy
+        assert!(fused.altitude_accuracy > 0.0); // Just ensure it's positive
+        assert!(fused.estimated_accuracy >= 1.0); // Minimum 1 meter accuracy
+
+        // Should have both GPS and GALILEO contributing
+        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
+        assert!(fused.contributing_systems.contains(&"GALILEO".to_string()));
+    }
+
+    #[test]
+    fn test_parse_lat_lon() {
+        let lat = parse_lat(Some(&"4807.038"), Some(&"N"));
+        let lon = parse_lon(Some(&"01131.000"), Some(&"E"));
+        assert!(lat.is_some());
+        assert!(lon.is_some());
+        let lat_val = lat.unwrap();
+        let lon_val = lon.unwrap();
+        assert!((lat_val - 48.1173).abs() < 0.0001);
+        assert!((lon_val - 11.5166667).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_parse_lat_lon_hemisphere_case_and_missing() {
+        let south = parse_lat(Some(&"4807.038"), Some(&"s")).unwrap();
+        assert!((south + 48.1173).abs() < 0.0001);
+        assert_eq!(parse_lat(Some(&"4807.038"), Some(&"n")), parse_lat(Some(&"4807.038"), Some(&"N")));
+        let west = parse_lon(Some(&"01131.000"), Some(&"w")).unwrap();
+        assert!((west + 11.5166667).abs() < 0.0001);
+        assert!(parse_lon(Some(&"01131.000"), Some(&"e")).unwrap() > 0.0);
+
+        // A missing or unknown hemisphere drops the coordinate instead of guessing its sign
+        assert_eq!(parse_lat(Some(&"4807.038"), Some(&"")), None);
+        assert_eq!(parse_lat(Some(&"4807.038"), None), None);
+        assert_eq!(parse_lat(Some(&"4807.038"), Some(&"E")), None);
+        assert_eq!(parse_lon(Some(&"01131.000"), Some(&"WW")), None);
+
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,s,01131.000,w,1,08,0.9,545.4,M,46.9,M,,"));
+        assert!(gnss.latitude().unwrap() < 0.0 && gnss.longitude().unwrap() < 0.0);
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123520,4807.038,,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.latitude(), None);
+    }
+
+    #[test]
+    fn test_beidou_altitude_integration() {
+        let mut gnss = GnssData::new();
+
+        // Add BeiDou satellites
+        let beidou_gsv = "$BDGSV,1,1,04,201,45,123,35,202,30,045,40,203,60,234,45,204,25,156,38*XX";
+        let _ = gnss.feed_nmea(beidou_gsv);
+        let beidou_gsa = "$GNGSA,A,3,201,202,203,204,205,206,,,,,,,1.5,0.8,1.2*XX";
+        let _ = gnss.feed_nmea(beidou_gsa);
+
+        // Add GPS for comparison
+        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
+        let _ = gnss.feed_nmea(gps_gsv);
+        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
+        let _ = gnss.feed_nmea(gps_gsa);
+
+        // Update coordinates with altitude
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,445.2,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gga);
+
+        // Verify BeiDou has altitude data
+        assert_eq!(gnss.system("BEIDOU").unwrap().altitude(), Some(445.2));
+        assert_eq!(gnss.system("GPS").unwrap().altitude(), Some(445.2));
+
+        // Calculate fused position including BeiDou
+        gnss.calculate_fused_position();
+
+        assert!(gnss.fused_position().is_some());
+        let fused = gnss.fused_position().unwrap();
+
+        // Verify BeiDou contributes to altitude fusion
+        assert!((fused.altitude_msl - 445.2).abs() < 0.001);
+        assert!(fused.altitude_accuracy > 0.0);
+        assert!(fused.contributing_systems.contains(&"BEIDOU".to_string()));
+        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
+    }
+
+    #[test]
+    fn test_qzss_gsv_and_gsa_integration() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("QZGSV,1,1,04,193,65,120,44,194,40,200,41,195,30,160,39,199,55,150,42"));
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,193,194,195,199,,,,,,,,,1.6,0.9,1.3"));
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        // BeiDou keeps 201 and up
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,201,202,,,,,,,,,,,1.5,0.8,1.2"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+
+        let qzss = gnss.system("QZSS").unwrap();
+        assert_eq!(qzss.satellites().len(), 4);
+        assert_eq!(qzss.satellites_used(), vec![193, 194, 195, 199]);
+        assert_eq!(qzss.hdop(), Some(0.9));
+        assert_eq!(gnss.system("BEIDOU").unwrap().satellites_used(), vec![201, 202]);
+
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["GPS", "QZSS"]);
+    }
+
+    #[test]
+    fn test_qzss_alternate_prns_under_qz_talker() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("QZGSV,1,1,02,33,65,120,44,37,40,200,41"));
+        let _ = gnss.feed_nmea(&with_checksum("QZGSA,A,3,33,37,,,,,,,,,,,1.6,0.9,1.3"));
+        let _ = gnss.feed_nmea(&with_checksum("GQGLL,3540.1234,N,13945.5678,E,123519,A,A"));
+
+        let qzss = gnss.system("QZSS").unwrap();
+        assert_eq!(qzss.satellites().keys().collect::<Vec<_>>(), vec![33, 37]);
+        assert_eq!(qzss.satellites_used(), vec![33, 37]);
+        assert!(qzss.latitude().is_some());
+        assert!(gnss.system("GPS").unwrap().satellites_used().is_empty());
+        assert!(gnss.system("GPS").unwrap().satellites().is_empty());
+    }
+
+    #[test]
+    fn test_navic_gsv_and_gsa_integration() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GIGSV,1,1,04,401,45,120,44,402,40,200,41,405,30,160,39,409,55,150,42"));
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,401,402,405,409,,,,,,,,,2.1,1.4,1.6"));
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+
+        let navic = gnss.system("NAVIC").unwrap();
+        assert_eq!(navic.satellites().len(), 4);
+        assert_eq!(navic.satellites_used(), vec![401, 402, 405, 409]);
+        assert_eq!(navic.accuracy(), 1.4 * 4.0);
+
+        // The default fused accuracy is recomputed over the active systems, NavIC included
+        let expected = 1.0 / (1.0 / (0.9_f64 * 2.0).powi(2) + 1.0 / (1.4_f64 * 4.0).powi(2)).sqrt();
+        assert!((gnss.get_fused_accuracy() - expected).abs() < 1e-12);
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS", "NAVIC"]);
+    }
+
+    #[test]
+    fn test_navic_prn_range_override() {
+        let mut gnss = GnssData::new();
+        assert_eq!(gnss.get_navic_prn_range(), DEFAULT_NAVIC_PRNS);
+        // Some receivers number NavIC within the BeiDou-adjacent 1xx block
+        gnss.set_navic_prn_range(101..=114);
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,101,102,405,,,,,,,,,,2.1,1.4,1.6"));
+        assert_eq!(gnss.system("NAVIC").unwrap().satellites_used(), vec![101, 102]);
+        assert_eq!(gnss.take_warnings().len(), 1);
+
+        // The GI talker is NavIC whatever the range
+        let _ = gnss.feed_nmea(&with_checksum("GIGSA,A,3,405,,,,,,,,,,,,2.1,1.4,1.6"));
+        assert_eq!(gnss.system("NAVIC").unwrap().satellites_used(), vec![101, 102, 405]);
+
+        gnss.reset();
+        assert_eq!(gnss.get_navic_prn_range(), 101..=114);
+    }
+
+    #[test]
+    fn test_default_accuracy_values() {
+        let gnss = GnssData::new();
+        
+        // Calculate expected fused accuracy using RSS formula
+        let expected_fused_accuracy = 1_f64/(((1_f64/2.0_f64.powi(2)) +
+                                              (1_f64/4.0_f64.powi(2)) +
+                                              (1_f64/3.0_f64.powi(2)) +
+                                              (1_f64/3.0_f64.powi(2)) +
+                                              (1_f64/3.0_f64.powi(2)) +
+                                              (1_f64/4.0_f64.powi(2))).sqrt());
+
+        // Test calculated fused accuracy (approximately 1.19)
+        assert!((gnss.get_fused_accuracy() - expected_fused_accuracy).abs() < 0.01);
+
+        // Test default system accuracies
+        assert_eq!(gnss.get_system_accuracy("GPS"), Some(2.0));
+        assert_eq!(gnss.get_system_accuracy("GLONASS"), Some(4.0));
+        assert_eq!(gnss.get_system_accuracy("GALILEO"), Some(3.0));
+        assert_eq!(gnss.get_system_accuracy("BEIDOU"), Some(3.0));
+        assert_eq!(gnss.get_system_accuracy("QZSS"), Some(3.0));
+        assert_eq!(gnss.get_system_accuracy("NAVIC"), Some(4.0));
+        assert_eq!(gnss.get_system_accuracy("INVALID"), None);
+    }
+
+    #[test]
+    fn test_builder_enables_selected_systems() {
+        let mut gnss = GnssDataBuilder::new()
+            .with_system(GnssSystem::Gps, 2.0)
+            .with_system(GnssSystem::Galileo, 5.0)
+            .with_system(GnssSystem::Galileo, 3.0)
+            .build();
+        assert_eq!(gnss.systems().count(), 2);
+        assert_eq!(gnss.get_system_fixed_accuracy("GALILEO"), Some(3.0));
+        assert_eq!(gnss.get_system_fixed_accuracy("GLONASS"), None);
+
+        // The RSS base covers only the enabled systems
+        let expected = 1.0 / (1.0 / 2.0_f64.powi(2) + 1.0 / 3.0_f64.powi(2)).sqrt();
+        assert!((gnss.get_fused_accuracy() - expected).abs() < 1e-12);
+
+        // Sentences of disabled systems are ignored
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,67,14,186,29,68,49,228,26,69,42,308,30,77,15,064,27"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,67,68,,,,,,,,,1.2,0.9,2.1"));
+        assert!(gnss.system("GLONASS").is_none());
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), [1, 2]);
+
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, ["GPS"]);
+
+        // A reset keeps the enabled systems
+        gnss.set_system_fixed_accuracy("GPS", 1.5);
+        gnss.reset();
+        assert_eq!(gnss.systems().count(), 2);
+        assert_eq!(gnss.get_system_accuracy("GPS"), Some(1.5));
+    }
+
+    #[test]
+    fn test_accuracy_getters_and_setters() {
+        let mut gnss = GnssData::new();
+
+        // Calculate expected fused accuracy using RSS formula
+        let expected_fused_accuracy = 1_f64/(((1_f64/2.0_f64.powi(2)) +
+                                              (1_f64/4.0_f64.powi(2)) +
+                                              (1_f64/3.0_f64.powi(2)) +
+                                              (1_f64/3.0_f64.powi(2)) +
+                                              (1_f64/3.0_f64.powi(2)) +
+                                              (1_f64/4.0_f64.powi(2))).sqrt());
+
+        // Test fused accuracy getter with calculated value (approximately 1.19)
+        assert!((gnss.get_fused_accuracy() - expected_fused_accuracy).abs() < 0.01);
+        gnss.set_fused_accuracy(3.0);
+        // Fused accuracy is always dynamic, so check the value again
+        assert!((gnss.get_fused_accuracy() - expected_fused_accuracy).abs() < 0.01);
+
+        // Test system accuracy getter and setter
+        assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(2.0));
+        assert!(gnss.set_system_fixed_accuracy("GPS", 1.5));
+        assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(1.5));
+
+        // Test setting accuracy for invalid system
+        assert!(!gnss.set_system_fixed_accuracy("INVALID", 1.0));
+        assert_eq!(gnss.get_system_fixed_accuracy("INVALID"), None);
+    }
+
+    #[test]
+    fn test_get_all_system_accuracies() {
+        let mut gnss = GnssData::new();
+
+        // Test getting all default accuracies
+        let accuracies = gnss.get_all_system_accuracies();
+        assert_eq!(accuracies.len(), 6);
+        assert_eq!(accuracies.get("GPS"), Some(&2.0));
+        assert_eq!(accuracies.get("GLONASS"), Some(&4.0));
+        assert_eq!(accuracies.get("GALILEO"), Some(&3.0));
+        assert_eq!(accuracies.get("BEIDOU"), Some(&3.0));
+        assert_eq!(accuracies.get("QZSS"), Some(&3.0));
+        assert_eq!(accuracies.get("NAVIC"), Some(&4.0));
+
+        // Test after modifying some accuracies
+        gnss.set_system_fixed_accuracy("GPS", 1.8);
+        gnss.set_system_fixed_accuracy("GALILEO", 2.5);
+
+        let updated_accuracies = gnss.get_all_system_accuracies();
+        assert_eq!(updated_accuracies.get("GPS"), Some(&1.8));
+        assert_eq!(updated_accuracies.get("GLONASS"), Some(&4.0));
+        assert_eq!(updated_accuracies.get("GALILEO"), Some(&2.5));
+        assert_eq!(updated_accuracies.get("BEIDOU"), Some(&3.0));
+    }
+
+    #[test]
+    fn test_warmup_epochs_suppress_then_report() {
+        let mut gnss = GnssData::new();
+        gnss.set_warmup_epochs(3);
+        // The good epoch is replayed after the dropout
+        gnss.set_late_tolerance(Duration::MAX);
+
+        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
+        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
+        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let _ = gnss.feed_nmea(gps_gsv);
+        let _ = gnss.feed_nmea(gps_gsa);
+        let _ = gnss.feed_nmea(gga);
+
+        // Fewer good epochs than the warm-up count are suppressed
+        for _ in 0..3 {
+            gnss.calculate_fused_position();
+            assert!(gnss.fused_position().is_none());
+        }
+
+        // The next good epoch is reported
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some());
+
+        // A dropout resets the warm-up counter
+        let _ = gnss.feed_nmea("$GNGGA,123520,,,,,0,00,,,M,,M,,*47");
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_none());
+        let _ = gnss.feed_nmea(gga);
+        for _ in 0..3 {
+            gnss.calculate_fused_position();
+            assert!(gnss.fused_position().is_none());
+        }
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some());
+    }
+
+    #[test]
+    fn test_geometry_what_if_mask_and_exclusion() {
+        let mut gnss = GnssData::new();
+        // Synthetic GPS constellation spread over elevations and azimuths
+        let _ = gnss.feed_nmea("$GPGSV,2,1,08,01,10,000,40,02,20,090,40,03,30,180,40,04,40,270,40*XX");
+        let _ = gnss.feed_nmea("$GPGSV,2,2,08,05,50,045,40,06,60,135,40,07,70,225,40,08,80,315,40*XX");
+        // Synthetic Galileo satellites
+        let _ = gnss.feed_nmea("$GAGSV,1,1,04,301,15,060,40,302,25,150,40,303,35,240,40,304,45,330,40*XX");
+
+        let all = gnss.geometry_what_if(0.0, &[]);
+        assert_eq!(all.remaining_sats, 12);
+        assert!(all.computed_hdop.is_some());
+        assert!(all.computed_vdop.is_some());
+
+        // Raising the mask removes satellites and can only degrade the geometry
+        let mut previous_hdop = all.computed_hdop.unwrap();
+        let mut previous_count = all.remaining_sats;
+        for mask in [12.0, 22.0, 32.0] {
+            let summary = gnss.geometry_what_if(mask, &[]);
+            assert!(summary.remaining_sats < previous_count);
+            let hdop = summary.computed_hdop.unwrap();
+            assert!(hdop >= previous_hdop);
+            previous_hdop = hdop;
+            previous_count = summary.remaining_sats;
+        }
+
+        // Excluding Galileo leaves only the GPS satellites
+        let gps_only = gnss.geometry_what_if(0.0, &[GnssSystem::Galileo]);
+        assert_eq!(gps_only.remaining_sats, 8);
+
+        // Fewer than four satellites yields no DOP values
+        let sparse = gnss.geometry_what_if(65.0, &[]);
+        assert_eq!(sparse.remaining_sats, 2);
+        assert!(sparse.computed_hdop.is_none());
+        assert!(sparse.computed_vdop.is_none());
+    }
+
+    #[test]
+    fn test_per_talker_statistics() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,05,06,07,08,09,10,11,12,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,2,1,08,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea("$GPGSV,2,2,08,05,40,083,41,06,17,308,43,07,13,172,42,08,09,020,39*00");
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,2,1,08,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17"));
+
+        let talkers = gnss.get_stats().talkers();
+        assert_eq!(talkers.len(), 3);
+        assert_eq!(talkers[0].source, "GL");
+        assert_eq!(talkers[0].sentences, 1);
+        assert_eq!(talkers[0].checksum_failures, 0);
+        assert_eq!(talkers[1].source, "GN");
+        assert_eq!(talkers[1].sentences, 2);
+        assert_eq!(talkers[1].checksum_failures, 0);
+        assert_eq!(talkers[2].source, "GP");
+        assert_eq!(talkers[2].sentences, 2);
+        assert_eq!(talkers[2].checksum_failures, 1);
+        assert!(talkers.iter().all(|talker| talker.last_seen.is_some()));
+    }
+
+    #[test]
+    fn test_checksum_validation_drops_corrupt_sentences() {
+        let good = with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,");
+        let corrupt = good.replace("4807.038", "4907.038");
+        let truncated = &good[..30];
+        assert!(validate_checksum(&good));
+        assert!(!validate_checksum(&corrupt));
+        assert!(!validate_checksum(truncated));
+
+        // Disabled by default: corrupt sentences are still parsed
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&corrupt);
+        assert!((gnss.latitude().unwrap() - (49.0 + 7.038 / 60.0)).abs() < 1e-9);
+        assert_eq!(gnss.get_stats().rejected_sentences(), 0);
+
+        let mut gnss = GnssData::new();
+        gnss.set_checksum_validation(true);
+        let _ = gnss.feed_nmea(&good);
+        let _ = gnss.feed_nmea(&corrupt);
+        let _ = gnss.feed_nmea(truncated);
+        assert!((gnss.latitude().unwrap() - (48.0 + 7.038 / 60.0)).abs() < 1e-9);
+        assert_eq!(gnss.get_stats().rejected_sentences(), 2);
+        assert_eq!(gnss.get_stats().sentence_count("GGA"), 1);
+        assert_eq!(gnss.get_stats().talkers()[0].checksum_failures, 1);
+    }
+
+    #[test]
+    fn test_late_position_sentences_are_ignored() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        // Epoch 123520's RMC and GLL overtake epoch 123519's GGA and GLL
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W"));
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123520,A,4807.040,N,01131.002,E,022.4,084.4,230394,003.1,W"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807.040,N,01131.002,E,123520,A,A"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807.038,N,01131.000,E,123519,A,A"));
+        // Sub-second jitter within the tolerance is accepted
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519.97,4807.040,N,01131.002,E,1,08,0.9,546.0,M,46.9,M,,"));
+
+        assert_eq!(gnss.get_stats().late_sentences(), 2);
+        assert_eq!(gnss.time(), Some("123519.97"));
+        assert!((gnss.latitude().unwrap() - (48.0 + 7.040 / 60.0)).abs() < 1e-9);
+        let gps = gnss.system("GPS").unwrap();
+        assert!((gps.longitude().unwrap() - (11.0 + 31.002 / 60.0)).abs() < 1e-9);
+        assert_eq!(gps.altitude(), Some(546.0));
+
+        // The day rolls over at midnight
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807.040,N,01131.002,E,235959,A,A"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807.041,N,01131.002,E,000000,A,A"));
+        assert_eq!(gnss.time(), Some("000000"));
+        assert_eq!(gnss.get_stats().late_sentences(), 2);
+    }
+
+    #[test]
+    fn test_speed_unit_conversions() {
+        let mut gnss = GnssData::new();
+        assert_eq!(gnss.speed_kmh(), None);
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123519,A,4807.038,N,01131.000,E,010.0,084.4,230394,003.1,W"));
+        assert!((gnss.speed_kmh().unwrap() - 18.52).abs() < 1e-9);
+        assert!((gnss.speed_ms().unwrap() - 5.14444).abs() < 1e-5);
+
+        // VTG without knots falls back to km/h
+        gnss.set_speed_precedence(&["VTG"]);
+        let _ = gnss.feed_nmea(&with_checksum("GNVTG,084.4,T,,M,,N,037.0,K,A"));
+        assert!((gnss.speed_kmh().unwrap() - 37.0).abs() < 1e-9);
+        assert!(gnss.take_warnings().is_empty());
+
+        // Both fields are cross-checked
+        let _ = gnss.feed_nmea(&with_checksum("GNVTG,084.4,T,,M,022.4,N,041.5,K,A"));
+        assert!(gnss.take_warnings().is_empty());
+        let _ = gnss.feed_nmea(&with_checksum("GNVTG,084.4,T,,M,022.4,N,050.0,K,A"));
+        assert_eq!(gnss.speed_knots(), Some(22.4));
+        assert_eq!(gnss.take_warnings(), vec![DataWarning::SpeedMismatch { knots: 22.4, kmh: 50.0 }]);
+    }
+
+    #[test]
+    fn test_vtg_full_modern_format() {
+        let mut gnss = GnssData::new();
+        gnss.feed_nmea(&with_checksum("GPVTG,054.7,T,034.4,M,005.5,N,010.2,K,D")).unwrap();
+        assert_eq!((gnss.track_angle(), gnss.track_angle_magnetic()), (Some(54.7), Some(34.4)));
+        assert_eq!(gnss.speed_knots(), Some(5.5));
+        assert!(gnss.take_warnings().is_empty());
+
+        // Mode 'N' reports no fix and updates nothing
+        let _ = gnss.feed_nmea(&with_checksum("GPVTG,120.0,T,100.0,M,001.0,N,001.9,K,N"));
+        assert_eq!((gnss.track_angle(), gnss.track_angle_magnetic()), (Some(54.7), Some(34.4)));
+        assert_eq!(gnss.speed_knots(), Some(5.5));
+
+        // Values are found by their unit letters, even with a pair left out
+        let _ = gnss.feed_nmea(&with_checksum("GPVTG,054.7,T,005.5,N,010.2,K,A"));
+        assert_eq!((gnss.track_angle(), gnss.track_angle_magnetic()), (Some(54.7), None));
+        assert_eq!(gnss.speed_knots(), Some(5.5));
+    }
+
+    #[test]
+    fn test_vtg_old_positional_format() {
+        let mut gnss = GnssData::new();
+        gnss.feed_nmea(&with_checksum("GPVTG,054.7,034.4,005.5,010.2")).unwrap();
+        assert_eq!((gnss.track_angle(), gnss.track_angle_magnetic()), (Some(54.7), Some(34.4)));
+        assert_eq!(gnss.speed_knots(), Some(5.5));
+        assert!((gnss.speed_kmh().unwrap() - 10.1860).abs() < 1e-3);
+        assert!(gnss.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_receiver_profile() {
+        // NMEA 4.10 multi-GNSS RTK rover at 5 Hz
+        let mut gnss = GnssData::new();
+        for time in ["123519.00", "123519.20", "123519.40"] {
+            for sentence in [
+                format!("GNRMC,{},A,4807.038,N,01131.000,E,0.0,,230394,,,R,V", time),
+                format!("GNGGA,{},4807.038,N,01131.000,E,4,12,0.6,545.4,M,46.9,M,1.0,0001", time),
+                "GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1,1".to_string(),
+                "GNGSA,A,3,301,302,303,,,,,,,,,,1.2,0.9,2.1,3".to_string(),
+                "GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39,1".to_string(),
+                "GAGSV,1,1,03,01,40,083,41,02,17,308,43,03,13,172,42,7".to_string(),
+            ] {
+                let _ = gnss.feed_nmea(&with_checksum(&sentence));
+            }
+        }
+        let profile = gnss.receiver_profile();
+        assert_eq!(profile.constellations, vec![GnssSystem::Gps, GnssSystem::Galileo]);
+        assert_eq!(profile.sentence_types, vec!["GGA", "RMC", "GSA", "GSV"]);
+        assert!(profile.signal_ids && profile.system_ids && profile.mode_indicators && profile.rtk);
+        assert_eq!(profile.min_nmea_version, Some("4.10"));
+        assert_eq!(profile.update_rate_hz, Some(5.0));
+        assert_eq!(
+            profile.to_string(),
+            "Tracks GPS and GALILEO. Emits GGA, RMC, GSA and GSV at 5 Hz. NMEA 4.10 or later \
+             (GSV signal IDs, GSA system IDs and mode indicators seen). RTK solutions reported."
+        );
+
+        // NMEA 2.2 GPS-only receiver at 1 Hz, with a dropped epoch
+        let mut gnss = GnssData::new();
+        for time in ["225444", "225445", "225447"] {
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GPGGA,{},4916.45,N,12311.12,W,1,05,1.5,280.2,M,-17.8,M,,", time)));
+            let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,05,,,,,,,,2.1,1.5,1.4"));
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GPRMC,{},A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E", time)));
+        }
+        let profile = gnss.receiver_profile();
+        assert_eq!(profile.constellations, vec![GnssSystem::Gps]);
+        assert_eq!(profile.sentence_types, vec!["GGA", "RMC", "GSA"]);
+        assert!(!profile.signal_ids && !profile.system_ids && !profile.mode_indicators && !profile.rtk);
+        assert_eq!(profile.min_nmea_version, None);
+        assert_eq!(profile.update_rate_hz, Some(1.0));
+        assert_eq!(
+            profile.to_string(),
+            "Tracks GPS. Emits GGA, RMC and GSA at 1 Hz. NMEA version undetermined."
+        );
+    }
+
+    #[test]
+    fn test_panicking_handler_is_deregistered() {
+        let mut gnss = GnssData::new();
+        assert!(gnss.register_sentence_handler("ROT", |_, parts| {
+            let _rate: f64 = parts[1].parse().expect("rate of turn");
+        }));
+        let _ = gnss.feed_nmea(&with_checksum("HEROT,-0.3,A"));
+        assert!(gnss.take_warnings().is_empty());
+
+        let parsed = gnss.feed_nmea(&with_checksum("HEROT,,A"));
+        assert_eq!(parsed, Ok(SentenceType::Custom("ROT".to_string())));
+        assert_eq!(gnss.take_warnings(), vec![DataWarning::ObserverPanicked { sentence_type: "ROT".to_string() }]);
+
+        // The parser keeps working without the handler
+        let parsed = gnss.feed_nmea(&with_checksum("HEROT,-0.3,A"));
+        assert!(matches!(parsed, Err(NmeaError::UnknownSentence { .. })));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.fix_quality(), Some(1));
+        assert_eq!(gnss.get_stats().sentence_count("ROT"), 2);
+    }
+
+    #[test]
+    fn test_panicking_field_callback_is_caught() {
+        let mut gnss = GnssData::new();
+        let gga = with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,");
+        let parsed = gnss.feed_nmea_with_fields(&gga, |fields| {
+            let _station: u16 = fields.get(14).unwrap_or_default().parse().expect("station ID");
+        });
+
+        assert_eq!(parsed, Ok(SentenceType::Gga));
+        assert_eq!(gnss.take_warnings(), vec![DataWarning::ObserverPanicked { sentence_type: "GGA".to_string() }]);
+        assert_eq!(gnss.fix_quality(), Some(1));
+        assert_eq!(gnss.get_stats().sentence_count("GGA"), 1);
+    }
+
+    #[test]
+    fn test_silent_constellation_expires() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut gnss = GnssData::new();
+        // The replay runs ahead of a clock left at its start
+        gnss.set_clock(ManualClock::new(t0));
+        for (talker, prns) in [("GP", ["01", "02", "03", "04"]), ("GL", ["65", "66", "67", "68"])] {
+            let sats: Vec<String> = prns.iter().map(|prn| format!("{prn},40,083,41")).collect();
+            let _ = gnss.feed_nmea_at(&with_checksum(&format!("{talker}GSV,1,1,04,{}", sats.join(","))), t0);
+            let _ = gnss.feed_nmea_at(&with_checksum(&format!("{talker}GSA,A,3,{},,,,,,,,,1.2,0.9,2.1", prns.join(","))), t0);
+            let _ = gnss.feed_nmea_at(&with_checksum(&format!("{talker}GGA,123519,4807.038,N,01131.000,E,1,04,0.9,545.4,M,46.9,M,,")), t0);
+        }
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS", "GLONASS"]);
+
+        // GLONASS goes silent while GPS keeps reporting
+        for second in 1..=10u64 {
+            let time = t0 + Duration::from_secs(second);
+            let _ = gnss.feed_nmea_at(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,40,083,41,03,40,083,41,04,40,083,41"), time);
+            let _ = gnss.feed_nmea_at(&with_checksum(&format!("GPGGA,1235{},4807.038,N,01131.000,E,1,04,0.9,545.4,M,46.9,M,,", 19 + second)), time);
+        }
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS", "GLONASS"]);
+
+        // Within the window nothing expires
+        assert!(gnss.expire_stale(Duration::from_secs(10)).is_empty());
+        assert_eq!(gnss.expire_stale(Duration::from_secs(5)), vec![GnssSystem::Glonass]);
+        let glonass = gnss.system("GLONASS").unwrap();
+        assert!(glonass.satellites().is_empty() && glonass.satellites_used().is_empty());
+        assert_eq!((glonass.latitude(), glonass.hdop(), glonass.last_update()), (None, None, None));
+        assert_eq!(glonass.accuracy(), glonass.fixed_accuracy());
+        assert_eq!(gnss.system("GPS").unwrap().satellites().len(), 4);
+
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS"]);
+        // An expired system is not expired again
+        assert!(gnss.expire_stale(Duration::from_secs(5)).is_empty());
+    }
+
+    #[test]
+    fn test_manual_clock_drives_staleness() {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        let mut gnss = GnssData::new();
+        gnss.set_clock(clock.clone());
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.system("GPS").unwrap().last_update(), Some(clock.now()));
+
+        // No sentence arrives, only the clock moves
+        clock.advance(Duration::from_secs(4));
+        assert!(gnss.expire_stale(Duration::from_secs(5)).is_empty());
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(gnss.expire_stale(Duration::from_secs(5)), vec![GnssSystem::Gps]);
+        assert_eq!(gnss.system("GPS").unwrap().latitude(), None);
+    }
+
+    #[test]
+    fn test_position_valid_from_rmc_status_and_fix_quality() {
+        let mut gnss = GnssData::new();
+        assert!(!gnss.position_valid());
+
+        // A cold-start RMC carries a position but a void status; the position is not taken
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123519,V,4807.038,N,01131.000,E,,,230394,,"));
+        assert_eq!(gnss.rmc_status(), Some('V'));
+        assert!(gnss.latitude().is_none());
+        assert!(!gnss.position_valid());
+
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123520,A,4807.038,N,01131.000,E,022.4,084.4,230394,,"));
+        assert_eq!(gnss.rmc_status(), Some('A'));
+        assert!(gnss.position_valid());
+
+        // A GGA without a fix overrides a valid status
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123521,4807.038,N,01131.000,E,0,00,,,M,,M,,"));
+        assert!(!gnss.position_valid());
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123522,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert!(gnss.position_valid());
+
+        // GGA alone is enough
+        let mut gga_only = GnssData::new();
+        let _ = gga_only.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gga_only.rmc_status(), None);
+        assert!(gga_only.position_valid());
+    }
+
+    #[test]
+    fn test_distance_and_bearing() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        gnss.calculate_fused_position();
+        let base = gnss.fused_position().cloned().unwrap();
+        let at = |latitude: f64, longitude: f64| FusedPosition { latitude, longitude, ..base.clone() };
+
+        // 0.009 degrees along a meridian is 1000.75 m on the mean-radius sphere
+        let (south, north) = (at(48.1, 11.5), at(48.109, 11.5));
+        assert!((south.distance_to(&north) - 1000.75).abs() < 1.0);
+        assert_eq!(south.bearing_to(&north), 0.0);
+        assert_eq!(north.bearing_to(&south), 180.0);
+
+        // Eiffel Tower to Arc de Triomphe, about 1.71 km to the north
+        let (eiffel, arc) = (at(48.8584, 2.2945), at(48.8738, 2.2950));
+        assert!((eiffel.distance_to(&arc) - 1712.8).abs() < 1.0);
+        assert!((eiffel.distance_to(&arc) - arc.distance_to(&eiffel)).abs() < 1e-9);
+        let bearing = eiffel.bearing_to(&arc);
+        assert!(bearing > 1.0 && bearing < 2.0, "bearing {}", bearing);
+
+        // Across the antimeridian the short way is taken
+        let (west, east) = (at(0.0, 179.995), at(0.0, -179.995));
+        assert!((west.distance_to(&east) - 1111.95).abs() < 1.0);
+        assert!((west.bearing_to(&east) - 90.0).abs() < 1e-6);
+        assert!((east.bearing_to(&west) - 270.0).abs() < 1e-6);
+
+        // Identical positions give zeros, never NaN
+        assert_eq!(base.distance_to(&base), 0.0);
+        assert_eq!(base.bearing_to(&base), 0.0);
+        assert!(base.bearing_to(&base).is_sign_positive());
+    }
+
+    #[test]
+    fn test_to_dms() {
+        assert_eq!(to_dms(48.1173, true), "48°07'02.3\"N");
+        assert_eq!(to_dms(-33.8688, true), "33°52'07.7\"S");
+        assert_eq!(to_dms(-79.98226, false), "79°58'56.1\"W");
+        assert_eq!(to_dms(0.0, false), "0°00'00.0\"E");
+        // 59.99 seconds round up into the next minute and degree
+        assert_eq!(to_dms(10.0 + 59.0 / 60.0 + 59.99 / 3600.0, false), "11°00'00.0\"E");
+
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.latitude_dms(), "48°07'02.3\"N");
+        assert_eq!(fused.longitude_dms(), "11°31'00.0\"E");
+    }
+
+    #[test]
+    fn test_trailing_garbage_after_checksum() {
+        let gsv = with_checksum("GPGSV,1,1,02,01,40,083,41,02,17,308,43");
+        let noisy = format!("{}@#\r\n", gsv);
+        assert!(validate_checksum(&gsv));
+        assert!(!validate_checksum(&noisy));
+
+        // Clean sentences, with or without line terminators, carry no garbage
+        let mut gnss = GnssData::new();
+        gnss.set_strict_framing(true);
+        assert_eq!(gnss.feed_nmea(&format!("{}\r\n", gsv)), Ok(SentenceType::Gsv { system: GnssSystem::Gps }));
+        assert_eq!(gnss.system("GPS").unwrap().satellites().get(&2).and_then(|sat| sat.snr), Some(43));
+        assert_eq!(gnss.get_stats().trailing_garbage_sentences(), 0);
+
+        // Lenient: the garbage is stripped and the last field parses cleanly
+        let mut gnss = GnssData::new();
+        gnss.set_checksum_validation(true);
+        assert_eq!(gnss.feed_nmea(&noisy), Ok(SentenceType::Gsv { system: GnssSystem::Gps }));
+        assert_eq!(gnss.system("GPS").unwrap().satellites().get(&2).and_then(|sat| sat.snr), Some(43));
+        assert_eq!(gnss.get_stats().trailing_garbage_sentences(), 1);
+        assert_eq!(gnss.get_stats().rejected_sentences(), 0);
+        assert!(gnss.take_warnings().is_empty());
+
+        // Strict: the sentence is rejected
+        let mut gnss = GnssData::new();
+        gnss.set_strict_framing(true);
+        assert_eq!(gnss.feed_nmea(&noisy), Err(NmeaError::TrailingGarbage { garbage: "@#".to_string() }));
+        assert!(gnss.system("GPS").unwrap().satellites().is_empty());
+        assert_eq!(gnss.get_stats().trailing_garbage_sentences(), 1);
+        assert_eq!(gnss.get_stats().rejected_sentences(), 1);
+    }
+
+    #[test]
+    fn test_tag_block_source_preferred() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("\\s:rx1,c:1241544035*4A\\$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("\\s:rx2*00\\$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+
+        let talkers = gnss.get_stats().talkers();
+        assert_eq!(talkers.len(), 2);
+        assert_eq!(talkers[0].source, "rx1");
+        assert_eq!(talkers[1].source, "rx2");
+        // The TAG block is stripped before parsing
+        assert_eq!(gnss.system("GPS").unwrap().satellites().len(), 4);
+        assert_eq!(gnss.altitude(), Some(545.4));
+    }
+
+    #[test]
+    fn test_gll_time_drives_fused_timestamp() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$GPGLL,4807.038,N,01131.000,E,123519.00,A,A*XX");
+
+        assert_eq!(gnss.time(), Some("123519.00"));
+
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.time, Some("123519.00".to_string()));
+        assert!((fused.latitude - 48.1173).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_gll_status_and_mode() {
+        let mut gnss = GnssData::new();
+
+        // The combined GNGLL sets the top-level position and every tracked system's
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        assert_eq!(gnss.feed_nmea(&with_checksum("GNGLL,4807.038,N,01131.000,E,123519.00,A,A")), Ok(SentenceType::Gll { system: None }));
+        assert!((gnss.latitude().unwrap() - 48.1173).abs() < 1e-4);
+        assert!((gnss.system("GPS").unwrap().latitude().unwrap() - 48.1173).abs() < 1e-4);
+        assert_eq!(gnss.time(), Some("123519.00"));
+
+        // A valid per-talker GLL updates its system
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807.100,N,01131.000,E,123520.00,A,D"));
+        assert!((gnss.system("GPS").unwrap().latitude().unwrap() - 48.11833).abs() < 1e-4);
+
+        // Void status or mode 'N' keep the last coordinates, but the time still advances
+        for body in ["GPGLL,0000.000,N,00000.000,E,123521.00,V,A", "GPGLL,0000.000,N,00000.000,E,123522.00,A,N"] {
+            let _ = gnss.feed_nmea(&with_checksum(body));
+            assert!((gnss.latitude().unwrap() - 48.11833).abs() < 1e-4, "{}", body);
+            assert!((gnss.system("GPS").unwrap().latitude().unwrap() - 48.11833).abs() < 1e-4, "{}", body);
+            assert!(gnss.system("GPS").unwrap().fix_lost, "{}", body);
+        }
+        assert_eq!(gnss.time(), Some("123522.00"));
+    }
+
+    #[test]
+    fn test_satellite_capacity_bounds_memory() {
+        let mut gnss = GnssData::new();
+
+        // 1000 distinct PRNs in one sequence; only the last 64 carry a strong signal
+        for (index, chunk) in (1..=1000u16).collect::<Vec<_>>().chunks(4).enumerate() {
+            let mut sentence = format!("$GPGSV,250,{},1000", index + 1);
+            for prn in chunk {
+                let snr = if *prn > 936 { 50 } else { prn % 40 };
+                sentence.push_str(&format!(",{},45,100,{}", prn, snr));
+            }
+            let _ = gnss.feed_nmea(&sentence);
+            assert!(gnss.system("GPS").unwrap().satellites().len() <= DEFAULT_MAX_SATELLITES_PER_SYSTEM);
+        }
+
+        let gps_info = gnss.system("GPS").unwrap().satellites();
+        assert_eq!(gps_info.len(), DEFAULT_MAX_SATELLITES_PER_SYSTEM);
+        assert!((937..=1000).all(|prn| gps_info.contains_key(&prn)));
+        assert_eq!(gnss.get_stats().satellite_evictions(), 936);
+    }
+
+    #[test]
+    fn test_satellite_total_capacity() {
+        let mut gnss = GnssData::new();
+        gnss.set_satellite_capacity(8, 10);
+        let _ = gnss.feed_nmea("$GPGSV,2,1,08,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39");
+        let _ = gnss.feed_nmea("$GPGSV,2,2,08,05,40,083,41,06,17,308,43,07,13,172,42,08,09,020,39");
+        let _ = gnss.feed_nmea("$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,30,77,15,064,17");
+
+        let total: usize = gnss.systems().map(|(_, sys)| sys).map(|sys| sys.satellites().len()).sum();
+        assert_eq!(total, 10);
+        assert_eq!(gnss.get_stats().satellite_evictions(), 2);
+    }
+
+    #[test]
+    fn test_satellite_map_keeps_prn_order() {
+        let mut map = SatelliteMap::new();
+        for prn in [17u16, 3, 42, 8] {
+            assert!(map.insert(prn, SatelliteInfo::default()).is_none());
+        }
+        let replaced = map.insert(8, SatelliteInfo { snr: Some(40), ..Default::default() });
+        assert_eq!(replaced.map(|sat| sat.prn), Some(8));
+
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec![3, 8, 17, 42]);
+        assert_eq!(map.get(&8).and_then(|sat| sat.snr), Some(40));
+        assert_eq!(map.remove(&17).map(|sat| sat.prn), Some(17));
+        assert!(!map.contains_key(&17));
+        assert_eq!(map.len(), 3);
+        // Borrowed iteration yields (prn, satellite) pairs, as a HashMap<u16, _> did
+        let pairs: Vec<(u16, Option<u8>)> = (&map).into_iter().map(|(prn, sat)| (*prn, sat.snr)).collect();
+        assert_eq!(pairs, vec![(3, None), (8, Some(40)), (42, None)]);
+    }
+
+    #[test]
+    fn test_single_system_gsa_sets_system_dops() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), Some(0.9));
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2, 3, 4]);
+        assert_eq!(gnss.hdop(), None);
+    }
+
+    #[test]
+    fn test_gsa_system_id_attributes_satellites_and_dops() {
+        let mut gnss = GnssData::new();
+        // Galileo PRNs 1-36 would be taken for GPS without the system ID
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,05,09,24,,,,,,,,,,1.4,0.8,1.1,3"));
+        assert_eq!(gnss.system("GALILEO").unwrap().satellites_used(), vec![5, 9, 24]);
+        assert_eq!(gnss.system("GALILEO").unwrap().hdop(), Some(0.8));
+        assert!(gnss.system("GPS").unwrap().satellites_used().is_empty());
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), None);
+        assert_eq!(gnss.hdop(), None);
+
+        // An empty HDOP is missing, not filled by shifting the system ID into the DOPs
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,,2.1,1"));
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2, 3, 4]);
+        assert_eq!((gnss.system("GPS").unwrap().pdop(), gnss.system("GPS").unwrap().hdop(), gnss.system("GPS").unwrap().vdop()), (Some(1.2), None, Some(2.1)));
+        assert_eq!(gnss.system("GALILEO").unwrap().hdop(), Some(0.8));
+
+        // Without a system ID, PRN ranges still decide
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), Some(0.9));
+    }
+
+    #[test]
+    fn test_gsa_system_id_glonass_and_legacy() {
+        let mut gnss = GnssData::new();
+        // NMEA 4.10, system ID 2: GLONASS, although PRNs 1-24 would be taken for GPS by range
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.5,0.8,1.3,2"));
+        let glonass = gnss.system("GLONASS").unwrap();
+        assert_eq!(glonass.satellites_used(), vec![1, 2, 3, 4]);
+        assert_eq!((glonass.pdop(), glonass.hdop(), glonass.vdop()), (Some(1.5), Some(0.8), Some(1.3)));
+        assert!(gnss.system("GPS").unwrap().satellites_used().is_empty());
+        assert!(gnss.receiver_profile().system_ids);
+
+        // Legacy sentence without a system ID: attributed by PRN range
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,65,66,67,68,,,,,,,,,1.5,0.8,1.3"));
+        let glonass = gnss.system("GLONASS").unwrap();
+        assert_eq!(glonass.satellites_used(), vec![65, 66, 67, 68]);
+        assert_eq!(glonass.hdop(), Some(0.8));
+    }
+
+    #[test]
+    fn test_gsa_dops_at_fixed_positions() {
+        let mut gnss = GnssData::new();
+        // NMEA 4.10 GPGSA with an empty VDOP: the trailing system ID must not become the VDOP
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,,1"));
+        let gps = gnss.system("GPS").unwrap();
+        assert_eq!((gps.pdop(), gps.hdop(), gps.vdop()), (Some(1.2), Some(0.9), None));
+
+        // Without a system ID an empty PDOP does not shift the HDOP and VDOP forward
+        let _ = gnss.feed_nmea(&with_checksum("GLGSA,A,3,65,66,67,68,,,,,,,,,,1.1,1.7"));
+        let glonass = gnss.system("GLONASS").unwrap();
+        assert_eq!((glonass.pdop(), glonass.hdop(), glonass.vdop()), (None, Some(1.1), Some(1.7)));
+    }
+
+    #[test]
+    fn test_mixed_prn_gsa_sets_combined_dops() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,65,66,,,,,,,,,1.6,1.1,1.2"));
+        assert_eq!((gnss.pdop(), gnss.hdop(), gnss.vdop()), (Some(1.6), Some(1.1), Some(1.2)));
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), None);
+        assert_eq!(gnss.system("GLONASS").unwrap().hdop(), None);
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2]);
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites_used(), vec![65, 66]);
+
+        // Fusion weighs systems without their own DOPs with the combined ones
+        let _ = gnss.feed_nmea(&gga_at(48.1173, 11.5166667));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["GPS".to_string()]);
+        assert!((fused.estimated_accuracy - 1.1 * 2.0).abs() < 1e-9);
+        assert_eq!(gnss.snapshot().hdop, Some(1.1));
+    }
+
+    #[test]
+    fn test_satellites_used_replaced_each_epoch() {
+        let mut gnss = GnssData::new();
+        for _ in 0..100 {
+            let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        }
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2, 3, 4]);
+
+        // Several GNGSA lines of one epoch accumulate; the next epoch replaces them
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,05,,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,65,66,,,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,06,,,,,,,,,,,,1.2,0.9,2.1"));
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2, 5, 6]);
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites_used(), vec![65, 66]);
+
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,02,05,,,,,,,,,,,1.2,0.9,2.1"));
+        // A talker GSA without PRNs empties its system's list
+        let _ = gnss.feed_nmea(&with_checksum("GLGSA,A,1,,,,,,,,,,,,,99.9,99.9,99.9"));
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![2, 5]);
+        assert!(gnss.system("GLONASS").unwrap().satellites_used().is_empty());
+    }
+
+    #[test]
+    fn test_gsa_manual_selection_mode() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GNGSA,M,2,01,02,03,04,,,,,,,,,1.2,0.9,2.1*XX");
+        assert_eq!(gnss.system("GPS").unwrap().selection_mode(), Some('M'));
+        assert_eq!(gnss.is_auto_mode("GPS"), Some(false));
+        assert_eq!(gnss.is_auto_mode("INVALID"), None);
+    }
+
+    #[test]
+    fn test_derived_course_stationary_noise() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39");
+
+        // Jitter of about a meter around a fixed point
+        let jitter = [(0.0, 0.0), (0.00001, -0.00001), (-0.00001, 0.00001), (0.000005, 0.00001), (0.0, -0.00001)];
+        for (dlat, dlon) in jitter {
+            let _ = gnss.feed_nmea(&gga_at(48.0 + dlat, 11.0 + dlon));
+            gnss.calculate_fused_position();
+            assert!(gnss.course().is_none());
+        }
+    }
+
+    #[test]
+    fn test_derived_course_straight_track() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39");
+
+        // 20 m steps on a bearing of 45 degrees
+        let step = 20.0 / 111_320.0;
+        let bearing = 45f64.to_radians();
+        for i in 0..6 {
+            let lat = 48.0 + i as f64 * step * bearing.cos();
+            let lon = 11.0 + i as f64 * step * bearing.sin() / 48f64.to_radians().cos();
+            let _ = gnss.feed_nmea(&gga_at(lat, lon));
+            gnss.calculate_fused_position();
+        }
+
+        let course = gnss.course().unwrap();
+        assert_eq!(course.source, CourseSource::Derived);
+        assert!((course.degrees - 45.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_merged_satellite_single_band() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GAGSV,1,1,04,301,45,123,35,302,30,045,40,303,60,234,45,304,25,156,38*XX");
+        let merged = gnss.merged_satellite("GALILEO", 302).unwrap();
+        assert_eq!(merged.prn, 302);
+        assert_eq!(merged.elevation, Some(30));
+        assert_eq!(merged.azimuth, Some(45));
+        assert_eq!(merged.snr, Some(40));
+        assert!(gnss.merged_satellite("INVALID", 302).is_none());
+    }
+
+    #[test]
+    fn test_merged_satellite_takes_max_snr_across_bands() {
+        let mut gnss = GnssData::new();
+        // PRN 3 on L1 C/A (signal 1) and L5 (signal 8); PRN 4 has no L5 SNR
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,02,03,52,172,36,04,09,020,39,1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,02,03,52,172,44,04,09,020,,8"));
+
+        let merged = gnss.merged_satellite("GPS", 3).unwrap();
+        assert_eq!((merged.prn, merged.elevation, merged.azimuth), (3, Some(52), Some(172)));
+        assert_eq!(merged.snr, Some(44));
+        assert_eq!(merged.signals.len(), 2);
+        // A band without an SNR does not hide the other band's
+        assert_eq!(gnss.merged_satellite("GPS", 4).unwrap().snr, Some(39));
+    }
+
+    #[test]
+    fn test_gns_modes_drive_fusion_eligibility_and_quality() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$BDGSV,1,1,04,201,45,123,35,202,30,045,40,203,60,234,45,204,25,156,38*XX");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,201,202,203,204,205,206,,,,,,,1.5,0.8,1.2*XX");
+
+        // GPS in RTK, BeiDou without a fix
+        let _ = gnss.feed_nmea("$GNGNS,123519.00,4807.038,N,01131.000,E,RNNN,12,0.9,545.4,46.9,,*XX");
+        assert_eq!(gnss.system("GPS").unwrap().fix_mode(), Some('R'));
+        assert_eq!(gnss.system("BEIDOU").unwrap().fix_mode(), Some('N'));
+
+        // A GGA in the same epoch must not erase the GNS modes
+        let _ = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,12,0.9,545.4,M,46.9,M,,*47");
+        assert_eq!(gnss.system("GPS").unwrap().fix_mode(), Some('R'));
+        assert_eq!(gnss.system("BEIDOU").unwrap().fix_mode(), Some('N'));
+
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["GPS".to_string()]);
+        assert_eq!(fused.fix_quality, Some(FixQuality::RtkFixed));
+
+        gnss.calculate_advanced_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["GPS".to_string()]);
+        assert_eq!(fused.fix_quality, Some(FixQuality::RtkFixed));
+    }
+
+    #[test]
+    fn test_gps_only_talker_yields_fused_position() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.system("GPS").unwrap().altitude(), Some(545.4));
+        assert!((gnss.system("GPS").unwrap().latitude().unwrap() - 48.1173).abs() < 1e-6);
+        assert_eq!(gnss.system("GLONASS").unwrap().latitude(), None);
+
+        // The GGA HDOP alone is enough for the advanced fusion
+        gnss.calculate_advanced_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS".to_string()]);
+
+        // A GPGSA goes straight to GPS, whatever its PRNs
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2, 3, 4]);
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), Some(0.9));
+        assert_eq!(gnss.hdop(), gnss.gga_hdop());
+        let _ = gnss.feed_nmea(&with_checksum("GBGSA,A,3,11,12,,,,,,,,,,,1.6,1.0,1.2"));
+        assert_eq!(gnss.system("BEIDOU").unwrap().satellites_used(), vec![11, 12]);
+        assert_eq!(gnss.system("GPS").unwrap().satellites_used(), vec![1, 2, 3, 4]);
+
+        // The GPS position is not broadcast to GLONASS, although it has satellites in view
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123520,A,4807.040,N,01131.000,E,022.4,084.4,230394,003.1,W"));
+        assert!((gnss.system("GPS").unwrap().latitude().unwrap() - 48.117_333).abs() < 1e-6);
+        assert_eq!(gnss.system("GLONASS").unwrap().latitude(), None);
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["GPS".to_string()]);
+        assert!((fused.latitude - 48.117_333).abs() < 1e-6);
+
+        // A GNGGA still applies to every system with satellites
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123521,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.system("GLONASS").unwrap().altitude(), Some(545.4));
+        assert_eq!(gnss.feed_nmea(&with_checksum("GNGLL,4807.038,N,01131.000,E,123521,A,A")), Ok(SentenceType::Gll { system: None }));
+    }
+
+    #[test]
+    fn test_gb_talker_epoch_yields_beidou_position() {
+        let mut gnss = GnssData::new();
+        // NMEA 4.10+ BeiDou PRNs are numbered 1-63 under the GB talker
+        let _ = gnss.feed_nmea(&with_checksum("GBGSV,2,1,05,11,45,123,35,12,30,045,40,14,60,234,45,19,25,156,38,1"));
+        let _ = gnss.feed_nmea(&with_checksum("GBGSV,2,2,05,21,15,300,33,1"));
+        let _ = gnss.feed_nmea(&with_checksum("GBGSA,A,3,11,12,14,19,21,,,,,,,,1.5,0.8,1.2,4"));
+        let _ = gnss.feed_nmea(&with_checksum("GBGGA,123519,4807.038,N,01131.000,E,1,05,0.8,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GBRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W"));
+        let _ = gnss.feed_nmea(&with_checksum("GBGLL,4807.038,N,01131.000,E,123519,A,A"));
+
+        let beidou = gnss.system("BEIDOU").unwrap();
+        assert_eq!(beidou.satellites().len(), 5);
+        assert_eq!(beidou.satellites_used(), vec![11, 12, 14, 19, 21]);
+        assert_eq!(beidou.hdop(), Some(0.8));
+        assert!((beidou.latitude().unwrap() - 48.1173).abs() < 1e-6);
+        assert_eq!(beidou.altitude(), Some(545.4));
+        assert!(gnss.system("GPS").unwrap().satellites_used().is_empty());
+        assert_eq!(gnss.system("GPS").unwrap().latitude(), None);
+        assert_eq!(gnss.speed_knots(), Some(22.4));
+        assert_eq!(gnss.get_stats().unhandled_sentences(), 0);
+
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["BEIDOU".to_string()]);
+        assert!((fused.latitude - 48.1173).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_gns_mixed_modes_without_gga() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*6C");
+        let _ = gnss.feed_nmea(&with_checksum("GAGSV,1,1,04,301,40,083,41,302,17,308,43,303,13,172,42,304,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGNS,123519.00,4807.038,N,01131.000,E,ANAN,12,1.1,545.4,46.9,,"));
+        assert_eq!(gnss.system("GPS").unwrap().fix_mode(), Some('A'));
+        assert_eq!(gnss.system("GLONASS").unwrap().fix_mode(), Some('N'));
+        assert_eq!(gnss.system("GALILEO").unwrap().fix_mode(), Some('A'));
+        assert_eq!(gnss.system("BEIDOU").unwrap().fix_mode(), Some('N'));
+        assert_eq!(gnss.gga_hdop(), Some(1.1));
+        assert_eq!(gnss.altitude(), Some(545.4));
+        assert_eq!(gnss.geoid_separation(), Some(46.9));
+        assert_eq!(gnss.system("GLONASS").unwrap().latitude(), None);
+
+        // The GNS HDOP alone is enough to fuse the systems with a fix
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems, vec!["GPS".to_string(), "GALILEO".to_string()]);
+        assert!((fused.altitude_msl - 545.4).abs() < 1e-9);
+
+        // Without any fix the HDOP placeholder is ignored
+        let _ = gnss.feed_nmea(&with_checksum("GNGNS,123520.00,,,,,NNNN,00,99.9,,,,"));
+        assert_eq!(gnss.gga_hdop(), Some(1.1));
+    }
+
+    #[test]
+    fn test_gns_from_constellation_talker() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39"));
+        assert_eq!(
+            gnss.feed_nmea(&with_checksum("GPGNS,123519.00,4807.038,N,01131.000,E,D,08,0.9,545.4,46.9,,")),
+            Ok(SentenceType::Gns)
+        );
+        let gps = gnss.system("GPS").unwrap();
+        assert_eq!(gps.fix_mode(), Some('D'));
+        assert!((gps.latitude().unwrap() - 48.1173).abs() < 1e-6);
+        assert_eq!(gps.altitude(), Some(545.4));
+        // Only the talker's constellation takes the mode and the position
+        let glonass = gnss.system("GLONASS").unwrap();
+        assert_eq!(glonass.fix_mode(), None);
+        assert_eq!(glonass.latitude(), None);
+        assert_eq!(gnss.get_stats().sentence_count("GNS"), 1);
+    }
+
+    #[test]
+    fn test_gns_modes_reset_at_next_epoch() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("BDGSV,1,1,04,201,45,123,35,202,30,045,40,203,60,234,45,204,25,156,38"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGNS,123519.00,4807.038,N,01131.000,E,ANNN,12,0.9,545.4,46.9,,"));
+        assert_eq!(gnss.system("BEIDOU").unwrap().fix_mode(), Some('N'));
+
+        // A receiver that stops sending GNS must not keep BeiDou excluded
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,12,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.system("GPS").unwrap().fix_mode(), None);
+        assert_eq!(gnss.system("BEIDOU").unwrap().fix_mode(), None);
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS".to_string(), "BEIDOU".to_string()]);
+    }
+
+    #[test]
+    fn test_zero_satellite_gsv_clears_system() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        assert_eq!(gnss.system("GPS").unwrap().satellites().len(), 4);
+
+        let _ = gnss.feed_nmea("$GPGSV,1,1,00,,,,*79");
+        assert!(gnss.system("GPS").unwrap().satellites().is_empty());
+        assert!(!gnss.system("GPS").unwrap().satellites().contains_key(&0));
+
+        // The checksum may directly follow the in-view count
+        let _ = gnss.feed_nmea("$GLGSV,1,1,00*65");
+        assert!(gnss.system("GLONASS").unwrap().satellites().is_empty());
+    }
+
+    #[test]
+    fn test_gsv_sequence_replaces_previous_sequence() {
+        let mut gnss = GnssData::new();
+        for sentence in [
+            "GPGSV,3,1,10,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39",
+            "GPGSV,3,2,10,05,40,083,41,06,17,308,43,07,13,172,42,08,09,020,39",
+            "GPGSV,3,3,10,09,40,083,41,10,17,308,43",
+        ] {
+            let _ = gnss.feed_nmea(&with_checksum(sentence));
+        }
+        assert_eq!(gnss.system("GPS").unwrap().satellites().keys().collect::<Vec<_>>(), (1..=10).collect::<Vec<_>>());
+
+        // Until the last sentence arrives the previous sequence stays visible
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,3,1,09,05,41,084,40,06,18,309,42,11,12,171,41,12,10,021,38"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,3,2,09,14,41,084,40,15,18,309,42,16,12,171,41,17,10,021,38"));
+        assert!(gnss.system("GPS").unwrap().satellites().contains_key(&1));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,3,3,09,18,41,084,40"));
+
+        let gps_info = gnss.system("GPS").unwrap().satellites();
+        assert_eq!(gps_info.keys().collect::<Vec<_>>(), vec![5, 6, 11, 12, 14, 15, 16, 17, 18]);
+        assert_eq!(gps_info.get(&5).and_then(|sat| sat.snr), Some(40));
+
+        // An out-of-order sentence is discarded without pruning
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,2,2,05,20,41,084,40"));
+        assert_eq!(gnss.system("GPS").unwrap().satellites().len(), 9);
+        assert!(!gnss.system("GPS").unwrap().satellites().contains_key(&20));
+    }
+
+    #[test]
+    fn test_gsv_cycle_staged_until_complete() {
+        let mut gnss = GnssData::new();
+        for sentence in [
+            "GLGSV,3,1,10,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39",
+            "GLGSV,3,2,10,69,40,083,41,70,17,308,43,71,13,172,42,72,09,020,39",
+            "GLGSV,3,3,10,73,40,083,41,74,17,308,43",
+        ] {
+            assert!(gnss.system("GLONASS").unwrap().satellites().is_empty());
+            let _ = gnss.feed_nmea(&with_checksum(sentence));
+        }
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites().len(), 10);
+
+        // The next cycle has fewer satellites; those that set are gone once it completes
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,2,1,05,65,41,084,40,66,18,309,42,67,12,171,41,68,10,021,38"));
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites().len(), 10);
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites().get(&65).and_then(|sat| sat.snr), Some(41));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,2,2,05,75,41,084,40"));
+        let glonass = gnss.system("GLONASS").unwrap().satellites();
+        assert_eq!(glonass.keys().collect::<Vec<_>>(), vec![65, 66, 67, 68, 75]);
+        assert_eq!(glonass.get(&65).and_then(|sat| sat.snr), Some(40));
+
+        // A cycle missing its middle sentence is discarded as a whole
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,3,1,09,80,41,084,40,81,18,309,42,82,12,171,41,83,10,021,38"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,3,3,09,88,41,084,40"));
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites().keys().collect::<Vec<_>>(), vec![65, 66, 67, 68, 75]);
+        // The sentences after the gap are ignored until a new cycle starts
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,02,84,41,084,40,85,18,309,42"));
+        assert_eq!(gnss.system("GLONASS").unwrap().satellites().keys().collect::<Vec<_>>(), vec![84, 85]);
+    }
+
+    #[test]
+    fn test_gsv_sequences_per_signal() {
+        let mut gnss = GnssData::new();
+        // NMEA 4.10 reports L1 (signal 1) and L5 (signal 8) in separate sequences
+        for _ in 0..2 {
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,03,01,40,083,41,02,17,308,43,03,13,172,42,1"));
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,02,03,13,172,38,04,09,020,35,8"));
+        }
+        assert_eq!(gnss.system("GPS").unwrap().satellites().keys().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,02,01,40,083,41,02,17,308,43,1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,01,04,09,020,35,8"));
+        assert_eq!(gnss.system("GPS").unwrap().satellites().keys().collect::<Vec<_>>(), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_per_signal_snr() {
+        let mut gnss = GnssData::new();
+        let signal = |sat: &SatelliteInfo, id: char| sat.signals.iter().find(|known| known.signal_id == id).and_then(|known| known.snr);
+        // L1 C/A and L5 cycles of the same PRNs arrive interleaved
+        for sentence in [
+            "GPGSV,2,1,05,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39,1",
+            "GPGSV,2,1,05,01,40,083,45,02,17,308,37,03,13,172,44,04,09,020,30,8",
+            "GPGSV,2,2,05,05,22,140,36,1",
+            "GPGSV,2,2,05,05,22,140,38,8",
+        ] {
+            let _ = gnss.feed_nmea(&with_checksum(sentence));
+        }
+        let gps = gnss.system("GPS").unwrap().satellites();
+        assert_eq!(gps.len(), 5);
+        let sat1 = gps.get(&1).unwrap();
+        assert_eq!(sat1.signals, vec![
+            SignalInfo { signal_id: '1', snr: Some(41) },
+            SignalInfo { signal_id: '8', snr: Some(45) },
+        ]);
+        assert_eq!((sat1.snr, sat1.best_snr()), (Some(45), Some(45)));
+        let sat2 = gps.get(&2).unwrap();
+        assert_eq!((signal(sat2, '1'), signal(sat2, '8'), sat2.best_snr()), (Some(43), Some(37), Some(43)));
+        assert_eq!(gnss.merged_satellite("GPS", 5).unwrap().snr, Some(38));
+
+        // The next L5 cycle loses PRN 5; its L1 signal stays
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,46,02,17,308,37,03,13,172,44,04,09,020,30,8"));
+        let sat5 = gnss.system("GPS").unwrap().satellites().get(&5).cloned().unwrap();
+        assert_eq!(sat5.signals, vec![SignalInfo { signal_id: '1', snr: Some(36) }]);
+        assert_eq!(sat5.snr, Some(36));
+        assert_eq!(gnss.system("GPS").unwrap().satellites().get(&1).unwrap().best_snr(), Some(46));
+
+        // No L5 satellites in view only clears L5
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,00,8"));
+        let gps = gnss.system("GPS").unwrap().satellites();
+        assert_eq!(gps.len(), 5);
+        assert!(gps.values().all(|sat| sat.signals.iter().all(|known| known.signal_id == '1')));
+        assert_eq!(gps.get(&1).unwrap().snr, Some(41));
+
+        // Receivers without signal IDs keep a single SNR
+        let mut legacy = GnssData::new();
+        let _ = legacy.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let sat = legacy.system("GPS").unwrap().satellites().get(&2).unwrap();
+        assert!(sat.signals.is_empty());
+        assert_eq!(sat.best_snr(), Some(43));
+    }
+
+    #[test]
+    fn test_data_warnings_payloads() {
+        let mut gnss = GnssData::new();
+        gnss.set_satellite_capacity(3, 0);
+
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*00");
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,150,,,,,,,,,,1.2,0.9,2.1"));
+
+        let warnings = gnss.take_warnings();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(warnings[0], DataWarning::ChecksumMismatch {
+            sentence_type: "GPGSV".to_string(),
+            expected: "00".to_string(),
+            computed: nmea_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"),
+        });
+        assert_eq!(warnings[1], DataWarning::SatelliteEvicted {
+            system: "GPS".to_string(),
+            prn: 4,
+            snr: Some(39),
+        });
+        assert_eq!(warnings[2], DataWarning::UnattributedPrn {
+            sentence_type: "GSA".to_string(),
+            prn: 150,
+        });
+        assert!(gnss.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_data_warnings_bounded() {
+        let mut gnss = GnssData::new();
+        for _ in 0..(MAX_PENDING_WARNINGS + 10) {
+            let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*00");
+        }
+        assert_eq!(gnss.take_warnings().len(), MAX_PENDING_WARNINGS);
+    }
+
+    #[test]
+    fn test_weighted_system_average_hdop() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,67,68,69,77,78,79,86,87,,,,,1.8,1.1,1.4*3F");
+
+        // GPS: accuracy 0.9 * 2.0 = 1.8 m; GLONASS: accuracy 1.1 * 4.0 = 4.4 m
+        let gps_weight = 1.0 / ((0.9_f64 * 1.8).max(1.8) + 0.1);
+        let glonass_weight = 1.0 / ((1.1_f64 * 4.4).max(4.4) + 0.1);
+        let expected = (0.9 * gps_weight + 1.1 * glonass_weight) / (gps_weight + glonass_weight);
+
+        // Like fusion, the average leaves out systems tracking fewer than four satellites
+        assert_eq!(gnss.weighted_system_average(|sys| sys.hdop()), None);
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        assert_eq!(gnss.weighted_system_average(|sys| sys.hdop()), Some(0.9));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,67,14,186,39,68,49,228,36,69,42,308,30,77,15,064,37"));
+
+        let average = gnss.weighted_system_average(|sys| sys.hdop()).unwrap();
+        assert!((average - expected).abs() < 1e-12);
+
+        // Systems without a value are skipped
+        assert_eq!(gnss.weighted_system_average(|_| None), None);
+    }
+
+    #[test]
+    fn test_every_table_handler_is_reachable() {
+        for descriptor in SENTENCE_TABLE {
+            let talker = "GP";
+            let example = match descriptor.sentence_type {
+                "GGA" => "123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,",
+                "RMC" => "123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W",
+                "VTG" => "084.4,T,,M,022.4,N,041.5,K,A",
+                "GSA" => "A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1",
+                "GNS" => "123519,4807.038,N,01131.000,E,AA,08,0.9,545.4,46.9,,",
+                "GSV" => "1,1,01,01,40,083,41",
+                "GLL" => "4807.038,N,01131.000,E,123519,A",
+                "GST" => "123519,1.2,0.8,0.6,45.0,0.3,0.4,0.9",
+                "GBS" => "123519,0.5,0.4,1.1,,,,",
+                "DTM" => "W84,,0.0,N,0.0,E,0.0,W84",
+                "ZDA" => "201530.00,04,07,2002,00,00",
+                "TXT" => "01,01,02,ANTENNA OK",
+                "HDT" => "274.07,T",
+                "THS" => "274.07,A",
+                other => panic!("no example sentence for {}", other),
+            };
+
+            let mut gnss = GnssData::new();
+            let parsed = gnss.feed_nmea(&with_checksum(&format!("{}{},{}", talker, descriptor.sentence_type, example)));
+            assert!(parsed.is_ok(), "{}: {:?}", descriptor.sentence_type, parsed);
+            assert_eq!(gnss.get_stats().sentence_count(descriptor.sentence_type), 1, "{}", descriptor.sentence_type);
+            assert_eq!(gnss.get_stats().unhandled_sentences(), 0);
+        }
+    }
+
+    #[test]
+    fn test_sentence_counts_follow_table() {
+        let mut gnss = GnssData::new();
+        gnss.register_sentence_handler("ROT", |_, _| {});
+        let _ = gnss.feed_nmea("$HEROT,-0.3,A*05");
+        let _ = gnss.feed_nmea(&with_checksum("GPGNS,123519.00,4807.038,N,01131.000,E,A,08,0.9,545.4,46.9,,"));
+        let _ = gnss.feed_nmea("$GPXYZ,1,2,3*00");
+
+        let counts = gnss.get_stats().sentence_counts();
+        let types: Vec<&str> = counts.iter().map(|(sentence_type, _)| sentence_type.as_str()).collect();
+        let mut expected: Vec<&str> = SENTENCE_TABLE.iter().map(|descriptor| descriptor.sentence_type).collect();
+        expected.push("ROT");
+        assert_eq!(types, expected);
+        assert_eq!(gnss.get_stats().sentence_count("ROT"), 1);
+        // GNS is accepted from constellation talkers as well as the combined one
+        assert_eq!(gnss.get_stats().sentence_count("GNS"), 1);
+        assert_eq!(gnss.get_stats().unhandled_sentences(), 1);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_feed_nmea_unchecked_applies_sentence() {
+        let mut gnss = GnssData::new();
+        // The wrapper drops the error but still applies what can be parsed
+        gnss.feed_nmea_unchecked("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*00");
+        assert_eq!(gnss.altitude(), Some(545.4));
+        assert_eq!(gnss.get_stats().sentence_count("GGA"), 1);
+    }
+
+    #[test]
+    fn test_feed_nmea_identifies_sentences() {
+        let mut gnss = GnssData::new();
+        gnss.register_sentence_handler("ROT", |_, _| {});
+        assert_eq!(gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59"), Ok(SentenceType::Gga));
+        assert_eq!(
+            gnss.feed_nmea(&with_checksum("GLGSV,1,1,01,65,40,083,41")),
+            Ok(SentenceType::Gsv { system: GnssSystem::Glonass })
+        );
+        assert_eq!(gnss.feed_nmea("$HEROT,-0.3,A*05"), Ok(SentenceType::Custom("ROT".to_string())));
+        assert_eq!(gnss.feed_nmea("$GPZDA,201530.00,04,07,2002,00,00*60"), Ok(SentenceType::Zda));
+
+        // Tally sentence rates and failures for a stream
+        let stream = [
+            "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59",
+            "$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*74",
+            "$GNGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59",
+            "",
+        ];
+        let mut parsed: HashMap<SentenceType, usize> = HashMap::new();
+        let mut failures = 0;
+        for sentence in stream {
+            match gnss.feed_nmea(sentence) {
+                Ok(sentence_type) => *parsed.entry(sentence_type).or_default() += 1,
+                Err(_) => failures += 1,
+            }
+        }
+        assert_eq!(parsed.get(&SentenceType::Gga), Some(&1));
+        assert_eq!(parsed.get(&SentenceType::Rmc), Some(&1));
+        // The second GGA carries the first one's checksum
+        assert_eq!(failures, 2);
+    }
+
+    #[test]
+    fn test_fields_expose_unmodeled_gga_fields() {
+        let mut gnss = GnssData::new();
+        let gga = with_checksum("GNGGA,123519,4807.038,N,01131.000,E,2,08,0.9,545.4,M,46.9,M,1.8,0131");
+        let mut seen = Vec::new();
+        let parsed = gnss.feed_nmea_with_fields(&gga, |fields| {
+            assert_eq!(fields.address(), "GNGGA");
+            assert_eq!(fields.len(), 15);
+            // DGPS correction age and station ID are not modeled by GnssData
+            seen.push(fields.get(13).unwrap().parse::<f64>().unwrap());
+            seen.push(fields.get(14).unwrap().parse::<f64>().unwrap());
+            assert_eq!(fields.get(15), None);
+            assert_eq!(fields.iter().last(), Some("0131"));
+        });
+        assert_eq!(parsed, Ok(SentenceType::Gga));
+        assert_eq!(seen, vec![1.8, 131.0]);
+        // The sentence was applied as well
+        assert_eq!(gnss.fix_quality(), Some(2));
+
+        // Unsupported sentences are visible too, rejected ones are not
+        let mut addresses = Vec::new();
+        let _ = gnss.feed_nmea_with_fields(&with_checksum("SDDPT,12.4,0.5"), |fields| addresses.push(fields.address().to_string()));
+        gnss.set_checksum_validation(true);
+        let _ = gnss.feed_nmea_with_fields("$SDDPT,12.4,0.5*00", |fields| addresses.push(fields.address().to_string()));
+        assert_eq!(addresses, vec!["SDDPT"]);
+    }
+
+    #[test]
+    fn test_feed_nmea_reports_errors() {
         let mut gnss = GnssData::new();
-        let gsv = "$GAGSV,1,1,04,301,45,123,35,302,30,045,40,303,60,234,45,304,25,156,38*XX";
-        gnss.feed_nmea(gsv);
-        let galileo_info = &gnss.systems["GALILEO"].satellites_info;
-        assert_eq!(galileo_info.len(), 4);
-        assert!(galileo_info.contains_key(&301));
-        assert!(galileo_info.contains_key(&302));
-        assert!(galileo_info.contains_key(&303));
-        assert!(galileo_info.contains_key(&304));
-        let sat301 = galileo_info.get(&301).unwrap();
-        assert_eq!(sat301.prn, 301);
-        assert_eq!(sat301.elevation, Some(45));
-        assert_eq!(sat301.azimuth, Some(123));
-        assert_eq!(sat301.snr, Some(35));
+
+        assert_eq!(gnss.feed_nmea(""), Err(NmeaError::EmptySentence));
+        assert_eq!(gnss.feed_nmea("$\r\n"), Err(NmeaError::EmptySentence));
+        assert_eq!(
+            gnss.feed_nmea("$GPXYZ,1,2,3*50"),
+            Err(NmeaError::UnknownSentence { address: "GPXYZ".to_string() })
+        );
+
+        // A GNSS sentence from a talker without a constellation, and a per-system one from GN
+        assert_eq!(
+            gnss.feed_nmea(&with_checksum("IIGNS,123519.00,4807.038,N,01131.000,E,A,08,0.9,545.4,46.9,,")),
+            Err(NmeaError::UnsupportedTalker { address: "IIGNS".to_string() })
+        );
+        assert_eq!(
+            gnss.feed_nmea(&with_checksum("GNGSV,1,1,01,01,40,083,41")),
+            Err(NmeaError::UnsupportedTalker { address: "GNGSV".to_string() })
+        );
+
+        // One corrupted character
+        assert_eq!(
+            gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,546.4,M,46.9,M,,*59"),
+            Err(NmeaError::ChecksumMismatch { expected: "59".to_string(), computed: 0x5A })
+        );
+
+        // Truncated after the fix quality
+        assert_eq!(
+            gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1")),
+            Err(NmeaError::MissingFields { expected: 15, found: 7 })
+        );
+
+        // Altitude garbled in transit
+        assert_eq!(
+            gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,54#.4,M,46.9,M,,")),
+            Err(NmeaError::InvalidNumber { index: 9 })
+        );
+        // Empty fields are not invalid numbers
+        assert!(gnss.feed_nmea(&with_checksum("GNGGA,123519,,,,,0,00,,,M,,M,,")).is_ok());
+
+        // A missing checksum is only an error while checksum validation is enabled
+        let unchecked = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,";
+        assert_eq!(gnss.feed_nmea(unchecked), Ok(SentenceType::Gga));
+        gnss.set_checksum_validation(true);
+        assert_eq!(
+            gnss.feed_nmea(unchecked),
+            Err(NmeaError::ChecksumMismatch { expected: String::new(), computed: 0x59 })
+        );
+    }
+
+    #[test]
+    fn test_gsv_cycle_age() {
+        let mut gnss = GnssData::new();
+        // First sentence of a two-sentence cycle does not complete it
+        let _ = gnss.feed_nmea("$GLGSV,2,1,08,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*00");
+        assert_eq!(gnss.gsv_cycle_age("GLONASS", gnss.now()), None);
+        let _ = gnss.feed_nmea("$GLGSV,2,2,08,69,40,083,41,70,17,308,43,71,13,172,42,72,09,020,39*00");
+
+        let start = gnss.now();
+        let age = gnss.gsv_cycle_age("GLONASS", start).unwrap();
+        let later = gnss.gsv_cycle_age("GLONASS", start + Duration::from_secs(5)).unwrap();
+        let much_later = gnss.gsv_cycle_age("GLONASS", start + Duration::from_secs(30)).unwrap();
+        assert!(later >= age + Duration::from_secs(5));
+        assert!(much_later >= later + Duration::from_secs(25));
+
+        assert_eq!(gnss.gsv_cycle_age("GPS", start), None);
+        assert_eq!(gnss.gsv_cycle_age("QZSS", start), None);
+    }
+
+    #[test]
+    fn test_lock_duration_resets_after_dropout() {
+        let with_prn_1 = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C";
+        let without_prn_1 = with_checksum("GPGSV,1,1,03,02,17,308,43,03,13,172,42,04,09,020,39");
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(with_prn_1);
+        assert_eq!(gnss.lock_duration("GPS", 1, gnss.now()), None);
+
+        gnss.set_satellite_history(true);
+        let _ = gnss.feed_nmea(with_prn_1);
+        let _ = gnss.feed_nmea(with_prn_1);
+        let later = gnss.now() + Duration::from_secs(10);
+        let locked = gnss.lock_duration("GPS", 1, later).unwrap();
+        assert!(locked >= Duration::from_secs(10));
+
+        // A cycle without the PRN ends its run
+        let _ = gnss.feed_nmea(&without_prn_1);
+        assert_eq!(gnss.lock_duration("GPS", 1, later), None);
+        assert!(gnss.lock_duration("GPS", 2, later).unwrap() >= locked);
+
+        // Reacquiring starts a new, shorter run
+        std::thread::sleep(Duration::from_millis(5));
+        let _ = gnss.feed_nmea(with_prn_1);
+        let relocked = gnss.lock_duration("GPS", 1, later).unwrap();
+        assert!(relocked < locked);
+        assert!(gnss.lock_duration("GPS", 2, later).unwrap() > relocked);
+    }
+
+    #[test]
+    fn test_snapshot_diff_reports_changed_fields_only() {
+        let previous = GnssSnapshot {
+            time: Some("123519".to_string()),
+            latitude: Some(48.1173),
+            longitude: Some(11.5166667),
+            altitude: Some(545.4),
+            num_satellites: Some(8),
+            pdop: Some(1.2),
+            hdop: Some(0.9),
+            vdop: Some(2.1),
+            fix_quality: Some(FixQuality::Gps),
+        };
+
+        // Jitter within the tolerances and a new epoch time are not changes
+        let jitter = GnssSnapshot {
+            time: Some("123520".to_string()),
+            latitude: Some(48.1173 + 1e-6),
+            altitude: Some(545.6),
+            hdop: Some(0.95),
+            ..previous.clone()
+        };
+        assert!(jitter.diff(&previous).is_empty());
+
+        let current = GnssSnapshot {
+            latitude: Some(48.1183),
+            num_satellites: Some(10),
+            hdop: Some(0.7),
+            fix_quality: Some(FixQuality::Dgps),
+            ..previous.clone()
+        };
+        let diff = current.diff(&previous);
+        assert_eq!(
+            diff.changes,
+            vec![
+                FieldChange::Position { old: Some((48.1173, 11.5166667)), new: Some((48.1183, 11.5166667)) },
+                FieldChange::NumSatellites { old: Some(8), new: Some(10) },
+                FieldChange::Hdop { old: Some(0.9), new: Some(0.7) },
+                FieldChange::FixQuality { old: Some(FixQuality::Gps), new: Some(FixQuality::Dgps) },
+            ]
+        );
+
+        // A tighter tolerance picks up the altitude jitter, and losing a value is a change
+        let tight = DiffTolerance { altitude_m: 0.1, ..Default::default() };
+        assert_eq!(
+            jitter.diff_with(&previous, &tight).changes,
+            vec![FieldChange::Altitude { old: Some(545.4), new: Some(545.6) }]
+        );
+        let lost = GnssSnapshot { vdop: None, ..previous.clone() };
+        assert_eq!(lost.diff(&previous).changes, vec![FieldChange::Vdop { old: Some(2.1), new: None }]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_diff_is_serializable() {
+        fn assert_serde<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+        assert_serde::<GnssSnapshot>();
+        assert_serde::<SnapshotDiff>();
+        assert_serde::<EpochData>();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_gnss_data_json_round_trip() {
+        let mut gnss = GnssData::new();
+        gnss.set_late_tolerance(Duration::from_secs(1));
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,04,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W"));
+        gnss.calculate_fused_position();
+
+        let json = serde_json::to_string(&gnss).unwrap();
+        assert!(json.find("\"GPS\"").unwrap() < json.find("\"NAVIC\"").unwrap());
+        let restored: GnssData = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.time(), gnss.time());
+        assert_eq!((restored.latitude(), restored.longitude()), (gnss.latitude(), gnss.longitude()));
+        assert_eq!(restored.rmc_status(), Some('A'));
+        let (fused, restored_fused) = (gnss.fused_position().unwrap(), restored.fused_position().unwrap());
+        assert_eq!((restored_fused.latitude, restored_fused.longitude), (fused.latitude, fused.longitude));
+        assert_eq!(restored_fused.contributing_systems, fused.contributing_systems);
+        assert_eq!(restored.systems().count(), GnssSystem::ALL.len());
+        let (gps, restored_gps) = (gnss.system("GPS").unwrap(), restored.system("GPS").unwrap());
+        assert_eq!(restored_gps.satellites_used(), gps.satellites_used());
+        assert_eq!(restored_gps.satellites().values().collect::<Vec<_>>(), gps.satellites().values().collect::<Vec<_>>());
+        assert_eq!((restored_gps.hdop(), restored_gps.accuracy()), (gps.hdop(), gps.accuracy()));
+        // Configuration is not part of the serialized form
+        assert_eq!(restored.get_late_tolerance(), DEFAULT_LATE_TOLERANCE);
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+
+        let unknown = json.replace("\"NAVIC\"", "\"COMPASS\"");
+        assert!(serde_json::from_str::<GnssData>(&unknown).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_fix_without_altitude_json_round_trip() {
+        let mut gnss = GnssData::new();
+        gnss.set_epoch_recording(4);
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,2,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,04,0.9,,M,,M,,"));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!(fused.altitude_msl.is_nan() && fused.altitude_accuracy.is_infinite());
+        let before = gnss.snapshot();
+        assert_eq!(before.altitude, None);
+
+        let json = serde_json::to_string(&fused).unwrap();
+        assert!(json.contains("\"altitude_msl\":null") && json.contains("\"altitude_accuracy\":null"));
+        let restored: FusedPosition = serde_json::from_str(&json).unwrap();
+        assert!(restored.altitude_msl.is_nan());
+        assert_eq!(restored.altitude_accuracy, f64::INFINITY);
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+
+        // Epoch records carrying the fused position round-trip too
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,04,0.9,,M,,M,,"));
+        let epochs = gnss.take_epochs();
+        let json = serde_json::to_string(&epochs[0]).unwrap();
+        let restored: EpochData = serde_json::from_str(&json).unwrap();
+        assert!(restored.fused_position.unwrap().altitude_msl.is_nan());
+
+        // Losing the altitude is a change
+        let mut with_altitude = before.clone();
+        with_altitude.altitude = Some(545.4);
+        assert!(before.diff(&with_altitude).changes.iter()
+            .any(|change| matches!(change, FieldChange::Altitude { new: None, .. })));
+    }
+
+    #[test]
+    fn test_dead_reckoning_exclusion() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,6,04,0.9,545.4,M,46.9,M,,"));
+        assert!(gnss.is_dead_reckoned());
+
+        // Included by default, flagged through the fix quality
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some());
+
+        gnss.set_exclude_dead_reckoning(true);
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_none());
+        gnss.calculate_advanced_fused_position();
+        assert!(gnss.fused_position().is_none());
+
+        // A real GNSS fix is fused again
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,04,0.9,545.4,M,46.9,M,,"));
+        assert!(!gnss.is_dead_reckoned());
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some());
+    }
+
+    #[test]
+    fn test_rmc_magnetic_variation_and_mode() {
+        let mut gnss = GnssData::new();
+        assert_eq!(gnss.true_to_magnetic(90.0), None);
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,012.5,W,A"));
+        assert_eq!((gnss.magnetic_variation(), gnss.rmc_mode()), (Some(-12.5), Some('A')));
+        // A westward variation makes the magnetic heading larger than the true one
+        assert!((gnss.true_to_magnetic(84.4).unwrap() - 96.9).abs() < 1e-9);
+        assert!((gnss.true_to_magnetic(350.0).unwrap() - 2.5).abs() < 1e-9);
+
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123520,A,4807.038,N,01131.000,E,022.4,084.4,230394,004.0,E,D"));
+        assert!((gnss.true_to_magnetic(2.0).unwrap() - 358.0).abs() < 1e-9);
+        let _ = gnss.feed_nmea(&with_checksum("GPRMC,123521,A,4807.038,N,01131.000,E,022.4,084.4,230394,,,A"));
+        assert_eq!(gnss.magnetic_variation(), None);
+    }
+
+    #[test]
+    fn test_rmc_nmea41_navigational_status() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123519.00,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,A,S"));
+        assert_eq!((gnss.rmc_mode(), gnss.nav_status()), (Some('A'), Some('S')));
+        assert!(gnss.position_valid() && !gnss.is_dead_reckoned());
+
+        // An estimated fix is kept and fused under the default policy, but is not a valid fix
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123520.00,A,4807.100,N,01131.000,E,022.4,084.4,230394,003.1,W,E,C"));
+        assert_eq!((gnss.rmc_mode(), gnss.nav_status()), (Some('E'), Some('C')));
+        assert!((gnss.latitude().unwrap() - 48.11833).abs() < 1e-4);
+        assert!(gnss.is_dead_reckoned() && !gnss.position_valid() && gnss.has_fix());
+        gnss.set_dead_reckoning_policy(DeadReckoningPolicy::Exclude);
+        assert!(!gnss.has_fix());
+
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123521.00,A,4807.100,N,01131.000,E,022.4,084.4,230394,003.1,W,A,V"));
+        assert_eq!(gnss.nav_status(), Some('V'));
+        assert!(!gnss.position_valid());
+
+        // Pre-4.1 sentences carry no navigational status
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123522.00,A,4807.100,N,01131.000,E,022.4,084.4,230394,003.1,W,A"));
+        assert_eq!(gnss.nav_status(), None);
+        assert!(gnss.position_valid());
+    }
+
+    #[test]
+    fn test_rmc_no_fix_mode_is_not_fused() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        // Status 'A' but mode 'N': the receiver has no fix
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,N"));
+        assert_eq!(gnss.rmc_mode(), Some('N'));
+        assert_eq!(gnss.system("GPS").unwrap().latitude(), None);
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_none());
+
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123520,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,A"));
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some());
+    }
+
+    #[test]
+    fn test_gbs_fault_reduces_system_weight() {
+        let fused_for = |gbs: &str, policy: FaultPolicy| {
+            let mut gnss = GnssData::new();
+            gnss.set_fault_policy(policy);
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+            let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,67,14,186,29,68,49,228,26,69,42,308,30,77,15,064,27"));
+            let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+            let _ = gnss.feed_nmea(&with_checksum("GLGSA,A,3,67,68,69,77,,,,,,,,,1.2,0.9,2.1"));
+            let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.000,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+            let _ = gnss.feed_nmea(&with_checksum("GLGGA,123519,4807.010,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+            let _ = gnss.feed_nmea(&with_checksum(gbs));
+            gnss.calculate_fused_position();
+            (gnss.fused_position().cloned().unwrap(), gnss.integrity().cloned().unwrap())
+        };
+        let healthy = "GNGBS,123519.00,2.5,1.8,6.0,,,,";
+        let gps_fault = "GNGBS,123519.00,2.5,1.8,6.0,02,0.001,45.2,3.1";
+
+        let (baseline, integrity) = fused_for(healthy, FaultPolicy::Inflate(10.0));
+        assert_eq!((integrity.failed_prn, integrity.failed_system), (None, None));
+        assert_eq!(fused_for(gps_fault, FaultPolicy::Include).0.latitude, baseline.latitude);
+
+        // With GPS inflated, the fused latitude moves toward the GLONASS fix
+        let (inflated, integrity) = fused_for(gps_fault, FaultPolicy::Inflate(10.0));
+        assert_eq!((integrity.failed_prn, integrity.failed_system), (Some(2), Some(GnssSystem::Gps)));
+        assert_eq!((integrity.bias, integrity.bias_std), (Some(45.2), Some(3.1)));
+        assert!(inflated.latitude > baseline.latitude);
+        assert_eq!(inflated.contributing_systems, ["GPS", "GLONASS"]);
+
+        let (excluded, _) = fused_for(gps_fault, FaultPolicy::Exclude);
+        assert_eq!(excluded.contributing_systems, ["GLONASS"]);
+
+        // The NMEA 4.10 system ID overrides the PRN range
+        let (_, integrity) = fused_for("GNGBS,123519.00,2.5,1.8,6.0,02,0.001,45.2,3.1,2,1", FaultPolicy::Include);
+        assert_eq!(integrity.failed_system, Some(GnssSystem::Glonass));
+    }
+
+    #[test]
+    fn test_dtm_datum_conversion_and_policy() {
+        let fused_for = |dtm: &str, policy: DatumPolicy| {
+            let mut gnss = GnssData::new();
+            gnss.set_datum_policy(policy);
+            let _ = gnss.feed_nmea(&with_checksum(dtm));
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+            let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+            let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+            gnss.calculate_fused_position();
+            gnss
+        };
+
+        let mut gnss = fused_for("GPDTM,W72,,0.012,N,0.006,W,4.5,W84", DatumPolicy::Convert);
+        assert!(!gnss.is_wgs84());
+        let datum = gnss.system("GPS").unwrap().datum().unwrap();
+        assert!((datum.latitude_offset.unwrap() - 0.0002).abs() < 1e-12);
+        assert!((datum.longitude_offset.unwrap() + 0.0001).abs() < 1e-12);
+        assert_eq!(gnss.take_warnings(), vec![DataWarning::NonWgs84Datum { datum: "W72".to_string() }]);
+
+        // The reported position stays as sent; converted positions remove the offsets
+        let reported = gnss.latitude().unwrap();
+        let (latitude, longitude, altitude) = gnss.wgs84_position().unwrap();
+        assert!((reported - latitude - 0.0002).abs() < 1e-9);
+        assert!((longitude - 11.5166667 - 0.0001).abs() < 1e-6);
+        assert!((altitude.unwrap() - 540.9).abs() < 1e-9);
+        let fused = gnss.fused_position().unwrap();
+        assert!((fused.latitude - latitude).abs() < 1e-9);
+
+        // Repeating the datum does not warn again; WGS84 does not warn at all
+        let _ = gnss.feed_nmea(&with_checksum("GPDTM,W72,,0.012,N,0.006,W,4.5,W84"));
+        let _ = gnss.feed_nmea(&with_checksum("GNDTM,W84,,0.0,N,0.0,E,0.0,W84"));
+        assert!(gnss.take_warnings().is_empty());
+        assert!(gnss.is_wgs84() && gnss.system("GLONASS").unwrap().datum().unwrap().is_wgs84());
+
+        assert!(fused_for("GPDTM,W72,,0.012,N,0.006,W,4.5,W84", DatumPolicy::Exclude).fused_position().is_none());
+        // A user-defined datum without offsets cannot be converted
+        let gnss = fused_for("GPDTM,999,,,,,,,W84", DatumPolicy::Convert);
+        assert!(gnss.wgs84_position().is_none() && gnss.fused_position().is_none());
+    }
+
+    #[test]
+    fn test_dead_reckoning_policy_inflates_rmc_estimate() {
+        let fused_accuracy = |mode: char, policy: DeadReckoningPolicy| {
+            let mut gnss = GnssData::new();
+            gnss.set_dead_reckoning_policy(policy);
+            let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+            let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,{}", mode)));
+            gnss.calculate_fused_position();
+            gnss.fused_position().map(|fused| fused.estimated_accuracy)
+        };
+        let fixed = fused_accuracy('A', DeadReckoningPolicy::Inflate(5.0)).unwrap();
+        assert_eq!(fused_accuracy('E', DeadReckoningPolicy::Include), Some(fixed));
+        assert!((fused_accuracy('E', DeadReckoningPolicy::Inflate(5.0)).unwrap() - fixed * 5.0).abs() < 1e-9);
+        assert_eq!(fused_accuracy('E', DeadReckoningPolicy::Exclude), None);
+
+        let mut gnss = GnssData::new();
+        gnss.set_dead_reckoning_policy(DeadReckoningPolicy::Inflate(0.5));
+        assert_eq!(gnss.get_dead_reckoning_policy(), DeadReckoningPolicy::Inflate(1.0));
+        gnss.set_exclude_dead_reckoning(true);
+        assert_eq!(gnss.get_dead_reckoning_policy(), DeadReckoningPolicy::Exclude);
+        gnss.reset();
+        assert!(gnss.get_exclude_dead_reckoning());
+    }
+
+    #[test]
+    fn test_timing_offset_smoothing() {
+        // 23 March 1994 12:35:19 UTC
+        let base_epoch = UNIX_EPOCH + Duration::from_secs(764_426_119);
+        let mut gnss = GnssData::new();
+
+        // No date yet: GGA alone cannot be measured
+        let _ = gnss.feed_nmea_at("$GNGGA,123518,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*46", base_epoch);
+        assert!(gnss.timing_offset().is_none());
+
+        // Constant 250 ms offset with +/-10 ms of alternating noise
+        for second in 0..200u64 {
+            let of_day = 12 * 3600 + 35 * 60 + 19 + second;
+            let time = format!("{:02}{:02}{:02}", of_day / 3600, of_day / 60 % 60, of_day % 60);
+            let noise_ms = if second % 2 == 0 { 10 } else { -10 };
+            let epoch = base_epoch + Duration::from_secs(second);
+            let received_at = epoch + Duration::from_millis((250 + noise_ms) as u64);
+            let rmc = with_checksum(&format!("GNRMC,{},A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W", time));
+            let _ = gnss.feed_nmea_at(&rmc, received_at);
+            // Later sentences of the same epoch are not measured again
+            let gga = with_checksum(&format!("GNGGA,{},4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,", time));
+            let _ = gnss.feed_nmea_at(&gga, received_at + Duration::from_millis(100));
+        }
+
+        let timing = gnss.timing_offset().unwrap();
+        assert_eq!(timing.samples, 200);
+        assert!((timing.offset - 0.25).abs() < 0.002, "offset {}", timing.offset);
+        assert!((timing.jitter - 0.01).abs() < 0.002, "jitter {}", timing.jitter);
+    }
+
+    #[test]
+    fn test_nmea_datetime_to_unix() {
+        assert_eq!(nmea_datetime_to_unix("010100", "000000"), Some(946_684_800.0));
+        assert_eq!(nmea_datetime_to_unix("290224", "120000.50"), Some(1_709_208_000.5));
+        assert_eq!(nmea_datetime_to_unix("230394", "123519"), Some(764_426_119.0));
+        assert_eq!(nmea_datetime_to_unix("320194", "123519"), None);
+        assert_eq!(nmea_datetime_to_unix("", "123519"), None);
+    }
+
+    #[test]
+    fn test_epoch_recording() {
+        let mut gnss = GnssData::new();
+        gnss.set_epoch_recording(2);
+        let gga = |time: &str| with_checksum(&format!("GNGGA,{},4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,", time));
+
+        // Epoch 1: fused
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&gga("123519"));
+        gnss.calculate_fused_position();
+        // Epoch 2: not fused
+        let _ = gnss.feed_nmea(&gga("123520"));
+        assert_eq!(gnss.take_epochs().len(), 1);
+        let _ = gnss.feed_nmea(&gga("123521"));
+
+        let epochs = gnss.take_epochs();
+        assert_eq!(epochs.len(), 1);
+        let epoch = &epochs[0];
+        assert_eq!(epoch.time.as_deref(), Some("123520"));
+        assert_eq!(epoch.fix_quality, Some(FixQuality::Gps));
+        assert_eq!(epoch.altitude, Some(545.4));
+        assert!(epoch.warnings.is_empty());
+        assert!(epoch.fused_position.is_none());
+        let gps = &epoch.systems[0];
+        assert_eq!(gps.system, "GPS");
+        assert_eq!(gps.hdop, Some(0.9));
+        assert_eq!(gps.satellites_used, vec![1, 2, 3, 4]);
+        assert_eq!(gps.satellites.as_ref().unwrap().len(), 4);
+        assert_eq!(epoch.systems.len(), GnssSystem::ALL.len());
+
+        // Only the newest records are kept, and satellites may be elided
+        gnss.set_epoch_satellite_elision(true);
+        for time in ["123522", "123523", "123524"] {
+            let _ = gnss.feed_nmea(&gga(time));
+        }
+        let epochs = gnss.take_epochs();
+        let times: Vec<_> = epochs.iter().map(|epoch| epoch.time.as_deref().unwrap()).collect();
+        assert_eq!(times, vec!["123522", "123523"]);
+        assert!(epochs[0].systems[0].satellites.is_none());
+    }
+
+    #[test]
+    fn test_epoch_recording_keeps_warnings_and_fused_position() {
+        let mut gnss = GnssData::new();
+        gnss.set_epoch_recording(4);
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,150,,,,,,,,1.2,0.9,2.1"));
+        gnss.calculate_fused_position();
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+
+        let epochs = gnss.take_epochs();
+        assert_eq!(epochs.len(), 1);
+        assert_eq!(epochs[0].warnings, vec![DataWarning::UnattributedPrn { sentence_type: "GSA".to_string(), prn: 150 }]);
+        let fused = epochs[0].fused_position.as_ref().unwrap();
+        assert_eq!(fused.time.as_deref(), Some("123519"));
+        // The warning queue is unaffected by recording
+        assert_eq!(gnss.take_warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_parsed_time() {
+        let mut gnss = GnssData::new();
+        // Times are fed out of order
+        gnss.set_late_tolerance(Duration::MAX);
+        assert_eq!(gnss.parsed_time(), None);
+        let time_of = |gnss: &mut GnssData, time: &str| {
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GNGGA,{},4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,", time)));
+            gnss.parsed_time()
+        };
+        let utc = |hour, minute, second, millis| Some(UtcTime { hour, minute, second, millis });
+
+        assert_eq!(time_of(&mut gnss, "123519"), utc(12, 35, 19, 0));
+        assert_eq!(time_of(&mut gnss, "123519.50"), utc(12, 35, 19, 500));
+        assert_eq!(time_of(&mut gnss, "000501.5"), utc(0, 5, 1, 500));
+        assert_eq!(time_of(&mut gnss, "235959.1239"), utc(23, 59, 59, 123));
+        assert_eq!(time_of(&mut gnss, "090807.007").unwrap().to_string(), "09:08:07.007");
+        assert_eq!(time_of(&mut gnss, ""), None);
+        assert_eq!(time_of(&mut gnss, "12351"), None);
+        assert_eq!(time_of(&mut gnss, "246000"), None);
+    }
+
+    #[test]
+    fn test_civil_days_round_trip() {
+        for days in [-719_468, -1, 0, 59, 10_956, 11_016, 19_782, 2_932_896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, i64::from(month), i64::from(day)), days);
+        }
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+
+    #[test]
+    fn test_utc_datetime_from_zda_and_rmc() {
+        let mut gnss = GnssData::new();
+        assert_eq!(gnss.utc_datetime(), None);
+
+        // ZDA alone provides date, time and zone
+        let _ = gnss.feed_nmea(&with_checksum("GNZDA,201530.25,04,07,2002,-05,30"));
+        let expected = UtcDateTime { year: 2002, month: 7, day: 4, hour: 20, minute: 15, second: 30, millis: 250 };
+        assert_eq!(gnss.utc_datetime(), Some(expected));
+        assert_eq!(gnss.local_zone_offset(), Some(-330));
+        assert_eq!(gnss.date(), Some("040702"));
+
+        // Fractional seconds of the epoch time, truncated to milliseconds
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,201531.5678,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let datetime = gnss.utc_datetime().unwrap();
+        assert_eq!((datetime.second, datetime.millis), (31, 567));
+        assert_eq!(datetime.unix_millis(), 1_025_813_731_567);
+
+        // Two-digit RMC years pivot at 80
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,201532,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W"));
+        assert_eq!(gnss.utc_datetime().unwrap().to_string(), "1994-03-23T20:15:32.000Z");
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,201533,A,4807.038,N,01131.000,E,022.4,084.4,230324,003.1,W"));
+        assert_eq!(gnss.utc_datetime().unwrap().year, 2024);
+    }
+
+    #[test]
+    fn test_utc_datetime_midnight_rollover() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,235959.00,A,4807.038,N,01131.000,E,022.4,084.4,311224,003.1,W"));
+        assert_eq!(gnss.utc_datetime().unwrap().to_string(), "2024-12-31T23:59:59.000Z");
+
+        // The GGA of the new day arrives before the RMC carrying the new date
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,000000.00,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.utc_datetime().unwrap().to_string(), "2025-01-01T00:00:00.000Z");
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,000000.00,A,4807.038,N,01131.000,E,022.4,084.4,010125,003.1,W"));
+        assert_eq!(gnss.utc_datetime().unwrap().to_string(), "2025-01-01T00:00:00.000Z");
+
+        // Leap day via ZDA
+        let _ = gnss.feed_nmea(&with_checksum("GPZDA,235959.90,28,02,2024,00,00"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,000000.90,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.utc_datetime().unwrap().to_string(), "2024-02-29T00:00:00.900Z");
+    }
+
+    #[test]
+    fn test_txt_messages_capped_and_cleared() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GNTXT,01,01,01,ANTENNA SHORT"));
+        let _ = gnss.feed_nmea(&with_checksum("GPTXT,01,01,02,ROM CORE 1.00 (59842), HW 00070000"));
+        let expected = [
+            TxtMessage { severity: 1, text: "ANTENNA SHORT".to_string() },
+            TxtMessage { severity: 2, text: "ROM CORE 1.00 (59842), HW 00070000".to_string() },
+        ];
+        assert_eq!(gnss.text_messages(), &expected);
+
+        // A missing severity is not a message
+        let _ = gnss.feed_nmea(&with_checksum("GNTXT,01,01,,ANTENNA OPEN"));
+        assert_eq!(gnss.text_messages().len(), 2);
+
+        for index in 0..MAX_TEXT_MESSAGES {
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GNTXT,01,01,02,MESSAGE {}", index)));
+        }
+        assert_eq!(gnss.text_messages().len(), MAX_TEXT_MESSAGES);
+        assert_eq!(gnss.text_messages()[0].text, "MESSAGE 0");
+
+        gnss.clear_text_messages();
+        assert!(gnss.text_messages().is_empty());
+    }
+
+    #[test]
+    fn test_heading_from_hdt_and_ths() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPHDT,274.07,T"));
+        assert_eq!((gnss.heading_true(), gnss.heading_mode()), (Some(274.07), None));
+
+        let _ = gnss.feed_nmea(&with_checksum("GNTHS,275.50,A"));
+        assert_eq!((gnss.heading_true(), gnss.heading_mode()), (Some(275.5), Some('A')));
+
+        // An invalid THS keeps the last valid heading
+        let _ = gnss.feed_nmea(&with_checksum("GNTHS,12.00,V"));
+        assert_eq!((gnss.heading_true(), gnss.heading_mode()), (Some(275.5), Some('A')));
+
+        // The fused position carries the heading
+        feed_two_system_epoch(&mut gnss, (48.1173, 11.5167), (48.1173, 11.5167));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().heading, Some(275.5));
+
+        // An empty HDT heading means the receiver has no heading solution
+        let _ = gnss.feed_nmea(&with_checksum("GPHDT,,T"));
+        assert_eq!(gnss.heading_true(), None);
+    }
+
+    #[test]
+    fn test_txt_multi_part_assembly_and_antenna_status() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPTXT,02,01,02,ROM CORE 3.01 (107888), "));
+        assert!(gnss.text_messages().is_empty());
+        let _ = gnss.feed_nmea(&with_checksum("GPTXT,02,02,02,FWVER=SPG 3.01"));
+        let message = gnss.recent_messages().next().unwrap();
+        assert_eq!(message.text, "ROM CORE 3.01 (107888), FWVER=SPG 3.01");
+        assert_eq!(message.level(), TxtSeverity::Notice);
+
+        // A part out of sequence discards the partial message
+        let _ = gnss.feed_nmea(&with_checksum("GPTXT,03,01,02,PART ONE"));
+        let _ = gnss.feed_nmea(&with_checksum("GPTXT,03,03,02,PART THREE"));
+        let _ = gnss.feed_nmea(&with_checksum("GPTXT,03,02,02,PART TWO"));
+        assert_eq!(gnss.text_messages().len(), 1);
+
+        assert_eq!(gnss.antenna_status(), None);
+        let _ = gnss.feed_nmea(&with_checksum("GNTXT,01,01,01,ANTSTATUS=SHORT"));
+        assert_eq!(gnss.antenna_status(), Some(AntennaStatus::Short));
+        let _ = gnss.feed_nmea(&with_checksum("GNTXT,01,01,02,ANTENNA OK"));
+        gnss.clear_text_messages();
+        assert_eq!(gnss.antenna_status(), Some(AntennaStatus::Ok));
+        assert_eq!(AntennaStatus::from_text("ANTENNA SHORTED"), Some(AntennaStatus::Short));
+        // "OK" only counts as a whole word
+        assert_eq!(AntennaStatus::from_text("ANTENNA BROKEN"), None);
+        assert_eq!(AntennaStatus::from_text("ANTSTATUS=OK"), Some(AntennaStatus::Ok));
+        let levels: Vec<TxtSeverity> = [0, 7, 9]
+            .map(|severity| TxtMessage { severity, text: String::new() }.level())
+            .to_vec();
+        assert_eq!(levels, [TxtSeverity::Error, TxtSeverity::User, TxtSeverity::Other(9)]);
+    }
+
+    #[test]
+    fn test_status_code_precedence() {
+        let mut gnss = GnssData::new();
+        assert_eq!(gnss.status_code(), GnssStatus::NoData);
+
+        // Satellite detail without a fix
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        assert_eq!(gnss.status_code(), GnssStatus::Searching);
+
+        let cases = [
+            ("0", "08", GnssStatus::Searching),
+            ("1", "03", GnssStatus::Fix2d),
+            ("1", "08", GnssStatus::Fix3d),
+            ("2", "08", GnssStatus::Dgps),
+            ("9", "08", GnssStatus::Dgps),
+            ("4", "08", GnssStatus::Rtk),
+            ("5", "03", GnssStatus::Rtk),
+            ("6", "08", GnssStatus::Searching),
+        ];
+        for (quality, satellites, expected) in cases {
+            let gga = format!("GNGGA,123519,4807.038,N,01131.000,E,{},{},0.9,545.4,M,46.9,M,,", quality, satellites);
+            let _ = gnss.feed_nmea(&with_checksum(&gga));
+            assert_eq!(gnss.status_code(), expected, "quality {} with {} satellites", quality, satellites);
+        }
+
+        // A fused position counts as 3D even with few satellites in the GGA
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,03,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some());
+        assert_eq!(gnss.status_code(), GnssStatus::Fix3d);
+    }
+
+    #[test]
+    fn test_time_to_first_fix_classification() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+        let gsa = "$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39";
+        let gga = &with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,04,0.9,545.4,M,46.9,M,,");
+
+        // Slow acquisition: satellites trickle in over 40 seconds
+        let mut gnss = GnssData::new();
+        gnss.reset_at(t0);
+        let _ = gnss.feed_nmea_at("$GPGSV,1,1,01,01,40,083,21", at(10));
+        let _ = gnss.feed_nmea_at("$GPGSV,1,1,02,01,40,083,25,02,17,308,22", at(25));
+        let _ = gnss.feed_nmea_at("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39", at(40));
+        let _ = gnss.feed_nmea_at(gsa, at(50));
+        let _ = gnss.feed_nmea_at(gga, at(50));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.time_to_first_fix(), Some(Duration::from_secs(50)));
+        assert_eq!(gnss.start_type(), Some(StartType::Cold));
+        assert_eq!(gnss.get_stats().time_to_first_fix(), Some(Duration::from_secs(50)));
+
+        // Later fixes do not change the first-fix record
+        let _ = gnss.feed_nmea_at(gga, at(60));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.time_to_first_fix(), Some(Duration::from_secs(50)));
+
+        // Instant acquisition after a reset
+        gnss.reset_at(at(100));
+        assert_eq!(gnss.time_to_first_fix(), None);
+        let _ = gnss.feed_nmea_at("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39", at(101));
+        let _ = gnss.feed_nmea_at(gsa, at(102));
+        let _ = gnss.feed_nmea_at(gga, at(102));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.time_to_first_fix(), Some(Duration::from_secs(2)));
+        assert_eq!(gnss.start_type(), Some(StartType::Hot));
+    }
+
+    #[test]
+    fn test_comma_decimal_stream() {
+        let mut gnss = GnssData::new();
+        assert_eq!(gnss.get_decimal_separator(), '.');
+        gnss.set_decimal_separator(',');
+
+        let _ = gnss.feed_nmea("$GNRMC;123519,50;A;4807,038;N;01131,000;W;022,4;084,4;230394;003,1;W");
+        assert_eq!(gnss.time(), Some("123519.50"));
+        assert!((gnss.latitude().unwrap() - 48.1173).abs() < 1e-6);
+        assert!((gnss.longitude().unwrap() + 11.516_666_7).abs() < 1e-6);
+        assert_eq!(gnss.speed_knots(), Some(22.4));
+        assert_eq!(gnss.track_angle(), Some(84.4));
+
+        let _ = gnss.feed_nmea("$GNGSA;A;3;01;02;03;04;;;;;;;;;1,2;0,9;2,1");
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), Some(0.9));
+        assert_eq!(gnss.system("GPS").unwrap().vdop(), Some(2.1));
+
+        // Default stays period-only
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GNGGA;123519,00;4807,038;N;01131,000;E;1;08;0,9;545,4;M;46,9;M;;");
+        assert_eq!(gnss.altitude(), None);
+    }
+
+    #[test]
+    fn test_comma_decimals_in_comma_delimited_sentence() {
+        let mut gnss = GnssData::new();
+        // Comma decimals without the semicolon delimiters split every number in two
+        let corrupted = with_checksum("GPGGA,123519,4807,038,N,01131,000,E,1,08,0,9,545,4,M,46,9,M,,");
+        let parsed = gnss.feed_nmea(&corrupted);
+        assert_eq!(parsed, Err(NmeaError::CommaDecimalSeparator { address: "GPGGA".to_string() }));
+        assert_eq!(gnss.take_warnings(), vec![DataWarning::CommaDecimalSeparator { sentence_type: "GPGGA".to_string() }]);
+        assert_eq!((gnss.latitude(), gnss.longitude(), gnss.altitude()), (None, None, None));
+        assert_eq!(gnss.get_stats().rejected_sentences, 1);
+
+        for sentence in [
+            "GNRMC,123519,A,4807,038,S,01131,000,E,022,4,084,4,230394,003,1,W",
+            "GPGLL,4807,038,N,01131,000,E,123519,A",
+            "GNGNS,123519,4807,038,N,01131,000,E,AA,08,0,9,545,4,46,9,,",
+        ] {
+            let _ = gnss.feed_nmea(&with_checksum(sentence));
+        }
+        assert_eq!(gnss.take_warnings().len(), 3);
+        assert_eq!((gnss.latitude(), gnss.speed_knots()), (None, None));
+
+        // Period decimals and integer latitudes are left alone
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807,N,01131,E,123519,A"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert!(gnss.take_warnings().is_empty());
+        assert_eq!(gnss.altitude(), Some(545.4));
+    }
+
+    #[test]
+    fn test_emitted_numbers_use_period_decimals() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519.25,4807.038,S,01131.000,W,1,04,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+
+        // Whatever the host locale, emitted numbers re-parse with '.' decimals
+        let kml = gnss.to_kml().unwrap();
+        let coordinates = kml.split("<coordinates>").nth(1).and_then(|rest| rest.split("</coordinates>").next()).unwrap();
+        let values: Vec<f64> = coordinates.split(',').map(|value| value.parse().unwrap()).collect();
+        assert_eq!(values.len(), 3);
+        assert!((values[0] - fused.longitude).abs() < 1e-7 && (values[1] - fused.latitude).abs() < 1e-7);
+        assert_eq!(values[2], 545.4);
+        let accuracy = kml.split("Horizontal accuracy: ").nth(1).and_then(|rest| rest.split(" m").next()).unwrap();
+        assert_eq!(accuracy.parse::<f64>().unwrap(), (fused.estimated_accuracy * 100.0).round() / 100.0);
+
+        let dms = fused.latitude_dms();
+        assert_eq!(dms, "48°07'02.3\"S");
+        assert_eq!(gnss.parsed_time().unwrap().to_string(), "12:35:19.250");
+
+        // Re-parsing the emitted coordinates as NMEA gives back the same position
+        let mut reparsed = GnssData::new();
+        let lat = format!("{:02}{:07.4}", fused.latitude.abs().trunc(), fused.latitude.abs().fract() * 60.0);
+        let lon = format!("{:03}{:07.4}", fused.longitude.abs().trunc(), fused.longitude.abs().fract() * 60.0);
+        let _ = reparsed.feed_nmea(&with_checksum(&format!("GNGGA,123519.25,{lat},S,{lon},W,1,04,0.9,545.4,M,46.9,M,,")));
+        assert!(reparsed.take_warnings().is_empty());
+        assert!((reparsed.latitude().unwrap() - fused.latitude).abs() < 1e-6);
+        assert!((reparsed.longitude().unwrap() - fused.longitude).abs() < 1e-6);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&fused).unwrap();
+            assert!(json.contains("\"altitude_msl\":545.4"));
+            let restored: FusedPosition = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.latitude, fused.latitude);
+        }
+    }
+
+    #[test]
+    fn test_altitude_disagreement() {
+        let run = |limit: f64| {
+            let mut gnss = GnssData::new();
+            gnss.set_altitude_spread_limit(limit);
+            let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+            let _ = gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*00");
+            let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+            let _ = gnss.feed_nmea("$GNGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1*00");
+            let _ = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+            let _ = gnss.feed_nmea(&with_checksum("GLGGA,123519,4807.038,N,01131.000,E,1,08,0.9,585.4,M,46.9,M,,"));
+            gnss.calculate_fused_position();
+            gnss
+        };
+
+        let mut gnss = run(20.0);
+        let fused = gnss.fused_position().cloned().unwrap();
+        let diagnostics = gnss.fusion_diagnostics().unwrap();
+        assert_eq!(diagnostics.altitude_residuals.len(), 2);
+        let (gps, gps_residual) = &diagnostics.altitude_residuals[1];
+        let (glonass, glonass_residual) = &diagnostics.altitude_residuals[0];
+        assert_eq!((gps.as_str(), glonass.as_str()), ("GPS", "GLONASS"));
+        assert!((gps_residual - (545.4 - fused.altitude_msl)).abs() < 1e-9);
+        assert!((glonass_residual - (585.4 - fused.altitude_msl)).abs() < 1e-9);
+        assert!((gnss.altitude_spread_m().unwrap() - 40.0).abs() < 1e-9);
+
+        // A 40 m spread against a 20 m limit doubles the altitude accuracy
+        let unlimited = run(0.0);
+        let baseline = unlimited.fused_position().unwrap().altitude_accuracy;
+        assert!((fused.altitude_accuracy - 2.0 * baseline).abs() < 1e-9);
+
+        let warnings = gnss.take_warnings();
+        assert!(warnings.iter().any(|warning| matches!(
+            warning,
+            DataWarning::AltitudeDisagreement { spread, limit } if (spread - 40.0).abs() < 1e-9 && *limit == 20.0
+        )));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_mixed_altitude_datums() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*00");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1*00");
+        let _ = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        assert_eq!(gnss.geoid_separation, Some(46.9));
+        let glonass = gnss.systems.get_mut("GLONASS").unwrap();
+        glonass.altitude = Some(592.3);
+        glonass.altitude_datum = AltitudeDatum::Ellipsoidal;
+        gnss.calculate_fused_position();
+
+        // The ellipsoidal GLONASS altitude is converted to MSL before averaging
+        let fused = gnss.fused_position.clone().unwrap();
+        assert!((fused.altitude_msl - 545.4).abs() < 1e-9);
+        assert!((fused.altitude_ellipsoidal.unwrap() - 592.3).abs() < 1e-9);
+        assert_eq!(fused.altitude, fused.altitude_msl);
+        assert!(gnss.altitude_spread_m().unwrap() < 1e-9);
+
+        // Without a geoid separation the ellipsoidal altitude cannot be used
+        gnss.geoid_separation = None;
+        gnss.systems.get_mut("GPS").unwrap().altitude = Some(500.0);
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position.clone().unwrap();
+        assert!((fused.altitude_msl - 500.0).abs() < 1e-9);
+        assert_eq!(fused.altitude_ellipsoidal, None);
+    }
+
+    #[cfg(feature = "geoid")]
+    #[test]
+    fn test_geoid_model_fills_missing_separation() {
+        use crate::geoid::GeoidGrid;
+        let mut gnss = GnssData::new();
+        gnss.set_geoid_model(Some(GeoidGrid::new(90.0, vec![40.0; 3 * 4]).unwrap()));
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,,M,,"));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!((fused.altitude_ellipsoidal.unwrap() - 585.4).abs() < 1e-4);
+
+        // A reported separation takes precedence over the model
+        let _ = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!((fused.altitude_ellipsoidal.unwrap() - 592.3).abs() < 1e-9);
+
+        // The model survives a reset
+        gnss.reset();
+        assert!(gnss.get_geoid_model().is_some());
+    }
+
+    #[test]
+    fn test_implausible_dops_are_treated_as_missing() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*6C");
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,65,66,67,68,,,,,,,,,0.0,0.0,0.0"));
+        assert_eq!(gnss.system("GLONASS").unwrap().hdop(), None);
+        let warnings = gnss.take_warnings();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(warnings[1], DataWarning::ImplausibleDop { sentence_type: "GSA".to_string(), value: 0.0 });
+
+        // GLONASS falls back to the GGA HDOP and is weighted below GPS
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGGA,123519,4807.638,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        assert_eq!(fused.contributing_systems.len(), 2);
+        assert!(fused.latitude - 48.1173 < 48.1273 - fused.latitude);
+
+        // Absurdly high DOPs are dropped too, within a configurable range
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,08,75.0,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.gga_hdop(), None);
+        gnss.set_plausible_dop_range(0.3, 100.0);
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123521,4807.038,N,01131.000,E,1,08,75.0,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.gga_hdop(), Some(75.0));
+        assert_eq!(gnss.take_warnings(), vec![DataWarning::ImplausibleDop { sentence_type: "GGA".to_string(), value: 75.0 }]);
+    }
+
+    #[test]
+    fn test_keep_best_only() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut gnss = GnssData::new();
+        gnss.set_keep_best_only(true);
+        gnss.set_best_fix_staleness(Some(Duration::from_secs(60)));
+        let _ = gnss.feed_nmea_at("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C", t0);
+
+        let epoch = |gnss: &mut GnssData, hdop: f64, secs: u64| {
+            let gsa = format!("GNGSA,A,3,01,02,03,04,,,,,,,,,2.5,{:.1},2.1", hdop);
+            let _ = gnss.feed_nmea_at(&with_checksum(&gsa), t0 + Duration::from_secs(secs));
+            let _ = gnss.feed_nmea_at(&gga_at(48.1173 + hdop * 1e-4, 11.5166667), t0 + Duration::from_secs(secs));
+            gnss.calculate_fused_position();
+            gnss.fused_position().cloned().unwrap()
+        };
+
+        // Improving accuracy replaces the stored fix
+        let first = epoch(&mut gnss, 2.0, 1);
+        let better = epoch(&mut gnss, 1.2, 2);
+        assert!(better.estimated_accuracy < first.estimated_accuracy);
+
+        // Worsening accuracy keeps the best fix
+        let kept = epoch(&mut gnss, 1.8, 3);
+        assert_eq!(kept.estimated_accuracy, better.estimated_accuracy);
+        assert_eq!(kept.latitude, better.latitude);
+
+        // Once the best fix is stale, a worse fix replaces it
+        let replaced = epoch(&mut gnss, 1.8, 70);
+        assert!(replaced.estimated_accuracy > better.estimated_accuracy);
+
+        // Without the flag every epoch replaces the fix
+        gnss.set_keep_best_only(false);
+        epoch(&mut gnss, 1.0, 71);
+        let worse = epoch(&mut gnss, 1.8, 72);
+        assert_eq!(worse.estimated_accuracy, replaced.estimated_accuracy);
     }
 
     #[test]
-    fn test_feed_nmea_beidou_gsv() {
+    fn test_position_precedence_in_every_order() {
+        let gga = with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,");
+        let rmc = with_checksum("GNRMC,123519.00,A,4807.039,N,01131.001,E,022.4,084.4,230394,003.1,W");
+        let gll = with_checksum("GPGLL,4807.040,N,01131.002,E,123519,A,A");
+        let orders = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        for order in orders {
+            let mut gnss = GnssData::new();
+            let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+            for index in order {
+                let _ = gnss.feed_nmea([&gga, &rmc, &gll][index]);
+            }
+            assert!((gnss.latitude().unwrap() - (48.0 + 7.038 / 60.0)).abs() < 1e-9, "order {:?}", order);
+            assert!((gnss.longitude().unwrap() - (11.0 + 31.0 / 60.0)).abs() < 1e-9, "order {:?}", order);
+            assert_eq!(gnss.system("GPS").unwrap().latitude(), gnss.latitude(), "order {:?}", order);
+            assert_eq!(gnss.altitude(), Some(545.4));
+        }
+
+        // A new epoch clears the claims, so a lone GLL sets the position again
         let mut gnss = GnssData::new();
-        let gsv = "$BDGSV,1,1,04,201,45,123,35,202,30,045,40,203,60,234,45,204,25,156,38*XX";
-        gnss.feed_nmea(gsv);
-        let beidou_info = &gnss.systems["BEIDOU"].satellites_info;
-        assert_eq!(beidou_info.len(), 4);
-        assert!(beidou_info.contains_key(&201));
-        assert!(beidou_info.contains_key(&202));
-        assert!(beidou_info.contains_key(&203));
-        assert!(beidou_info.contains_key(&204));
-        let sat201 = beidou_info.get(&201).unwrap();
-        assert_eq!(sat201.prn, 201);
-        assert_eq!(sat201.elevation, Some(45));
-        assert_eq!(sat201.azimuth, Some(123));
-        assert_eq!(sat201.snr, Some(35));
+        let _ = gnss.feed_nmea(&gga);
+        let _ = gnss.feed_nmea(&with_checksum("GPGLL,4807.040,N,01131.002,E,123520,A,A"));
+        assert!((gnss.latitude().unwrap() - (48.0 + 7.040 / 60.0)).abs() < 1e-9);
     }
 
     #[test]
-    fn test_feed_nmea_gsa_gps() {
+    fn test_speed_precedence() {
         let mut gnss = GnssData::new();
-        let gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,09,10,11,12,1.2,0.9,2.1*39";
-        gnss.feed_nmea(gsa);
-        let gps_used = &gnss.systems["GPS"].satellites_used;
-        assert!(gps_used.contains(&1));
-        assert!(gps_used.contains(&2));
-        assert!(gps_used.contains(&3));
-        assert!(gps_used.contains(&4));
-        assert_eq!(gnss.systems["GPS"].pdop, Some(1.2));
-        assert_eq!(gnss.systems["GPS"].hdop, Some(0.9));
-        assert_eq!(gnss.systems["GPS"].vdop, Some(2.1));
+        let _ = gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A");
+        let _ = gnss.feed_nmea("$GNVTG,084.4,T,,M,022.5,N,041.7,K,A*00");
+        assert_eq!(gnss.speed_knots(), Some(22.4));
+
+        gnss.set_speed_precedence(&["VTG", "RMC"]);
+        let _ = gnss.feed_nmea("$GNVTG,084.4,T,,M,022.5,N,041.7,K,A*00");
+        let _ = gnss.feed_nmea("$GNRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A");
+        assert_eq!(gnss.speed_knots(), Some(22.5));
     }
 
     #[test]
-    fn test_feed_nmea_gsa_glonass() {
+    fn test_coordinate_conflict_between_sentence_types() {
         let mut gnss = GnssData::new();
-        let gsa = "$GNGSA,A,3,67,68,69,77,78,79,86,87,88,,,,,1.8,1.1,1.4*3F";
-        gnss.feed_nmea(gsa);
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        // RMC of the same epoch one arc-minute of latitude (about 1853 m) further north
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123519,A,4808.038,N,01131.000,E,022.4,084.4,230394,003.1,W"));
 
-        // Debug output
-        println!("GLONASS satellites_used: {:?}", gnss.systems["GLONASS"].satellites_used);
-        println!("GLONASS pdop: {:?}", gnss.systems["GLONASS"].pdop);
-        println!("GLONASS hdop: {:?}", gnss.systems["GLONASS"].hdop);
-        println!("GLONASS vdop: {:?}", gnss.systems["GLONASS"].vdop);
+        let spread = gnss.coordinate_consistency().unwrap();
+        assert!((spread - 1853.2).abs() < 1.0, "{}", spread);
+        assert!(gnss.has_coordinate_conflict(100.0));
+        assert!(!gnss.has_coordinate_conflict(2000.0));
+        // Precedence still keeps the GGA position
+        assert!((gnss.latitude().unwrap() - (48.0 + 7.038 / 60.0)).abs() < 1e-9);
 
-        let glonass_used = &gnss.systems["GLONASS"].satellites_used;
-        assert!(glonass_used.contains(&67));
-        assert!(glonass_used.contains(&68));
-        assert!(glonass_used.contains(&69));
-        assert!(glonass_used.contains(&77));
-        assert_eq!(gnss.systems["GLONASS"].pdop, Some(1.8));
-        assert_eq!(gnss.systems["GLONASS"].hdop, Some(1.1));
-        assert_eq!(gnss.systems["GLONASS"].vdop, Some(1.4));
+        // A new epoch starts over
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert_eq!(gnss.coordinate_consistency(), None);
+        assert!(!gnss.has_coordinate_conflict(100.0));
     }
 
     #[test]
-    fn test_feed_nmea_gsa_galileo() {
+    fn test_to_kml_coordinate_order() {
         let mut gnss = GnssData::new();
-        let gsa = "$GNGSA,A,3,301,302,303,304,305,306,,,,,,,2.1,1.3,1.6*XX";
-        gnss.feed_nmea(gsa);
-        let galileo_used = &gnss.systems["GALILEO"].satellites_used;
-        assert!(galileo_used.contains(&301));
-        assert!(galileo_used.contains(&302));
-        assert!(galileo_used.contains(&303));
-        assert!(galileo_used.contains(&304));
-        assert_eq!(gnss.systems["GALILEO"].pdop, Some(2.1));
-        assert_eq!(gnss.systems["GALILEO"].hdop, Some(1.3));
-        assert_eq!(gnss.systems["GALILEO"].vdop, Some(1.6));
+        assert_eq!(gnss.to_kml(), None);
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,3345.000,S,07030.000,W,1,08,0.9,520.0,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+
+        let kml = gnss.to_kml().unwrap();
+        assert!(kml.starts_with("<Placemark>") && kml.ends_with("</Placemark>"));
+        // Longitude first, then latitude, then altitude
+        assert!(kml.contains("<coordinates>-70.5000000,-33.7500000,520.00</coordinates>"), "{}", kml);
+        assert!(kml.contains("<altitudeMode>absolute</altitudeMode>"));
+        assert!(kml.contains("systems: GPS"));
     }
 
     #[test]
-    fn test_feed_nmea_gsa_beidou() {
+    fn test_gst_sigmas_drive_fused_accuracy() {
         let mut gnss = GnssData::new();
-        let gsa = "$GNGSA,A,3,201,202,203,204,205,206,,,,,,,1.5,0.8,1.2*XX";
-        gnss.feed_nmea(gsa);
-        let beidou_used = &gnss.systems["BEIDOU"].satellites_used;
-        assert!(beidou_used.contains(&201));
-        assert!(beidou_used.contains(&202));
-        assert!(beidou_used.contains(&203));
-        assert!(beidou_used.contains(&204));
-        assert_eq!(gnss.systems["BEIDOU"].pdop, Some(1.5));
-        assert_eq!(gnss.systems["BEIDOU"].hdop, Some(0.8));
-        assert_eq!(gnss.systems["BEIDOU"].vdop, Some(1.2));
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*6C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1*29");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        let static_accuracy = gnss.fused_position().unwrap().estimated_accuracy;
+        assert!(static_accuracy > 1.0);
+
+        // RTK-grade sigmas for the same epoch
+        assert_eq!(gnss.feed_nmea(&with_checksum("GNGST,123519,0.02,0.01,0.01,12.0,0.012,0.016,0.03")), Ok(SentenceType::Gst));
+        let stats = gnss.error_statistics().cloned().unwrap();
+        assert_eq!(stats.rms_residual, Some(0.02));
+        assert_eq!(stats.sigma_altitude, Some(0.03));
+        for advanced in [false, true] {
+            if advanced {
+                gnss.calculate_advanced_fused_position();
+            } else {
+                gnss.calculate_fused_position();
+            }
+            let fused = gnss.fused_position().unwrap();
+            assert!((fused.estimated_accuracy - 0.02).abs() < 1e-12, "advanced: {}", advanced);
+            assert_eq!(fused.altitude_accuracy, 0.03);
+        }
+
+        // Statistics of another epoch are not applied
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().estimated_accuracy, static_accuracy);
+
+        // Without a fix the sigmas are empty and the statistics cleared
+        let _ = gnss.feed_nmea(&with_checksum("GNGST,123520,,,,,,,"));
+        assert_eq!(gnss.error_statistics(), None);
     }
 
     #[test]
-    fn test_coordinates_update_for_systems_with_satellites() {
+    fn test_per_system_gst_sigmas() {
         let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*6C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1*29");
+        let _ = gnss.feed_nmea("$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        let _ = gnss.feed_nmea(&with_checksum("GPGST,123519,1.1,,,,0.6,0.8,1.5"));
+        assert_eq!(gnss.error_statistics(), None);
+        assert_eq!(gnss.system("GPS").unwrap().error_std().0, Some(0.6));
+        assert_eq!(gnss.system("GPS").unwrap().error_std().2, Some(1.5));
 
-        // Add GPS satellites
-        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
-        gnss.feed_nmea(gps_gsv);
+        // Until every contributing system reports sigmas, the DOP heuristic is kept
+        gnss.calculate_advanced_fused_position();
+        let heuristic = gnss.fused_position().unwrap().estimated_accuracy;
+        assert!(heuristic > 1.0);
 
-        // Add GLONASS satellites
-        let glonass_gsv = "$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*XX";
-        gnss.feed_nmea(glonass_gsv);
+        let _ = gnss.feed_nmea(&with_checksum("GLGST,123519,2.3,,,,1.2,1.6,2.0"));
+        gnss.calculate_advanced_fused_position();
+        let fused = gnss.fused_position().unwrap();
+        let expected = 1.0 / (1.0_f64 / 1.0 + 1.0 / 4.0).sqrt();
+        assert!((fused.estimated_accuracy - expected).abs() < 1e-12);
+        let expected = 1.0 / (1.0_f64 / 2.25 + 1.0 / 4.0).sqrt();
+        assert!((fused.altitude_accuracy - expected).abs() < 1e-12);
 
-        // Update coordinates via GGA
-        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
-        gnss.feed_nmea(gga);
+        // Empty sigmas clear the system's statistics
+        let _ = gnss.feed_nmea(&with_checksum("GLGST,123520,,,,,,,"));
+        assert_eq!(gnss.system("GLONASS").unwrap().error_std().1, None);
+        gnss.calculate_advanced_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().estimated_accuracy, heuristic);
+    }
 
-        // Systems with satellites should have coordinates
-        assert!(gnss.systems["GPS"].latitude.is_some());
-        assert!(gnss.systems["GPS"].longitude.is_some());
-        assert!(gnss.systems["GLONASS"].latitude.is_some());
-        assert!(gnss.systems["GLONASS"].longitude.is_some());
+    #[test]
+    #[allow(deprecated)]
+    fn test_degraded_grid_is_isotropic() {
+        let fused_at = |latitude: f64, longitude: f64| FusedPosition {
+            latitude,
+            longitude,
+            altitude: 100.0,
+            altitude_msl: 100.0,
+            altitude_ellipsoidal: None,
+            estimated_accuracy: 2.0,
+            instantaneous_accuracy: 2.0,
+            altitude_accuracy: 3.0,
+            altitude_held: false,
+            altitude_source: AltitudeSource::Measured3D,
+            contributing_systems: vec!["GPS".to_string()],
+            time: None,
+            fix_quality: None,
+            heading: None,
+        };
+        let accuracy = 100.0;
+        let cell = accuracy * std::f64::consts::SQRT_2;
 
-        // Systems without satellites should not have coordinates
-        assert!(gnss.systems["GALILEO"].latitude.is_none());
-        assert!(gnss.systems["GALILEO"].longitude.is_none());
-        assert!(gnss.systems["BEIDOU"].latitude.is_none());
-        assert!(gnss.systems["BEIDOU"].longitude.is_none());
+        for base_latitude in [0.0, 60.0] {
+            // Sweep 2 km north and east in 10 m steps and collect the grid lines hit
+            let step_deg = (10.0 / EARTH_RADIUS_M).to_degrees();
+            let mut latitudes = Vec::new();
+            let mut longitudes = Vec::new();
+            for i in 0..200 {
+                let offset = i as f64 * step_deg;
+                let east = fused_at(base_latitude, 10.0 + offset / base_latitude.to_radians().cos());
+                let north = fused_at(base_latitude + offset, 10.0);
+                for (original, degraded) in [(&east, east.degraded(accuracy)), (&north, north.degraded(accuracy))] {
+                    let moved = haversine_distance(original.latitude, original.longitude, degraded.latitude, degraded.longitude);
+                    assert!(moved <= accuracy + 1e-6, "moved {} m at {}", moved, base_latitude);
+                    assert_eq!(degraded.estimated_accuracy, accuracy);
+                }
+                latitudes.push(north.degraded(accuracy).latitude);
+                longitudes.push((east.degraded(accuracy).latitude, east.degraded(accuracy).longitude));
+            }
+            latitudes.dedup();
+            longitudes.dedup();
+            assert!(latitudes.len() > 10 && longitudes.len() > 10);
+
+            // Adjacent grid lines are one cell apart in meters along both axes
+            for pair in latitudes.windows(2) {
+                let spacing = haversine_distance(pair[0], 10.0, pair[1], 10.0);
+                assert!((spacing - cell).abs() < 0.01, "latitude spacing {} m at {}", spacing, base_latitude);
+            }
+            for pair in longitudes.windows(2) {
+                let ((latitude, lon1), (_, lon2)) = (pair[0], pair[1]);
+                let spacing = haversine_distance(latitude, lon1, latitude, lon2);
+                assert!((spacing - cell).abs() < 0.01, "longitude spacing {} m at {}", spacing, base_latitude);
+            }
+        }
+
+        // Worse accuracies are kept, and invalid accuracies leave the position unchanged
+        let mut coarse = fused_at(45.0, 7.0);
+        coarse.estimated_accuracy = 500.0;
+        assert_eq!(coarse.degraded(accuracy).estimated_accuracy, 500.0);
+        let unchanged = fused_at(45.123456, 7.654321).degraded(f64::NAN);
+        assert_eq!((unchanged.latitude, unchanged.longitude, unchanged.estimated_accuracy), (45.123456, 7.654321, 2.0));
     }
 
     #[test]
-    fn test_fused_position_calculation() {
+    fn test_to_kml_privacy_mode() {
         let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C");
+        let _ = gnss.feed_nmea("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39");
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,3345.123,S,07030.456,W,1,08,0.9,520.0,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        let exact = gnss.fused_position().cloned().unwrap();
 
-        // Add GPS satellites and coordinates
-        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
-        gnss.feed_nmea(gps_gsv);
-        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
-        gnss.feed_nmea(gps_gsa);
+        gnss.set_privacy_accuracy(Some(1000.0));
+        let degraded = exact.degraded(1000.0);
+        let kml = gnss.to_kml().unwrap();
+        let coordinates = format!("<coordinates>{:.7},{:.7},520.00</coordinates>", degraded.longitude, degraded.latitude);
+        assert!(kml.contains(&coordinates), "{}", kml);
+        assert!(kml.contains("Horizontal accuracy: 1000.00 m"));
+        // The stored fix keeps its precision
+        assert_eq!(gnss.fused_position().unwrap().latitude, exact.latitude);
+        gnss.reset();
+        assert_eq!(gnss.get_privacy_accuracy(), Some(1000.0));
+    }
 
-        // Add GLONASS satellites and coordinates
-        let glonass_gsv = "$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*XX";
-        gnss.feed_nmea(glonass_gsv);
-        let glonass_gsa = "$GNGSA,A,3,67,68,69,77,78,79,86,87,,,,,1.8,1.1,1.4*3F";
-        gnss.feed_nmea(glonass_gsa);
+    #[test]
+    fn test_altitude_source_from_gsa_fix_types() {
+        let fused_for = |gps_fix: u8, glonass_fix: u8| {
+            let mut gnss = GnssData::new();
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+            let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,67,14,186,29,68,49,228,26,69,42,308,30,77,15,064,27"));
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GPGSA,A,{},01,02,03,04,,,,,,,,,1.2,0.9,2.1", gps_fix)));
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GLGSA,A,{},67,68,69,77,,,,,,,,,1.2,0.9,2.1", glonass_fix)));
+            let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+            let _ = gnss.feed_nmea(&with_checksum("GLGGA,123519,4807.038,N,01131.000,E,1,08,0.9,600.0,M,46.9,M,,"));
+            assert_eq!(gnss.system("GLONASS").unwrap().fix_type(), FixType::from_gsa(glonass_fix));
+            gnss.calculate_fused_position();
+            gnss.fused_position().cloned().unwrap()
+        };
 
-        // Update coordinates
-        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
-        gnss.feed_nmea(gga);
+        let all_3d = fused_for(3, 3);
+        assert_eq!(all_3d.altitude_source, AltitudeSource::Measured3D);
+        assert!(all_3d.altitude_msl > 545.4 && all_3d.altitude_msl < 600.0);
+        assert!(all_3d.altitude_accuracy.is_finite());
 
-        // Calculate fused position
+        // The 2D system's assumed altitude is left out
+        let mixed = fused_for(3, 2);
+        assert_eq!(mixed.altitude_source, AltitudeSource::Measured3D);
+        assert_eq!(mixed.altitude_msl, 545.4);
+        assert!(mixed.altitude_accuracy.is_finite());
+
+        let all_2d = fused_for(2, 2);
+        assert_eq!(all_2d.altitude_source, AltitudeSource::Constrained2D);
+        assert!(all_2d.altitude_msl.is_finite());
+        assert_eq!(all_2d.altitude_accuracy, f64::INFINITY);
+
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,2,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,,M,,M,,"));
         gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().altitude_source, AltitudeSource::Unavailable);
+    }
 
-        assert!(gnss.fused_position.is_some());
-        let fused = gnss.fused_position.as_ref().unwrap();
-        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
-        assert!(fused.contributing_systems.contains(&"GLONASS".to_string()));
-        assert!(fused.estimated_accuracy > 0.0);
+    #[test]
+    fn test_gsa_fix_loss_clears_dops_and_fusion() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,67,14,186,29,68,49,228,26,69,42,308,30,77,15,064,27"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSA,A,3,67,68,69,77,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, ["GPS", "GLONASS"]);
+        assert_eq!(gnss.fix_type(), Some(FixType::Fix3D));
+
+        // GLONASS loses its fix: the DOPs and used satellites of the last fix are dropped
+        let _ = gnss.feed_nmea(&with_checksum("GLGSA,M,1,,,,,,,,,,,,,99.9,99.9,99.9"));
+        let glonass = gnss.system("GLONASS").unwrap();
+        assert_eq!((glonass.fix_type(), glonass.selection_mode()), (Some(FixType::None), Some('M')));
+        assert_eq!((glonass.pdop(), glonass.hdop(), glonass.vdop()), (None, None, None));
+        assert!(glonass.satellites_used().is_empty());
+        assert_eq!(glonass.accuracy(), glonass.fixed_accuracy());
+        assert_eq!(gnss.fix_type(), Some(FixType::None));
+        assert_eq!(gnss.system("GPS").unwrap().hdop(), Some(0.9));
+
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, ["GPS"]);
     }
 
     #[test]
-    fn test_fused_position_with_altitude() {
+    fn test_satellites_marked_used_from_gsa() {
+        let used = |gnss: &GnssData| -> Vec<u16> {
+            let mut prns: Vec<u16> = gnss.system("GPS").unwrap().satellites().values().filter(|sat| sat.used).map(|sat| sat.prn).collect();
+            prns.sort_unstable();
+            prns
+        };
         let mut gnss = GnssData::new();
 
-        // Add GPS satellites and coordinates with altitude
-        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
-        gnss.feed_nmea(gps_gsv);
-        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
-        gnss.feed_nmea(gps_gsa);
+        // GSA before GSV: satellites are marked as they are tracked
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,03,,,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        assert_eq!(used(&gnss), [1, 3]);
 
-        // Add GLONASS satellites and coordinates
-        let glonass_gsv = "$GLGSV,1,1,04,67,14,186,09,68,49,228,26,69,42,308,,77,15,064,17*XX";
-        gnss.feed_nmea(glonass_gsv);
-        let glonass_gsa = "$GNGSA,A,3,67,68,69,77,78,79,86,87,,,,,1.8,1.1,1.4*3F";
-        gnss.feed_nmea(glonass_gsa);
+        // GSV before GSA: the next epoch's GSA updates the tracked satellites
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,02,03,04,,,,,,,,,,1.2,0.9,2.1"));
+        assert_eq!(used(&gnss), [2, 3, 4]);
 
-        // Update coordinates with altitude data
-        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
-        gnss.feed_nmea(gga);
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,1,,,,,,,,,,,,,,,"));
+        assert!(used(&gnss).is_empty());
+    }
 
-        // Verify altitude is stored in system data
-        assert_eq!(gnss.systems["GPS"].altitude, Some(545.4));
-        assert_eq!(gnss.systems["GLONASS"].altitude, Some(545.4));
+    #[test]
+    fn test_altitude_held_for_2d_epoch() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let gga_3d = with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,");
+        let gga_2d = with_checksum("GNGGA,123520,4807.039,N,01131.000,E,1,08,0.9,,M,,M,,");
+        let mut gnss = GnssData::new();
+        // The two epochs are replayed alternately
+        gnss.set_late_tolerance(Duration::MAX);
+        let _ = gnss.feed_nmea_at("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C", t0);
+        let _ = gnss.feed_nmea_at("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39", t0);
 
-        // Calculate fused position
+        // Without a hold, a 2D-only epoch has no altitude rather than a zero one
+        let _ = gnss.feed_nmea_at(&gga_3d, t0);
+        gnss.calculate_fused_position();
+        let _ = gnss.feed_nmea_at(&gga_2d, t0 + Duration::from_secs(1));
         gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!(fused.altitude_msl.is_nan() && !fused.altitude_held);
+        assert_eq!(fused.altitude_mm(), None);
+        assert!(gnss.to_kml().unwrap().contains("<coordinates>11.5166667,48.1173167</coordinates>"));
 
-        assert!(gnss.fused_position.is_some());
-        let fused = gnss.fused_position.as_ref().unwrap();
+        gnss.set_altitude_hold(Some(Duration::from_secs(10)));
+        let _ = gnss.feed_nmea_at(&gga_3d, t0 + Duration::from_secs(2));
+        gnss.calculate_fused_position();
+        let three_d = gnss.fused_position().cloned().unwrap();
+        assert_eq!(three_d.altitude_msl, 545.4);
+        assert!(!three_d.altitude_held);
 
-        // Verify altitude and altitude accuracy are calculated (use approximate comparison for floating point)
-        assert!((fused.altitude - 545.4).abs() < 0.001);
-        assert!(fused.altitude_accuracy > 0.0);
-        assert!(fused.estimated_accuracy > 0.0);
+        // The 2D epoch within the hold keeps the last 3D altitude and flags it
+        let _ = gnss.feed_nmea_at(&gga_2d, t0 + Duration::from_secs(3));
+        gnss.calculate_fused_position();
+        let held = gnss.fused_position().cloned().unwrap();
+        assert!(held.altitude_held);
+        assert_eq!(held.altitude_msl, 545.4);
+        assert_eq!(held.altitude_accuracy, three_d.altitude_accuracy);
+        assert!((held.latitude - (48.0 + 7.039 / 60.0)).abs() < 1e-9);
 
-        // Verify contributing systems
-        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
-        assert!(fused.contributing_systems.contains(&"GLONASS".to_string()));
+        // Past the hold duration the altitude is no longer held
+        let _ = gnss.feed_nmea_at(&with_checksum("GNGGA,123535,4807.039,N,01131.000,E,1,08,0.9,,M,,M,,"), t0 + Duration::from_secs(15));
+        gnss.calculate_fused_position();
+        assert!(!gnss.fused_position().unwrap().altitude_held);
     }
 
     #[test]
-    fn test_advanced_fused_position_with_altitude() {
+    fn test_position_filter_over_stationary_epochs() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let epoch = |gnss: &mut GnssData, second: u64| {
+            let at = t0 + Duration::from_secs(second);
+            let time = format!("{:02}{:02}{:02}", 12 + second / 3600, 35 + second / 60 % 60, second % 60);
+            let _ = gnss.feed_nmea_at("$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*7C", at);
+            let _ = gnss.feed_nmea_at("$GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1*39", at);
+            let _ = gnss.feed_nmea_at(&with_checksum(&format!("GNGGA,{},4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,", time)), at);
+            gnss.calculate_fused_position();
+            gnss.fused_position().cloned().unwrap()
+        };
         let mut gnss = GnssData::new();
+        gnss.set_position_filter(Some(0.01));
 
-        // Add GPS satellites and coordinates
-        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
-        gnss.feed_nmea(gps_gsv);
-        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
-        gnss.feed_nmea(gps_gsa);
+        let first = epoch(&mut gnss, 0);
+        assert_eq!(first.estimated_accuracy, first.instantaneous_accuracy);
+        assert_eq!(gnss.fusion_diagnostics().unwrap().filter_gain, Some(1.0));
 
-        // Add GALILEO satellites with different DOP values
-        let galileo_gsv = "$GAGSV,1,1,04,301,45,123,35,302,30,045,40,303,60,234,45,304,25,156,38*XX";
-        gnss.feed_nmea(galileo_gsv);
-        let galileo_gsa = "$GNGSA,A,3,301,302,303,304,305,306,,,,,,,2.1,1.3,1.6*XX";
-        gnss.feed_nmea(galileo_gsa);
+        // Repeated fixes of a stationary receiver shrink the filtered uncertainty only
+        let mut previous = first.estimated_accuracy;
+        for second in 1..10 {
+            let fused = epoch(&mut gnss, second);
+            assert_eq!(fused.instantaneous_accuracy, first.instantaneous_accuracy);
+            assert!(fused.estimated_accuracy < previous, "epoch {}", second);
+            assert!((fused.latitude - first.latitude).abs() < 1e-12);
+            previous = fused.estimated_accuracy;
+        }
+        assert!(previous < first.instantaneous_accuracy / 2.0);
+        assert!(gnss.fusion_diagnostics().unwrap().filter_gain.unwrap() < 0.5);
 
-        // Update coordinates with altitude
-        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
-        gnss.feed_nmea(gga);
+        // After a long outage, the uncertainty has grown back to the per-epoch accuracy
+        let after_outage = epoch(&mut gnss, 3600);
+        assert!((after_outage.estimated_accuracy - after_outage.instantaneous_accuracy).abs() < 0.01 * after_outage.instantaneous_accuracy);
+        assert!(epoch(&mut gnss, 3601).estimated_accuracy < after_outage.estimated_accuracy);
 
-        // Calculate advanced fused position
-        gnss.calculate_advanced_fused_position();
+        // A reset keeps the filter enabled but restarts it from the next fix
+        gnss.reset_at(t0 + Duration::from_secs(3602));
+        assert_eq!(gnss.get_position_filter(), Some(0.01));
+        let restarted = epoch(&mut gnss, 3602);
+        assert_eq!(restarted.estimated_accuracy, restarted.instantaneous_accuracy);
 
-        assert!(gnss.fused_position.is_some());
-        let fused = gnss.fused_position.as_ref().unwrap();
+        // Without a filter both accuracies are equal
+        gnss.set_position_filter(None);
+        let unfiltered = epoch(&mut gnss, 3603);
+        assert_eq!(unfiltered.estimated_accuracy, unfiltered.instantaneous_accuracy);
+        assert_eq!(gnss.fusion_diagnostics().unwrap().filter_gain, None);
+    }
 
-        // Verify altitude fusion and accuracy calculation
-        assert!((fused.altitude - 545.4).abs() < 0.001);
-        // Since both systems have identical altitude, variance will be 0, so altitude_accuracy will be 1.5x horizontal accuracy
-        assert!(fused.altitude_accuracy > 0.0); // Just ensure it's positive
-        assert!(fused.estimated_accuracy >= 1.0); // Minimum 1 meter accuracy
+    #[test]
+    fn test_feed_bytes_buffers_partial_lines() {
+        let mut gnss = GnssData::new();
+        let gga = with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,");
+        let (head, tail) = gga.as_bytes().split_at(30);
 
-        // Should have both GPS and GALILEO contributing
-        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
-        assert!(fused.contributing_systems.contains(&"GALILEO".to_string()));
+        // Noise and the first half of the sentence parse nothing yet
+        assert_eq!(gnss.feed_bytes(b"boot banner\r\n"), 0);
+        assert_eq!(gnss.feed_bytes(head), 0);
+        assert_eq!(gnss.latitude(), None);
+
+        // The terminator completes the buffered sentence, even split between CR and LF
+        assert_eq!(gnss.feed_bytes(&[tail, b"\r"].concat()), 1);
+        assert!((gnss.latitude().unwrap() - 48.1173).abs() < 1e-9);
+        assert_eq!(gnss.feed_bytes(b"\n"), 0);
+
+        // Several sentences in one chunk, with bare LF terminators
+        let rmc = with_checksum("GNRMC,123520,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W");
+        assert_eq!(gnss.feed_bytes(format!("{}\n{}\n", gga, rmc).as_bytes()), 2);
+        assert_eq!(gnss.speed_knots(), Some(22.4));
+
+        // Encapsulated sentences are fed like feed_nmea feeds them
+        assert_eq!(gnss.feed_bytes(b"!AIVDM,1,1,,B,8P?wqthJ<v3io0qRM=`3231hKn3L,0*6F\r\n"), 1);
+        assert_eq!(gnss.get_stats().talkers().iter().find(|talker| talker.source == "AI").map(|talker| talker.sentences), Some(1));
+
+        // An overlong line is dropped whole rather than fed truncated
+        let overlong = format!("${}\r\n", "A".repeat(MAX_BUFFERED_LINE + 10));
+        assert_eq!(gnss.feed_bytes(overlong.as_bytes()), 0);
+        assert_eq!(gnss.feed_bytes(format!("{}\r\n", gga).as_bytes()), 1);
     }
 
-    #[test]
-    fn test_parse_lat_lon() {
-        let lat = parse_lat(Some(&"4807.038"), Some(&"N"));
-        let lon = parse_lon(Some(&"01131.000"), Some(&"E"));
-        assert!(lat.is_some());
-        assert!(lon.is_some());
-        let lat_val = lat.unwrap();
-        let lon_val = lon.unwrap();
-        assert!((lat_val - 48.1173).abs() < 0.0001);
-        assert!((lon_val - 11.5166667).abs() < 0.0001);
+    /// Feeds a GPS and a GLONASS epoch at separate positions, with identical DOPs.
+    fn feed_two_system_epoch(gnss: &mut GnssData, gps: (f64, f64), glonass: (f64, f64)) {
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1"));
+        for (talker, (lat, lon)) in [("GP", gps), ("GL", glonass)] {
+            let _ = gnss.feed_nmea(&with_checksum(&format!("{}GGA,123519,{},{},1,08,0.9,545.4,M,46.9,M,,",
+                talker, nmea_coordinate(lat, true), nmea_coordinate(lon, false))));
+        }
     }
 
     #[test]
-    fn test_beidou_altitude_integration() {
+    fn test_southern_and_western_fix() {
         let mut gnss = GnssData::new();
+        // Buenos Aires
+        feed_two_system_epoch(&mut gnss, (-34.6037, -58.3816), (-34.6037, -58.3816));
+        assert!((gnss.system("GPS").unwrap().latitude().unwrap() + 34.6037).abs() < 1e-6);
+        assert!((gnss.system("GLONASS").unwrap().longitude().unwrap() + 58.3816).abs() < 1e-6);
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!((fused.latitude + 34.6037).abs() < 1e-6 && (fused.longitude + 58.3816).abs() < 1e-6);
+        assert_eq!(fused.latitude_dms(), "34°36'13.3\"S");
+        assert_eq!(fused.longitude_dms(), "58°22'53.8\"W");
+        gnss.calculate_advanced_fused_position();
+        assert!((gnss.fused_position().unwrap().longitude + 58.3816).abs() < 1e-6);
+    }
 
-        // Add BeiDou satellites
-        let beidou_gsv = "$BDGSV,1,1,04,201,45,123,35,202,30,045,40,203,60,234,45,204,25,156,38*XX";
-        gnss.feed_nmea(beidou_gsv);
-        let beidou_gsa = "$GNGSA,A,3,201,202,203,204,205,206,,,,,,,1.5,0.8,1.2*XX";
-        gnss.feed_nmea(beidou_gsa);
+    #[test]
+    fn test_fusion_across_antimeridian() {
+        let mut gnss = GnssData::new();
+        // Either side of the antimeridian near Fiji, about 200 m apart
+        feed_two_system_epoch(&mut gnss, (-16.5, 179.999), (-16.5, -179.999));
+        gnss.calculate_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert_eq!(fused.contributing_systems.len(), 2);
+        assert!(fused.longitude.abs() > 179.999, "longitude {}", fused.longitude);
 
-        // Add GPS for comparison
-        let gps_gsv = "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*XX";
-        gnss.feed_nmea(gps_gsv);
-        let gps_gsa = "$GNGSA,A,3,01,02,03,04,05,06,07,08,,,,,1.2,0.9,2.1*39";
-        gnss.feed_nmea(gps_gsa);
+        gnss.calculate_advanced_fused_position();
+        let fused = gnss.fused_position().cloned().unwrap();
+        assert!(fused.longitude.abs() > 179.999, "longitude {}", fused.longitude);
+        // The spread between the systems is about 100 m either way, not half the globe
+        assert!(fused.estimated_accuracy < 200.0, "accuracy {}", fused.estimated_accuracy);
 
-        // Update coordinates with altitude
-        let gga = "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,445.2,M,46.9,M,,*47";
-        gnss.feed_nmea(gga);
+        // Both systems on the same side stay there
+        feed_two_system_epoch(&mut gnss, (-16.5, 179.998), (-16.5, 179.999));
+        gnss.calculate_fused_position();
+        let longitude = gnss.fused_position().unwrap().longitude;
+        assert!(longitude > 179.998 && longitude < 179.999, "longitude {}", longitude);
+    }
 
-        // Verify BeiDou has altitude data
-        assert_eq!(gnss.systems["BEIDOU"].altitude, Some(445.2));
-        assert_eq!(gnss.systems["GPS"].altitude, Some(445.2));
+    #[test]
+    fn test_advanced_fusion_spread_shrinks_with_latitude() {
+        let spread_at = |lat: f64| {
+            let mut gnss = GnssData::new();
+            feed_two_system_epoch(&mut gnss, (lat, 20.0), (lat, 20.01));
+            gnss.calculate_advanced_fused_position();
+            gnss.fused_position().unwrap().estimated_accuracy
+        };
+        // Systems 0.01° of longitude apart are 1.1 km apart at the equator
+        let equator = spread_at(0.0);
+        assert!(equator > 500.0, "equator {}", equator);
+        // Near the pole, the same longitude difference spans cos(latitude) of that distance
+        for lat in [60.0, 80.0, -80.0] {
+            let ratio = spread_at(lat) / equator;
+            assert!((ratio - f64::to_radians(lat).cos()).abs() < 1e-3, "latitude {}: ratio {}", lat, ratio);
+        }
+    }
 
-        // Calculate fused position including BeiDou
+    #[test]
+    fn test_lost_fix_clears_fused_position() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
         gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_some() && gnss.has_fix());
 
-        assert!(gnss.fused_position.is_some());
-        let fused = gnss.fused_position.as_ref().unwrap();
+        // A void RMC neither moves the position nor lets fusion use the stale one
+        let _ = gnss.feed_nmea(&with_checksum("GNRMC,123520,V,0000.000,N,00000.000,E,,,230394,,"));
+        assert!((gnss.latitude().unwrap() - 48.1173).abs() < 1e-9);
+        assert!((gnss.system("GPS").unwrap().latitude().unwrap() - 48.1173).abs() < 1e-9);
+        assert!(!gnss.has_fix());
+        gnss.calculate_fused_position();
+        assert!(gnss.fused_position().is_none());
+        gnss.calculate_advanced_fused_position();
+        assert!(gnss.fused_position().is_none());
 
-        // Verify BeiDou contributes to altitude fusion
-        assert!((fused.altitude - 445.2).abs() < 0.001);
-        assert!(fused.altitude_accuracy > 0.0);
-        assert!(fused.contributing_systems.contains(&"BEIDOU".to_string()));
-        assert!(fused.contributing_systems.contains(&"GPS".to_string()));
+        // A fix restores fusion; a GGA with fix quality 0 loses it again
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123521,4807.040,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        gnss.calculate_fused_position();
+        assert!((gnss.fused_position().unwrap().latitude - (48.0 + 7.040 / 60.0)).abs() < 1e-9);
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123522,,,,,0,00,99.99,,M,,M,,"));
+        assert!(gnss.latitude().is_some() && !gnss.position_valid());
+        gnss.calculate_advanced_fused_position();
+        assert!(gnss.fused_position().is_none());
     }
 
     #[test]
-    fn test_default_accuracy_values() {
-        let gnss = GnssData::new();
-        
-        // Calculate expected fused accuracy using RSS formula
-        let expected_fused_accuracy = 1_f64/(((1_f64/2.0_f64.powi(2)) +
-                                              (1_f64/4.0_f64.powi(2)) +
-                                              (1_f64/3.0_f64.powi(2)) +
-                                              (1_f64/3.0_f64.powi(2))).sqrt());
-
-        // Test calculated fused accuracy (approximately 1.37)
-        assert!((gnss.get_fused_accuracy() - expected_fused_accuracy).abs() < 0.01);
+    fn test_fix_quality_kind_codes() {
+        let expected = [
+            FixQuality::Invalid,
+            FixQuality::Gps,
+            FixQuality::Dgps,
+            FixQuality::Pps,
+            FixQuality::RtkFixed,
+            FixQuality::RtkFloat,
+            FixQuality::DeadReckoning,
+            FixQuality::Manual,
+            FixQuality::Simulation,
+            FixQuality::Sbas,
+            FixQuality::Invalid,
+        ];
+        for (code, quality) in expected.into_iter().enumerate() {
+            let mut gnss = GnssData::new();
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GNGGA,123519,4807.038,N,01131.000,E,{},08,0.9,545.4,M,46.9,M,,", code)));
+            assert_eq!(gnss.fix_quality(), Some(code as u8));
+            assert_eq!(gnss.fix_quality_kind(), quality, "code {}", code);
+        }
+        assert_eq!(GnssData::new().fix_quality_kind(), FixQuality::Invalid);
+    }
 
-        // Test default system accuracies
-        assert_eq!(gnss.get_system_accuracy("GPS"), Some(2.0));
-        assert_eq!(gnss.get_system_accuracy("GLONASS"), Some(4.0));
-        assert_eq!(gnss.get_system_accuracy("GALILEO"), Some(3.0));
-        assert_eq!(gnss.get_system_accuracy("BEIDOU"), Some(3.0));
-        assert_eq!(gnss.get_system_accuracy("INVALID"), None);
+    #[test]
+    fn test_rtk_fix_lowers_accuracy_floor() {
+        let accuracy_with = |quality: u8, hdop: &str| {
+            let mut gnss = GnssData::new();
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+            let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GNGGA,123519,4807.038,N,01131.000,E,{},08,{},545.4,M,46.9,M,,", quality, hdop)));
+            gnss.calculate_fused_position();
+            let fused = gnss.fused_position().cloned().unwrap();
+            (fused.estimated_accuracy, fused.altitude_accuracy)
+        };
+        let (autonomous, _) = accuracy_with(1, "0.9");
+        assert!(autonomous > 1.0);
+        assert_eq!(accuracy_with(4, "0.9"), (RTK_FIXED_ACCURACY_M, RTK_FIXED_ACCURACY_M * 1.5));
+        assert_eq!(accuracy_with(5, "0.9").0, RTK_FLOAT_ACCURACY_M);
+        // A poor geometry still scales the RTK estimate
+        assert!((accuracy_with(4, "2.0").0 - RTK_FIXED_ACCURACY_M * 2.0).abs() < 1e-12);
+        // SBAS corrections do not reach RTK accuracy
+        assert_eq!(accuracy_with(9, "0.9").0, autonomous);
     }
 
     #[test]
-    fn test_accuracy_getters_and_setters() {
+    fn test_rtk_gga_fused_accuracy_under_decimeter() {
         let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,03,04,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,65,66,67,68,,,,,,,,,1.2,0.9,2.1"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,4,12,0.6,545.4,M,46.9,M,1.0,0001"));
+        assert_eq!((gnss.fix_quality(), gnss.fix_quality_kind()), (Some(4), FixQuality::RtkFixed));
+        for advanced in [false, true] {
+            if advanced {
+                gnss.calculate_advanced_fused_position();
+            } else {
+                gnss.calculate_fused_position();
+            }
+            let fused = gnss.fused_position().unwrap();
+            assert_eq!(fused.fix_quality, Some(FixQuality::RtkFixed));
+            assert!(fused.estimated_accuracy < 0.1, "advanced: {}", advanced);
+        }
+    }
 
-        // Calculate expected fused accuracy using RSS formula
-        let expected_fused_accuracy = 1_f64/(((1_f64/2.0_f64.powi(2)) +
-                                              (1_f64/4.0_f64.powi(2)) +
-                                              (1_f64/3.0_f64.powi(2)) +
-                                              (1_f64/3.0_f64.powi(2))).sqrt());
-
-        // Test fused accuracy getter with calculated value (approximately 1.37)
-        assert!((gnss.get_fused_accuracy() - expected_fused_accuracy).abs() < 0.01);
-        gnss.set_fused_accuracy(3.0);
-        // Fused accuracy is always dynamic, so check the value again
-        assert!((gnss.get_fused_accuracy() - expected_fused_accuracy).abs() < 0.01);
-
-        // Test system accuracy getter and setter
-        assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(2.0));
-        assert!(gnss.set_system_fixed_accuracy("GPS", 1.5));
-        assert_eq!(gnss.get_system_fixed_accuracy("GPS"), Some(1.5));
-
-        // Test setting accuracy for invalid system
-        assert!(!gnss.set_system_fixed_accuracy("INVALID", 1.0));
-        assert_eq!(gnss.get_system_fixed_accuracy("INVALID"), None);
+    #[test]
+    fn test_gga_all_fields_and_stale_dgps() {
+        let fused_accuracy = |dgps_age: &str| {
+            let mut gnss = GnssData::new();
+            let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+            let _ = gnss.feed_nmea(&with_checksum(&format!("GPGGA,123519,4807.038,N,01131.000,E,2,08,0.9,545.4,M,46.9,M,{},0120", dgps_age)));
+            assert_eq!(gnss.gga_hdop(), Some(0.9));
+            assert_eq!(gnss.geoid_separation(), Some(46.9));
+            assert!((gnss.altitude_ellipsoid().unwrap() - 592.3).abs() < 1e-9);
+            assert_eq!(gnss.dgps_station(), Some(120));
+            gnss.calculate_fused_position();
+            (gnss.dgps_age(), gnss.fused_position().unwrap().estimated_accuracy)
+        };
+        let (fresh_age, fresh) = fused_accuracy("3.5");
+        assert_eq!(fresh_age, Some(3.5));
+        let (stale_age, stale) = fused_accuracy("75.0");
+        assert_eq!(stale_age, Some(75.0));
+        assert!((stale - fresh * STALE_DGPS_PENALTY).abs() < 1e-9);
+        // At the limit the corrections still count as fresh
+        assert_eq!(fused_accuracy("60").1, fresh);
     }
 
     #[test]
-    fn test_get_all_system_accuracies() {
+    fn test_gga_without_dgps_fields() {
         let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123519,4807.038,N,01131.000,E,2,08,0.9,545.4,M,46.9,M,5.0,0120"));
+        let _ = gnss.feed_nmea(&with_checksum("GPGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,,M,,"));
+        assert_eq!((gnss.dgps_age(), gnss.dgps_station()), (None, None));
+        assert_eq!(gnss.geoid_separation(), None);
+        assert_eq!(gnss.altitude_ellipsoid(), None);
+        assert_eq!(gnss.gga_hdop(), Some(0.9));
+    }
 
-        // Test getting all default accuracies
-        let accuracies = gnss.get_all_system_accuracies();
-        assert_eq!(accuracies.len(), 4);
-        assert_eq!(accuracies.get("GPS"), Some(&2.0));
-        assert_eq!(accuracies.get("GLONASS"), Some(&4.0));
-        assert_eq!(accuracies.get("GALILEO"), Some(&3.0));
-        assert_eq!(accuracies.get("BEIDOU"), Some(&3.0));
+    #[test]
+    fn test_accessors_read_parsed_data() {
+        let mut gnss = GnssData::new();
+        let _ = gnss.feed_nmea(&with_checksum("GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39"));
+        let _ = gnss.feed_nmea(&with_checksum("GNGGA,123519,4807.038,N,01131.000,E,1,08,1.1,545.4,M,46.9,M,,"));
+        assert_eq!((gnss.time(), gnss.fix_quality(), gnss.num_satellites()), (Some("123519"), Some(1), Some(8)));
+        assert_eq!((gnss.altitude(), gnss.geoid_separation()), (Some(545.4), Some(46.9)));
+        // Without a combined GSA the solution HDOP falls back to GGA
+        assert_eq!((gnss.hdop(), gnss.gga_hdop(), gnss.pdop()), (Some(1.1), Some(1.1), None));
+        let _ = gnss.feed_nmea(&with_checksum("GNGSA,A,3,01,02,65,66,,,,,,,,,1.8,0.8,1.6"));
+        assert_eq!((gnss.pdop(), gnss.hdop(), gnss.vdop()), (Some(1.8), Some(0.8), Some(1.6)));
 
-        // Test after modifying some accuracies
-        gnss.set_system_fixed_accuracy("GPS", 1.8);
-        gnss.set_system_fixed_accuracy("GALILEO", 2.5);
+        let gps = gnss.system("GPS").unwrap();
+        assert_eq!(gps.satellites().len(), 4);
+        assert_eq!(gps.satellites_used(), &[1, 2]);
+        assert!((gps.latitude().unwrap() - 48.1173).abs() < 1e-9);
+        assert!(gnss.system("GLONASS").unwrap().satellites().is_empty());
+        assert!(gnss.system("UNKNOWN").is_none());
+        let names: Vec<&str> = gnss.systems().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "NAVIC"]);
+        assert!(gnss.satellites().all(|(name, _)| name == "GPS"));
 
-        let updated_accuracies = gnss.get_all_system_accuracies();
-        assert_eq!(updated_accuracies.get("GPS"), Some(&1.8));
-        assert_eq!(updated_accuracies.get("GLONASS"), Some(&4.0));
-        assert_eq!(updated_accuracies.get("GALILEO"), Some(&2.5));
-        assert_eq!(updated_accuracies.get("BEIDOU"), Some(&3.0));
+        gnss.calculate_fused_position();
+        assert_eq!(gnss.fused_position().unwrap().contributing_systems, vec!["GPS"]);
     }
 }
@@ -1,2 +1,4 @@
 pub mod gnss_multignss_parser;
-
+pub mod nmea_encoder;
+#[cfg(feature = "geoid")]
+pub mod geoid;
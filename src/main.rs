@@ -20,6 +20,8 @@ fn main() {
     let port_name = "COM12";
     let baud_rate = 9600;
     let mut gnss = GnssData::new();
+    // Reads can split a sentence across buffers; drop the partial pieces
+    gnss.set_checksum_validation(true);
 
     // Attempt to open the serial port with specified settings.
     let serial_result = serialport::new(port_name, baud_rate)
@@ -43,59 +45,55 @@ fn main() {
                 if bytes_read == 0 {
                     continue;
                 }
-                let data = String::from_utf8_lossy(&buffer[..bytes_read]);
                 io::stdout().flush().unwrap();
-                for line in data.lines() {
-                    // Process only lines that start with '$' (NMEA sentences).
-                    if line.starts_with('$') {
-                        gnss.feed_nmea(line);
+                // Feed complete NMEA sentences; one cut off by the read is completed by the next read.
+                if gnss.feed_bytes(&buffer[..bytes_read]) > 0 {
+                    // Calculate fused position after processing NMEA data.
+                    //gnss.calculate_fused_position();
+                    gnss.calculate_advanced_fused_position();
 
-                        // Calculate fused position after processing NMEA data.
-                        //gnss.calculate_fused_position();
-                        gnss.calculate_advanced_fused_position();
-
-                        // Print individual system data.
-                        for (system, sys_data) in &gnss.systems {
-                            let sat_count = sys_data.satellites_info.len();
-                            println!("System: {} | Satellites: {}", system, sat_count);
-                            if let (Some(lat), Some(lon)) = (sys_data.latitude, sys_data.longitude) {
-                                print!("System: {} | Lat: {:.6}, Lon: {:.6}", system, lat, lon);
-                                if let Some(alt) = sys_data.altitude {
-                                    print!(" | Alt: {:.1}m", alt);
-                                }
-                                println!();
-                            } else {
-                                println!("System: {} | Coordinates not available", system);
+                    // Print individual system data in canonical order.
+                    for (system, sys_data) in gnss.systems() {
+                        let sat_count = sys_data.satellites().len();
+                        println!("System: {} | Satellites: {}", system, sat_count);
+                        if let (Some(lat), Some(lon)) = (sys_data.latitude(), sys_data.longitude()) {
+                            print!("System: {} | Lat: {:.6}, Lon: {:.6}", system, lat, lon);
+                            if let Some(alt) = sys_data.altitude() {
+                                print!(" | Alt: {:.1}m", alt);
                             }
-                            if let Some(hdop) = sys_data.hdop {
-                                print!("System: {} | HDOP: {:.2}", system, hdop);
-                                if let Some(vdop) = sys_data.vdop {
-                                    print!(" | VDOP: {:.2}", vdop);
-                                }
-                                if let Some(pdop) = sys_data.pdop {
-                                    print!(" | PDOP: {:.2}", pdop);
-                                }
-                                let acc = sys_data.accuracy;
-                                print!(" | Sys. Acc: {:.2}m", acc);
-                                println!();
+                            println!();
+                        } else {
+                            println!("System: {} | Coordinates not available", system);
+                        }
+                        if let Some(hdop) = sys_data.hdop() {
+                            print!("System: {} | HDOP: {:.2}", system, hdop);
+                            if let Some(vdop) = sys_data.vdop() {
+                                print!(" | VDOP: {:.2}", vdop);
                             }
+                            if let Some(pdop) = sys_data.pdop() {
+                                print!(" | PDOP: {:.2}", pdop);
+                            }
+                            let acc = sys_data.accuracy();
+                            print!(" | Sys. Acc: {:.2}m", acc);
+                            println!();
                         }
+                    }
 
-                        // Print comprehensive fused position data.
-                        if let Some(fused) = &gnss.fused_position {
-                            println!("┌─ FUSED POSITION DATA ─────────────────────────────────────────┐");
-                            println!("│ Latitude:         {:.7}°", fused.latitude);
-                            println!("│ Longitude:        {:.7}°", fused.longitude);
-                            println!("│ Altitude:         {:.2} m", fused.altitude);
-                            println!("│ Horizontal Acc:   {:.2} m", fused.estimated_accuracy);
-                            println!("│ Altitude Acc:     {:.2} m", fused.altitude_accuracy);
-                            println!("│ Contributing:     {:?}", fused.contributing_systems);
-                            println!("└───────────────────────────────────────────────────────────────┘");
-                        } else {
-                            println!("FUSED | Position not available");
-                        }
-                        println!("---");
+                    // Print comprehensive fused position data.
+                    if let Some(fused) = gnss.fused_position() {
+                        println!("┌─ FUSED POSITION DATA ─────────────────────────────────────────┐");
+                        println!("│ Latitude:         {:.7}°", fused.latitude);
+                        println!("│ Longitude:        {:.7}°", fused.longitude);
+                        println!("│ Altitude:         {:.2} m", fused.altitude_msl);
+                        println!("│ Horizontal Acc:   {:.2} m", fused.estimated_accuracy);
+                        println!("│ Epoch Acc:        {:.2} m", fused.instantaneous_accuracy);
+                        println!("│ Altitude Acc:     {:.2} m", fused.altitude_accuracy);
+                        println!("│ Contributing:     {:?}", fused.contributing_systems);
+                        println!("└───────────────────────────────────────────────────────────────┘");
+                    } else {
+                        println!("FUSED | Position not available");
                     }
+                    println!("---");
                 }
             }
             // Handle serial port timeout errors gracefully.
@@ -107,4 +105,13 @@ fn main() {
             }
         }
     }
+
+    // Print the acquisition summary on exit.
+    let stats = gnss.get_stats();
+    match (stats.time_to_first_fix(), stats.start_type()) {
+        (Some(ttff), Some(start_type)) => {
+            println!("Time to first fix: {:.1} s ({:?} start)", ttff.as_secs_f64(), start_type)
+        }
+        _ => println!("Time to first fix: no fix acquired"),
+    }
 }
@@ -0,0 +1,353 @@
+//! NMEA Sentence Encoder
+//!
+//! This module formats GGA, RMC, GSA and GSV sentences, the inverse of the parser in
+//! [`gnss_multignss_parser`](crate::gnss_multignss_parser). It is meant for simulators, test
+//! fixtures and forwarding positions to NMEA consumers. Every sentence is framed with `$`, its
+//! checksum and no line terminator.
+//!
+//! # Precision
+//!
+//! Feeding an encoded sentence back through `GnssData::feed_nmea` recovers:
+//! - Times exactly, to the millisecond (`hhmmss.sss`)
+//! - Latitudes and longitudes to within `COORDINATE_TOLERANCE_DEG`, as minutes with 5 decimals
+//! - Altitudes, geoid separations, DOPs, speeds, tracks and DGPS ages to within
+//!   `DECIMAL_TOLERANCE`, as 2 decimals
+//! - Dates exactly, with the two-digit RMC year read back as 1980-2079
+//! - Satellite PRNs, elevations, azimuths and SNRs exactly
+//!
+//! Coordinates are rounded as a whole, so a value just below a whole degree is written as the
+//! next degree with 0 minutes rather than with 60 minutes. Missing and non-finite values are
+//! written as empty fields.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nema_parser::gnss_multignss_parser::{GnssData, UtcTime};
+//! use nema_parser::nmea_encoder::{encode_gga, FixReport};
+//! let fix = FixReport {
+//!     fix_quality: 1,
+//!     num_satellites: 8,
+//!     hdop: Some(0.9),
+//!     altitude: Some(545.4),
+//!     geoid_separation: Some(46.9),
+//!     ..FixReport::new(UtcTime { hour: 12, minute: 35, second: 19, millis: 0 }, 48.1173, 11.5166667)
+//! };
+//! let sentence = encode_gga("GN", &fix);
+//! assert_eq!(sentence, "$GNGGA,123519.000,4807.03800,N,01131.00000,E,1,08,0.90,545.40,M,46.90,M,,*77");
+//! let mut gnss = GnssData::new();
+//! gnss.feed_nmea(&sentence).unwrap();
+//! assert_eq!(gnss.altitude(), Some(545.4));
+//! ```
+
+use crate::gnss_multignss_parser::{nmea_checksum, SatelliteInfo, UtcTime};
+
+/// Largest difference between an encoded coordinate and the one parsed back, in degrees.
+pub const COORDINATE_TOLERANCE_DEG: f64 = 1e-7;
+
+/// Largest difference between an encoded 2-decimal value and the one parsed back.
+pub const DECIMAL_TOLERANCE: f64 = 0.005 + 1e-9;
+
+/// Number of satellites in one GSV sentence.
+const SATELLITES_PER_GSV: usize = 4;
+
+/// Number of PRN slots in a GSA sentence.
+const GSA_PRN_SLOTS: usize = 12;
+
+/// A position fix to encode as GGA or RMC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixReport {
+    /// UTC time of the fix
+    pub time: UtcTime,
+    /// Latitude in decimal degrees, negative south
+    pub latitude: f64,
+    /// Longitude in decimal degrees, negative west
+    pub longitude: f64,
+    /// GGA fix quality indicator (0 = no fix, 1 = GPS, 2 = DGPS, 4 = RTK fixed, ...)
+    pub fix_quality: u8,
+    /// Number of satellites used for the fix
+    pub num_satellites: u8,
+    /// Horizontal Dilution of Precision
+    pub hdop: Option<f64>,
+    /// Altitude above mean sea level in meters
+    pub altitude: Option<f64>,
+    /// Geoid separation (ellipsoid height minus MSL height) in meters
+    pub geoid_separation: Option<f64>,
+    /// Age of the differential corrections in seconds
+    pub dgps_age: Option<f64>,
+    /// Reference station ID of the differential corrections (0-1023)
+    pub dgps_station: Option<u16>,
+    /// Speed over ground in knots, for RMC
+    pub speed_knots: Option<f64>,
+    /// Track angle in degrees, for RMC
+    pub track_angle: Option<f64>,
+    /// Date as (year, month, day), for RMC
+    pub date: Option<(u16, u8, u8)>,
+}
+
+impl FixReport {
+    /// Creates a report of an autonomous fix at a position, with every optional field empty.
+    ///
+    /// # Arguments
+    /// * `time` - UTC time of the fix
+    /// * `latitude` - Latitude in decimal degrees
+    /// * `longitude` - Longitude in decimal degrees
+    ///
+    /// # Returns
+    /// * `FixReport` - The report, with fix quality 1 and no satellites
+    pub fn new(time: UtcTime, latitude: f64, longitude: f64) -> Self {
+        Self {
+            time,
+            latitude,
+            longitude,
+            fix_quality: 1,
+            num_satellites: 0,
+            hdop: None,
+            altitude: None,
+            geoid_separation: None,
+            dgps_age: None,
+            dgps_station: None,
+            speed_knots: None,
+            track_angle: None,
+            date: None,
+        }
+    }
+}
+
+/// The satellites and DOPs of a solution to encode as GSA.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GsaReport {
+    /// Fix type (1 = no fix, 2 = 2D, 3 = 3D)
+    pub fix_type: u8,
+    /// PRNs of the satellites used; only the first 12 fit in a sentence
+    pub prns: Vec<u16>,
+    /// Position Dilution of Precision
+    pub pdop: Option<f64>,
+    /// Horizontal Dilution of Precision
+    pub hdop: Option<f64>,
+    /// Vertical Dilution of Precision
+    pub vdop: Option<f64>,
+    /// NMEA 4.10 system ID (1 = GPS, 2 = GLONASS, ...), appended after the DOPs when set
+    pub system_id: Option<u8>,
+}
+
+/// Frames a sentence body with `$` and its checksum.
+///
+/// # Arguments
+/// * `body` - The sentence between `$` and `*`, e.g. "GPGGA,..."
+///
+/// # Returns
+/// * `String` - The framed sentence
+///
+/// # Example
+/// ```
+/// use nema_parser::nmea_encoder::frame_sentence;
+/// assert_eq!(frame_sentence("GPGSV,1,1,00"), "$GPGSV,1,1,00*79");
+/// ```
+pub fn frame_sentence(body: &str) -> String {
+    format!("${}*{:02X}", body, nmea_checksum(body))
+}
+
+/// Encodes a fix as a GGA sentence.
+///
+/// # Arguments
+/// * `talker` - The talker ID, e.g. "GN" or "GP"
+/// * `fix` - The fix to encode; speed, track and date are not part of GGA
+///
+/// # Returns
+/// * `String` - The framed sentence
+pub fn encode_gga(talker: &str, fix: &FixReport) -> String {
+    frame_sentence(&format!(
+        "{}GGA,{},{},{},{},{:02},{},{},M,{},M,{},{}",
+        talker,
+        format_time(fix.time),
+        format_coordinate(fix.latitude, true),
+        format_coordinate(fix.longitude, false),
+        fix.fix_quality,
+        fix.num_satellites,
+        format_decimal(fix.hdop),
+        format_decimal(fix.altitude),
+        format_decimal(fix.geoid_separation),
+        format_decimal(fix.dgps_age),
+        fix.dgps_station.map(|station| format!("{:04}", station)).unwrap_or_default(),
+    ))
+}
+
+/// Encodes a fix as an RMC sentence.
+///
+/// The status is 'A' with a fix and 'V' for fix quality 0, and the mode indicator follows the
+/// fix quality. The magnetic variation is left empty.
+///
+/// # Arguments
+/// * `talker` - The talker ID, e.g. "GN" or "GP"
+/// * `fix` - The fix to encode; altitude, HDOP and DGPS fields are not part of RMC
+///
+/// # Returns
+/// * `String` - The framed sentence
+pub fn encode_rmc(talker: &str, fix: &FixReport) -> String {
+    let status = if fix.fix_quality == 0 { 'V' } else { 'A' };
+    let mode = match fix.fix_quality {
+        0 => 'N',
+        2 => 'D',
+        4 => 'R',
+        5 => 'F',
+        6 => 'E',
+        7 => 'M',
+        8 => 'S',
+        _ => 'A',
+    };
+    let date = fix.date
+        .map(|(year, month, day)| format!("{:02}{:02}{:02}", day, month, year % 100))
+        .unwrap_or_default();
+    frame_sentence(&format!(
+        "{}RMC,{},{},{},{},{},{},{},,,{}",
+        talker,
+        format_time(fix.time),
+        status,
+        format_coordinate(fix.latitude, true),
+        format_coordinate(fix.longitude, false),
+        format_decimal(fix.speed_knots),
+        format_decimal(fix.track_angle),
+        date,
+        mode,
+    ))
+}
+
+/// Encodes the satellites and DOPs of a solution as a GSA sentence.
+///
+/// The selection mode is always 'A' (automatic). PRNs beyond the twelfth are dropped.
+///
+/// # Arguments
+/// * `talker` - The talker ID, e.g. "GN" or "GP"
+/// * `report` - The satellites and DOPs to encode
+///
+/// # Returns
+/// * `String` - The framed sentence
+///
+/// # Example
+/// ```
+/// use nema_parser::nmea_encoder::{encode_gsa, GsaReport};
+/// let report = GsaReport { fix_type: 3, prns: vec![1, 2, 3, 4], pdop: Some(1.2), hdop: Some(0.9), vdop: Some(2.1), system_id: None };
+/// assert_eq!(encode_gsa("GP", &report), "$GPGSA,A,3,01,02,03,04,,,,,,,,,1.20,0.90,2.10*0F");
+/// ```
+pub fn encode_gsa(talker: &str, report: &GsaReport) -> String {
+    let slots: Vec<String> = (0..GSA_PRN_SLOTS)
+        .map(|slot| report.prns.get(slot).map(|prn| format!("{:02}", prn)).unwrap_or_default())
+        .collect();
+    let mut body = format!(
+        "{}GSA,A,{},{},{},{},{}",
+        talker,
+        report.fix_type,
+        slots.join(","),
+        format_decimal(report.pdop),
+        format_decimal(report.hdop),
+        format_decimal(report.vdop),
+    );
+    if let Some(system_id) = report.system_id {
+        body.push_str(&format!(",{:X}", system_id));
+    }
+    frame_sentence(&body)
+}
+
+/// Encodes satellites in view as a cycle of GSV sentences, four satellites per sentence.
+///
+/// The last sentence carries the remaining satellites without padding, and no satellites
+/// still give one sentence reporting zero in view. Per-signal SNRs are not encoded.
+///
+/// # Arguments
+/// * `talker` - The talker ID of the constellation, e.g. "GP" or "GL"
+/// * `satellites` - The satellites in view, in the order to report them
+///
+/// # Returns
+/// * `Vec<String>` - The framed sentences of the cycle, in order
+///
+/// # Example
+/// ```
+/// use nema_parser::gnss_multignss_parser::SatelliteInfo;
+/// use nema_parser::nmea_encoder::encode_gsv;
+/// let satellite = SatelliteInfo { prn: 3, elevation: Some(13), azimuth: Some(172), snr: Some(42), signals: Vec::new(), used: false };
+/// assert_eq!(encode_gsv("GP", &[satellite]), vec!["$GPGSV,1,1,01,03,13,172,42*4B"]);
+/// ```
+pub fn encode_gsv(talker: &str, satellites: &[SatelliteInfo]) -> Vec<String> {
+    let total = satellites.len().div_ceil(SATELLITES_PER_GSV).max(1);
+    (0..total)
+        .map(|index| {
+            let mut body = format!("{}GSV,{},{},{:02}", talker, total, index + 1, satellites.len());
+            let start = index * SATELLITES_PER_GSV;
+            for satellite in satellites.iter().skip(start).take(SATELLITES_PER_GSV) {
+                body.push_str(&format!(
+                    ",{:02},{},{},{}",
+                    satellite.prn,
+                    satellite.elevation.map(|elevation| format!("{:02}", elevation)).unwrap_or_default(),
+                    satellite.azimuth.map(|azimuth| format!("{:03}", azimuth)).unwrap_or_default(),
+                    satellite.snr.map(|snr| format!("{:02}", snr)).unwrap_or_default(),
+                ));
+            }
+            frame_sentence(&body)
+        })
+        .collect()
+}
+
+/// Formats a UTC time as `hhmmss.sss`.
+fn format_time(time: UtcTime) -> String {
+    format!("{:02}{:02}{:02}.{:03}", time.hour, time.minute, time.second, time.millis)
+}
+
+/// Formats a coordinate as `ddmm.mmmmm,N` (latitude) or `dddmm.mmmmm,E` (longitude).
+///
+/// The coordinate is rounded to whole 1e-5 minutes before splitting off the degrees, so the
+/// minutes never round up to 60.
+fn format_coordinate(value: f64, is_latitude: bool) -> String {
+    if !value.is_finite() {
+        return ",".to_string();
+    }
+    let hemisphere = match (is_latitude, value < 0.0) {
+        (true, false) => 'N',
+        (true, true) => 'S',
+        (false, false) => 'E',
+        (false, true) => 'W',
+    };
+    const UNITS_PER_MINUTE: u64 = 100_000;
+    let units = (value.abs() * 60.0 * UNITS_PER_MINUTE as f64).round() as u64;
+    let degrees = units / (60 * UNITS_PER_MINUTE);
+    let minutes = units % (60 * UNITS_PER_MINUTE);
+    let width = if is_latitude { 2 } else { 3 };
+    format!(
+        "{:0width$}{:02}.{:05},{}",
+        degrees,
+        minutes / UNITS_PER_MINUTE,
+        minutes % UNITS_PER_MINUTE,
+        hemisphere,
+        width = width
+    )
+}
+
+/// Formats a value with 2 decimals, or an empty field if it is missing or not finite.
+fn format_decimal(value: Option<f64>) -> String {
+    value.filter(|value| value.is_finite()).map(|value| format!("{:.2}", value)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coordinate_minutes_never_reach_sixty() {
+        assert_eq!(format_coordinate(48.999_999_999, true), "4900.00000,N");
+        assert_eq!(format_coordinate(-0.000_000_01, true), "0000.00000,S");
+        assert_eq!(format_coordinate(-179.999_999_999, false), "18000.00000,W");
+        assert_eq!(format_coordinate(11.5166667, false), "01131.00000,E");
+        assert_eq!(format_coordinate(f64::NAN, true), ",");
+    }
+
+    #[test]
+    fn test_gsv_cycle_layout() {
+        let satellites: Vec<SatelliteInfo> = (1..=5)
+            .map(|prn| SatelliteInfo { prn, elevation: None, azimuth: Some(prn * 10), snr: None, signals: Vec::new(), used: false })
+            .collect();
+        let cycle = encode_gsv("GP", &satellites);
+        assert_eq!(cycle.len(), 2);
+        assert!(cycle[0].starts_with("$GPGSV,2,1,05,01,,010,,02,,020,,03,,030,,04,,040,*"));
+        assert!(cycle[1].starts_with("$GPGSV,2,2,05,05,,050,*"));
+        assert_eq!(encode_gsv("GL", &[]), vec![frame_sentence("GLGSV,1,1,00")]);
+    }
+}
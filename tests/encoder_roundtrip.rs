@@ -0,0 +1,154 @@
+//! Property tests feeding sentences from `nmea_encoder` back through `GnssData`.
+//!
+//! Random but valid fixes, satellite sets and DOPs are encoded as GGA, RMC, GSA and GSV, and
+//! the parsed values must match the inputs within the precision documented by the encoder.
+
+use nema_parser::gnss_multignss_parser::{FixType, GnssData, SatelliteInfo, UtcDateTime, UtcTime};
+use nema_parser::nmea_encoder::{
+    encode_gga, encode_gsa, encode_gsv, encode_rmc, FixReport, GsaReport, COORDINATE_TOLERANCE_DEG, DECIMAL_TOLERANCE,
+};
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+
+/// Checks that a parsed value matches the encoded one within a tolerance.
+fn assert_close(field: &str, parsed: Option<f64>, encoded: Option<f64>, tolerance: f64) -> Result<(), TestCaseError> {
+    match (parsed, encoded) {
+        (Some(parsed), Some(encoded)) => {
+            prop_assert!((parsed - encoded).abs() <= tolerance, "{}: parsed {} from {}", field, parsed, encoded);
+        }
+        (parsed, encoded) => prop_assert_eq!(parsed.is_some(), encoded.is_some(), "{} presence", field),
+    }
+    Ok(())
+}
+
+fn utc_time() -> impl Strategy<Value = UtcTime> {
+    (0u8..24, 0u8..60, 0u8..60, 0u16..1000)
+        .prop_map(|(hour, minute, second, millis)| UtcTime { hour, minute, second, millis })
+}
+
+/// DOPs inside the default plausible range, which drops anything else
+fn dop() -> impl Strategy<Value = f64> {
+    0.5f64..30.0
+}
+
+fn fix_report() -> impl Strategy<Value = FixReport> {
+    (
+        (utc_time(), -90.0f64..=90.0, -180.0f64..=180.0, prop::sample::select(vec![1u8, 2, 4, 5]), 4u8..=24),
+        (dop(), -400.0f64..9000.0, -110.0f64..90.0, proptest::option::of((0.0f64..300.0, 0u16..=1023))),
+        (0.0f64..500.0, 0.0f64..360.0, (1980u16..2080, 1u8..=12, 1u8..=28)),
+    )
+        .prop_map(|(position, accuracy, motion)| {
+            let (time, latitude, longitude, fix_quality, num_satellites) = position;
+            let (hdop, altitude, geoid_separation, dgps) = accuracy;
+            let (speed_knots, track_angle, date) = motion;
+            FixReport {
+                fix_quality,
+                num_satellites,
+                hdop: Some(hdop),
+                altitude: Some(altitude),
+                geoid_separation: Some(geoid_separation),
+                dgps_age: dgps.map(|(age, _)| age),
+                dgps_station: dgps.map(|(_, station)| station),
+                speed_knots: Some(speed_knots),
+                track_angle: Some(track_angle),
+                date: Some(date),
+                ..FixReport::new(time, latitude, longitude)
+            }
+        })
+}
+
+fn gsa_report() -> impl Strategy<Value = GsaReport> {
+    (btree_set(1u16..=32, 0..=12), 2u8..=3, dop(), dop(), dop()).prop_map(|(prns, fix_type, pdop, hdop, vdop)| GsaReport {
+        fix_type,
+        prns: prns.into_iter().collect(),
+        pdop: Some(pdop),
+        hdop: Some(hdop),
+        vdop: Some(vdop),
+        system_id: None,
+    })
+}
+
+fn satellites() -> impl Strategy<Value = Vec<SatelliteInfo>> {
+    btree_set(1u16..=32, 1..=16).prop_flat_map(|prns| {
+        let count = prns.len();
+        vec((proptest::option::of(0u8..=90), proptest::option::of(0u16..360), proptest::option::of(0u8..=99)), count)
+            .prop_map(move |fields| {
+                prns.iter()
+                    .zip(fields)
+                    .map(|(&prn, (elevation, azimuth, snr))| SatelliteInfo { prn, elevation, azimuth, snr, signals: Vec::new(), used: false })
+                    .collect()
+            })
+    })
+}
+
+proptest! {
+    #[test]
+    fn gga_round_trip(fix in fix_report()) {
+        let mut gnss = GnssData::new();
+        prop_assert!(gnss.feed_nmea(&encode_gga("GN", &fix)).is_ok());
+        prop_assert_eq!(gnss.parsed_time(), Some(fix.time));
+        assert_close("latitude", gnss.latitude(), Some(fix.latitude), COORDINATE_TOLERANCE_DEG)?;
+        assert_close("longitude", gnss.longitude(), Some(fix.longitude), COORDINATE_TOLERANCE_DEG)?;
+        prop_assert_eq!(gnss.fix_quality(), Some(fix.fix_quality));
+        prop_assert_eq!(gnss.num_satellites(), Some(fix.num_satellites));
+        assert_close("hdop", gnss.gga_hdop(), fix.hdop, DECIMAL_TOLERANCE)?;
+        assert_close("altitude", gnss.altitude(), fix.altitude, DECIMAL_TOLERANCE)?;
+        assert_close("geoid separation", gnss.geoid_separation(), fix.geoid_separation, DECIMAL_TOLERANCE)?;
+        assert_close("dgps age", gnss.dgps_age(), fix.dgps_age, DECIMAL_TOLERANCE)?;
+        prop_assert_eq!(gnss.dgps_station(), fix.dgps_station);
+    }
+
+    #[test]
+    fn rmc_round_trip(fix in fix_report()) {
+        let mut gnss = GnssData::new();
+        prop_assert!(gnss.feed_nmea(&encode_rmc("GN", &fix)).is_ok());
+        prop_assert_eq!(gnss.rmc_status(), Some('A'));
+        assert_close("latitude", gnss.latitude(), Some(fix.latitude), COORDINATE_TOLERANCE_DEG)?;
+        assert_close("longitude", gnss.longitude(), Some(fix.longitude), COORDINATE_TOLERANCE_DEG)?;
+        assert_close("speed", gnss.speed_knots(), fix.speed_knots, DECIMAL_TOLERANCE)?;
+        assert_close("track", gnss.track_angle(), fix.track_angle, DECIMAL_TOLERANCE)?;
+        let (year, month, day) = fix.date.unwrap();
+        let UtcTime { hour, minute, second, millis } = fix.time;
+        prop_assert_eq!(gnss.utc_datetime(), Some(UtcDateTime { year, month, day, hour, minute, second, millis }));
+    }
+
+    #[test]
+    fn gsa_round_trip(report in gsa_report()) {
+        let mut gnss = GnssData::new();
+        prop_assert!(gnss.feed_nmea(&encode_gsa("GP", &report)).is_ok());
+        let gps = gnss.system("GPS").unwrap();
+        prop_assert_eq!(gps.satellites_used(), &report.prns[..]);
+        assert_close("pdop", gps.pdop(), report.pdop, DECIMAL_TOLERANCE)?;
+        assert_close("hdop", gps.hdop(), report.hdop, DECIMAL_TOLERANCE)?;
+        assert_close("vdop", gps.vdop(), report.vdop, DECIMAL_TOLERANCE)?;
+        prop_assert_eq!(gps.fix_type(), FixType::from_gsa(report.fix_type));
+    }
+
+    #[test]
+    fn gsa_with_system_id_round_trip(report in gsa_report()) {
+        let report = GsaReport { system_id: Some(1), ..report };
+        let mut gnss = GnssData::new();
+        prop_assert!(gnss.feed_nmea(&encode_gsa("GN", &report)).is_ok());
+        let gps = gnss.system("GPS").unwrap();
+        prop_assert_eq!(gps.satellites_used(), &report.prns[..]);
+        assert_close("pdop", gps.pdop(), report.pdop, DECIMAL_TOLERANCE)?;
+        assert_close("hdop", gps.hdop(), report.hdop, DECIMAL_TOLERANCE)?;
+        assert_close("vdop", gps.vdop(), report.vdop, DECIMAL_TOLERANCE)?;
+    }
+
+    #[test]
+    fn gsv_round_trip(satellites in satellites()) {
+        let mut gnss = GnssData::new();
+        for sentence in encode_gsv("GP", &satellites) {
+            prop_assert!(gnss.feed_nmea(&sentence).is_ok(), "rejected {}", sentence);
+        }
+        let info = gnss.system("GPS").unwrap().satellites();
+        prop_assert_eq!(info.len(), satellites.len());
+        for satellite in &satellites {
+            let parsed = info.get(&satellite.prn);
+            prop_assert!(parsed.is_some(), "PRN {} missing", satellite.prn);
+            let parsed = parsed.unwrap();
+            prop_assert_eq!((parsed.elevation, parsed.azimuth, parsed.snr), (satellite.elevation, satellite.azimuth, satellite.snr));
+        }
+    }
+}
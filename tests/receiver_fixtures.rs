@@ -0,0 +1,216 @@
+//! End-to-end tests driven by multi-minute receiver captures in `tests/fixtures/`.
+//!
+//! Each fixture is fed through `GnssData::feed_bytes`, the adapter for bytes read from a
+//! receiver, one line at a time so the fused position can be recomputed after every sentence.
+//! A list of invariants describing the capture is checked along the way. The captures are
+//! synthetic and only follow the output formats of the receivers they are named after; see
+//! `tests/fixtures/README.md` for their composition.
+
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use nema_parser::gnss_multignss_parser::GnssData;
+
+/// An invariant a fixture must satisfy.
+enum Check {
+    /// Every fused position computed while feeding lies inside the box
+    FusedWithin { latitude: RangeInclusive<f64>, longitude: RangeInclusive<f64> },
+    /// The first fused position is produced by this 1-based line
+    FirstFusedAt(usize),
+    /// Satellites tracked by a system at the end of the capture
+    Satellites { system: &'static str, range: RangeInclusive<usize> },
+    /// Sentences of a type dispatched to a handler
+    SentenceCount(&'static str, u64),
+    /// Sentences no handler accepted
+    Unhandled(u64),
+    /// Sentences accounted to a talker
+    TalkerSentences(&'static str, u64),
+    /// No sentence failed its checksum
+    ChecksumsValid,
+    /// GGA fix quality right after this 1-based line was fed
+    FixQualityAt { line: usize, quality: Option<u8> },
+}
+
+/// A capture and the invariants it must satisfy.
+struct Fixture {
+    file: &'static str,
+    lines: usize,
+    checks: Vec<Check>,
+}
+
+impl Fixture {
+    /// Feeds the capture through a fresh parser and asserts every invariant.
+    fn run(&self) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(self.file);
+        let capture = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let mut gnss = GnssData::new();
+        let mut first_fused = None;
+        let mut lines = 0;
+
+        for (index, line) in capture.split_inclusive(|byte| *byte == b'\n').enumerate() {
+            let line_number = index + 1;
+            lines = line_number;
+            assert_eq!(gnss.feed_bytes(line), 1, "{}:{}: sentence not fed", self.file, line_number);
+            gnss.calculate_fused_position();
+
+            if let Some(fused) = gnss.fused_position() {
+                first_fused.get_or_insert(line_number);
+                for check in &self.checks {
+                    if let Check::FusedWithin { latitude, longitude } = check {
+                        assert!(
+                            latitude.contains(&fused.latitude) && longitude.contains(&fused.longitude),
+                            "{}:{}: fused position {}, {} outside the bounding box",
+                            self.file, line_number, fused.latitude, fused.longitude
+                        );
+                    }
+                }
+            }
+            for check in &self.checks {
+                if let Check::FixQualityAt { line, quality } = check {
+                    if *line == line_number {
+                        assert_eq!(gnss.fix_quality(), *quality, "{}:{}: fix quality", self.file, line_number);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(lines, self.lines, "{}: line count", self.file);
+        let stats = gnss.get_stats();
+        for check in &self.checks {
+            match check {
+                Check::FirstFusedAt(line) => assert_eq!(first_fused, Some(*line), "{}: first fused position", self.file),
+                Check::Satellites { system, range } => {
+                    let tracked = gnss.system(system).unwrap().satellites().len();
+                    assert!(range.contains(&tracked), "{}: {} tracks {} satellites", self.file, system, tracked);
+                }
+                Check::SentenceCount(sentence_type, count) => {
+                    assert_eq!(stats.sentence_count(sentence_type), *count, "{}: {} sentences", self.file, sentence_type);
+                }
+                Check::Unhandled(count) => assert_eq!(stats.unhandled_sentences(), *count, "{}: unhandled sentences", self.file),
+                Check::TalkerSentences(source, count) => {
+                    let sentences = stats.talkers().iter().find(|talker| talker.source == *source).map(|talker| talker.sentences);
+                    assert_eq!(sentences, Some(*count), "{}: sentences from {}", self.file, source);
+                }
+                Check::ChecksumsValid => {
+                    for talker in stats.talkers() {
+                        assert_eq!(talker.checksum_failures, 0, "{}: checksum failures from {}", self.file, talker.source);
+                    }
+                }
+                Check::FusedWithin { .. } | Check::FixQualityAt { .. } => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn ublox_m8_multignss() {
+    Fixture {
+        file: "ublox_m8_multignss.nmea",
+        lines: 2100,
+        checks: vec![
+            Check::FusedWithin { latitude: 48.1172..=48.1177, longitude: 11.5166..=11.5172 },
+            // The no-fix GSAs name their system, so fusion waits for the first fix's GSA
+            Check::FirstFusedAt(144),
+            Check::FixQualityAt { line: 142, quality: Some(0) },
+            Check::FixQualityAt { line: 143, quality: Some(1) },
+            Check::FixQualityAt { line: 842, quality: Some(1) },
+            Check::FixQualityAt { line: 843, quality: Some(2) },
+            Check::Satellites { system: "GPS", range: 10..=11 },
+            Check::Satellites { system: "GLONASS", range: 7..=7 },
+            Check::Satellites { system: "GALILEO", range: 6..=6 },
+            Check::SentenceCount("GGA", 150),
+            Check::SentenceCount("RMC", 150),
+            Check::SentenceCount("VTG", 150),
+            Check::SentenceCount("GSA", 450),
+            Check::SentenceCount("GSV", 1050),
+            Check::SentenceCount("GLL", 150),
+            Check::Unhandled(0),
+            Check::TalkerSentences("GN", 1050),
+            Check::TalkerSentences("GP", 450),
+            Check::TalkerSentences("GL", 300),
+            Check::TalkerSentences("GA", 300),
+            Check::ChecksumsValid,
+        ],
+    }
+    .run();
+}
+
+#[test]
+fn sirf_gps_only() {
+    // GP-talker sentences are attributed to GPS alone
+    Fixture {
+        file: "sirf_gps_only.nmea",
+        lines: 900,
+        checks: vec![
+            Check::FusedWithin { latitude: -33.8690..=-33.8686, longitude: 151.2091..=151.2095 },
+            // The first valid GGA (line 121) precedes the GSA that replaces the no-fix fix type
+            Check::FirstFusedAt(122),
+            Check::FixQualityAt { line: 120, quality: Some(0) },
+            Check::FixQualityAt { line: 121, quality: Some(1) },
+            Check::Satellites { system: "GPS", range: 9..=9 },
+            Check::Satellites { system: "GLONASS", range: 0..=0 },
+            Check::SentenceCount("GSV", 450),
+            Check::SentenceCount("GGA", 150),
+            Check::SentenceCount("GSA", 150),
+            Check::SentenceCount("RMC", 150),
+            Check::Unhandled(0),
+            Check::TalkerSentences("GP", 900),
+            Check::ChecksumsValid,
+        ],
+    }
+    .run();
+}
+
+#[test]
+fn rtk_fixed() {
+    Fixture {
+        file: "rtk_fixed.nmea",
+        lines: 1350,
+        checks: vec![
+            Check::FusedWithin { latitude: 40.44615..=40.44621, longitude: -79.98230..=-79.98222 },
+            // Satellites first arrive after the opening epoch's GGA, so the next RMC fixes
+            Check::FirstFusedAt(10),
+            Check::FixQualityAt { line: 3, quality: Some(1) },
+            Check::FixQualityAt { line: 272, quality: Some(1) },
+            Check::FixQualityAt { line: 273, quality: Some(5) },
+            Check::FixQualityAt { line: 542, quality: Some(5) },
+            Check::FixQualityAt { line: 543, quality: Some(4) },
+            Check::FixQualityAt { line: 1350, quality: Some(4) },
+            Check::Satellites { system: "GPS", range: 8..=8 },
+            Check::Satellites { system: "GLONASS", range: 5..=5 },
+            Check::SentenceCount("GGA", 150),
+            Check::SentenceCount("GSA", 300),
+            Check::SentenceCount("GSV", 600),
+            Check::Unhandled(0),
+            Check::TalkerSentences("GN", 750),
+            Check::ChecksumsValid,
+        ],
+    }
+    .run();
+}
+
+#[test]
+fn marine_mux_ais() {
+    Fixture {
+        file: "marine_mux_ais.nmea",
+        lines: 1932,
+        checks: vec![
+            Check::FusedWithin { latitude: 50.801..=50.805, longitude: -1.107..=-1.099 },
+            Check::FixQualityAt { line: 3, quality: Some(1) },
+            Check::Satellites { system: "GPS", range: 7..=7 },
+            Check::Satellites { system: "GLONASS", range: 5..=5 },
+            Check::SentenceCount("RMC", 150),
+            Check::SentenceCount("GGA", 150),
+            Check::SentenceCount("GSA", 300),
+            Check::SentenceCount("GSV", 600),
+            // AIS, depth, wind and heading sentences pass through unhandled
+            Check::Unhandled(732),
+            Check::TalkerSentences("AI", 282),
+            Check::TalkerSentences("SD", 150),
+            Check::TalkerSentences("II", 150),
+            Check::TalkerSentences("HC", 150),
+            Check::ChecksumsValid,
+        ],
+    }
+    .run();
+}
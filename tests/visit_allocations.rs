@@ -0,0 +1,82 @@
+//! Checks that the borrow-based visitors run without touching the heap.
+//!
+//! The test binary installs a global allocator that counts allocations made on the current
+//! thread while counting is switched on, so other test threads do not disturb the count.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use nema_parser::gnss_multignss_parser::GnssData;
+
+/// Forwards to the system allocator, counting allocations of the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    /// Whether allocations of this thread are being counted
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    /// Allocations counted on this thread
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `f` and returns the number of allocations it made.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.with(|count| count.set(0));
+    COUNTING.with(|counting| counting.set(true));
+    f();
+    COUNTING.with(|counting| counting.set(false));
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn visiting_satellites_does_not_allocate() {
+    let mut gnss = GnssData::new();
+    for sentence in [
+        "$GPGSV,1,1,04,01,40,083,41,02,17,308,43,03,13,172,42,04,09,020,39*78",
+        "$GLGSV,1,1,04,65,40,083,41,66,17,308,43,67,13,172,42,68,09,020,39*6C",
+        "$GAGSV,1,1,02,05,40,083,41,09,17,308,43*66",
+    ] {
+        gnss.feed_nmea(sentence).unwrap();
+    }
+
+    let mut visited = [(0u16, 0u8); 16];
+    let mut satellites = 0;
+    let mut systems = 0;
+    let allocations = count_allocations(|| {
+        gnss.visit_satellites(|_, sat| {
+            visited[satellites] = (sat.prn, sat.snr.unwrap_or(0));
+            satellites += 1;
+        });
+        gnss.visit_systems(|_, data| systems += usize::from(!data.satellites().is_empty()));
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(satellites, 10);
+    assert_eq!(systems, 3);
+    // Canonical system order, ascending PRN within a system
+    assert_eq!(visited[..satellites].iter().map(|(prn, _)| *prn).collect::<Vec<_>>(), vec![1, 2, 3, 4, 65, 66, 67, 68, 5, 9]);
+
+    // The counter does see allocations
+    assert_eq!(count_allocations(|| drop(std::hint::black_box(Vec::<u8>::with_capacity(8)))), 1);
+}